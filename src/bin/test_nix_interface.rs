@@ -5,7 +5,7 @@ use color_eyre::eyre::Result;
 
 fn main() -> Result<()> {
     // Setup logging
-    ng::logging::setup_logging(2)?;
+    ng::logging::setup_logging(2, false, None, false, false, None, false)?;
     
     println!("Testing NixInterface...");
     
@@ -0,0 +1,99 @@
+//! `ng check`: wraps `nix flake check`, attributing failures back to their `checks.<system>`
+//! attribute and fetching failed derivation logs, instead of leaving users to read raw nix
+//! `--keep-going` stderr.
+
+use color_eyre::eyre::{bail, eyre, WrapErr};
+use tracing::warn;
+
+use crate::commands::Command;
+use crate::error_handler;
+use crate::installable::Installable;
+use crate::interface::CheckArgs;
+use crate::nix_interface::{parse_failed_derivations, NixInterface};
+use crate::Result;
+
+impl CheckArgs {
+    pub fn run(&self, verbose_count: u8) -> Result<()> {
+        let Installable::Flake { reference, .. } = &self.installable else {
+            bail!("ng check only supports flake installables (e.g. `.` or `github:user/repo`)");
+        };
+
+        let system = detect_current_system(verbose_count)?;
+        let check_names = list_check_names(reference, &system, verbose_count);
+
+        let pb = crate::progress::start_spinner(&format!("Running `nix flake check` for {reference}..."));
+        let output = Command::new("nix")
+            .args(["flake", "check", "--keep-going"])
+            .arg(reference)
+            .add_verbosity_flags(verbose_count)
+            .run_capture_output()
+            .wrap_err("Failed to run `nix flake check`")?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        let failures = parse_failed_derivations(&stderr);
+
+        if output.status.success() {
+            crate::progress::finish_spinner_success(&pb, "All checks passed.");
+        } else {
+            crate::progress::finish_spinner_fail(&pb);
+        }
+
+        if !check_names.is_empty() {
+            let results: Vec<(String, bool)> = check_names
+                .iter()
+                .map(|name| {
+                    let failed = failures.iter().any(|f| f.drv_path.contains(name.as_str()));
+                    (name.clone(), !failed)
+                })
+                .collect();
+            if let Err(e) = crate::tables::display_check_results(results) {
+                warn!("Failed to render check results table: {}", e);
+            }
+        } else if !failures.is_empty() {
+            if let Err(e) = crate::tables::display_failed_derivations(failures.clone()) {
+                warn!("Failed to render failed derivations table: {}", e);
+            }
+        }
+
+        for failure in &failures {
+            match error_handler::fetch_and_format_nix_log(&failure.drv_path, verbose_count) {
+                Ok(log) => println!("\n{log}"),
+                Err(e) => warn!("Failed to fetch build log for {}: {}", failure.drv_path, e),
+            }
+        }
+
+        if !output.status.success() {
+            bail!(
+                "`nix flake check` failed with {} failed check(s)",
+                failures.len().max(1)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn detect_current_system(verbose_count: u8) -> Result<String> {
+    Command::new("nix")
+        .args(["eval", "--impure", "--raw", "--expr", "builtins.currentSystem"])
+        .add_verbosity_flags(verbose_count)
+        .run_capture()?
+        .map(|s| s.trim().to_string())
+        .ok_or_else(|| eyre!("Failed to detect the current Nix system"))
+}
+
+/// Best-effort list of `checks.<system>` attribute names, used to attribute failures and to show
+/// passing checks in the summary table. Returns an empty list (rather than an error) if the
+/// flake has no `checks` output for this system, since that's the common case, not a failure.
+fn list_check_names(reference: &str, system: &str, verbose_count: u8) -> Vec<String> {
+    let installable = Installable::Flake {
+        reference: reference.to_string(),
+        attribute: vec!["checks".to_string(), system.to_string()],
+    };
+
+    NixInterface::new(verbose_count, false)
+        .eval_json(&installable)
+        .ok()
+        .and_then(|value| value.as_object().map(|obj| obj.keys().cloned().collect()))
+        .unwrap_or_default()
+}
@@ -34,7 +34,8 @@ type GenerationsTagged = BTreeMap<Generation, ToBeRemoved>;
 type ProfilesTagged = HashMap<PathBuf, GenerationsTagged>;
 
 impl interface::CleanMode {
-    pub fn run(&self, _verbose_count: u8) -> Result<()> {
+    pub fn run(&self, verbose_count: u8) -> Result<()> {
+        let config = crate::config::NgConfig::load();
         let mut profiles = Vec::new();
         let mut gcroots_tagged: HashMap<PathBuf, ToBeRemoved> = HashMap::new();
         let now = SystemTime::now();
@@ -43,6 +44,9 @@ impl interface::CleanMode {
         // What profiles to clean depending on the call mode
         let uid = nix::unistd::Uid::effective();
         let args = match self {
+            interface::CleanMode::Schedule(args) => {
+                return crate::clean_schedule::run(args, verbose_count);
+            }
             interface::CleanMode::Profile(args) => {
                 profiles.push(args.profile.clone());
                 is_profile_clean = true;
@@ -93,7 +97,7 @@ impl interface::CleanMode {
         for p in profiles {
             profiles_tagged.insert(
                 p.clone(),
-                cleanable_generations(&p, args.keep, args.keep_since)?,
+                cleanable_generations(&p, args.keep, args.keep_since, &config.clean)?,
             );
         }
 
@@ -149,11 +153,15 @@ impl interface::CleanMode {
                         Err(err) => {
                             warn!(?err, ?now, "Failed to compare time!");
                         }
-                        Ok(val) if val <= args.keep_since.into() => {
+                        Ok(val) if val <= Into::<std::time::Duration>::into(args.keep_since) => {
                             gcroots_tagged.insert(dst, false);
                         }
                         Ok(_) => {
-                            gcroots_tagged.insert(dst, true);
+                            let protected = config.clean.is_protected(&dst);
+                            if protected {
+                                debug!(?dst, "Gcroot matches a configured protect pattern, keeping");
+                            }
+                            gcroots_tagged.insert(dst, !protected);
                         }
                     }
                 } else {
@@ -162,6 +170,10 @@ impl interface::CleanMode {
             }
         }
 
+        if args.interactive {
+            interactive_select(&mut profiles_tagged, &mut gcroots_tagged, verbose_count, args.dry)?;
+        }
+
         // Present the user the information about the paths to clean
         use owo_colors::OwoColorize;
         println!();
@@ -327,6 +339,77 @@ impl interface::CleanMode {
     }
 }
 
+impl interface::StoreMode {
+    pub fn run(&self, verbose_count: u8) -> Result<()> {
+        match self {
+            interface::StoreMode::Optimise(args) => run_store_optimise(args, verbose_count),
+            interface::StoreMode::Verify(args) => args.run(verbose_count),
+        }
+    }
+}
+
+fn run_store_optimise(args: &interface::StoreOptimiseArgs, verbose_count: u8) -> Result<()> {
+    let is_root = nix::unistd::Uid::effective().is_root();
+
+    let spinner = crate::progress::start_spinner("[⚡ Optimise] Optimizing Nix store");
+
+    let output = if is_root {
+        Command::new("nix")
+            .args(["store", "optimise"])
+            .add_verbosity_flags(verbose_count)
+            .dry(args.dry)
+            .run_capture_output()?
+    } else {
+        Command::new("sudo")
+            .args(["nix", "store", "optimise"])
+            .add_verbosity_flags(verbose_count)
+            .dry(args.dry)
+            .run_capture_output()?
+    };
+
+    if !output.status.success() {
+        crate::progress::finish_spinner_fail(&spinner);
+        bail!("nix store optimise failed:\n{}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match parse_optimise_savings(&stderr) {
+        Some((files, bytes)) => crate::progress::finish_spinner_success(
+            &spinner,
+            &format!("[✅ Optimise] Hard-linked {files} files, saving {}", crate::tables::format_bytes(bytes)),
+        ),
+        None => crate::progress::finish_spinner_success(&spinner, "[✅ Optimise] Store optimisation completed successfully"),
+    }
+
+    Ok(())
+}
+
+/// Best-effort parse of `nix store optimise`'s "N store paths ... M MiB ... hard-linking K
+/// files" summary line. Returns `(files_linked, bytes_saved)`, or `None` if nix's wording has
+/// changed and the line couldn't be matched — the caller falls back to a generic success message.
+fn parse_optimise_savings(output: &str) -> Option<(u64, u64)> {
+    let re = Regex::new(
+        r"([\d.]+)\s*(B|KiB|MiB|GiB|TiB)\s*(?:/\s*[\d.]+\s*\w+\s*)?freed by hard-linking (\d+) files?",
+    )
+    .unwrap();
+    let caps = re.captures(output)?;
+
+    let size: f64 = caps.get(1)?.as_str().parse().ok()?;
+    let unit = caps.get(2)?.as_str();
+    let multiplier: f64 = match unit {
+        "B" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    let bytes = (size * multiplier) as u64;
+    let files: u64 = caps.get(3)?.as_str().parse().ok()?;
+
+    Some((files, bytes))
+}
+
 #[instrument(ret, level = "debug")]
 fn profiles_in_dir<P: AsRef<Path> + fmt::Debug>(dir: P) -> Vec<PathBuf> {
     let mut res = Vec::new();
@@ -371,6 +454,7 @@ fn cleanable_generations(
     profile: &Path,
     keep: u32,
     keep_since: humantime::Duration,
+    protect: &crate::config::CleanConfig,
 ) -> Result<GenerationsTagged> {
     let name = profile
         .file_name()
@@ -417,7 +501,7 @@ fn cleanable_generations(
             Err(err) => {
                 warn!(?err, ?now, ?gen, "Failed to compare time!");
             }
-            Ok(val) if val <= keep_since.into() => {
+            Ok(val) if val <= Into::<std::time::Duration>::into(keep_since) => {
                 *tbr = false;
             }
             Ok(_) => {}
@@ -428,10 +512,104 @@ fn cleanable_generations(
         *tbr = false;
     }
 
+    for (gen, tbr) in result.iter_mut() {
+        if *tbr && protect.is_protected(&gen.path) {
+            debug!(?gen, "Generation matches a configured protect pattern, keeping");
+            *tbr = false;
+        }
+    }
+
     debug!("{:#?}", result);
     Ok(result)
 }
 
+/// Presents a checklist of every item currently marked for deletion (old generations, dangling
+/// gcroots), with its closure size, and narrows `profiles_tagged`/`gcroots_tagged` down to just
+/// what the user leaves checked. Items that the `--keep`/`--keep-since` logic already decided to
+/// keep aren't offered — this only lets the user pull items *out* of the deletion set, not add
+/// items back in that policy already protected.
+fn interactive_select(
+    profiles_tagged: &mut ProfilesTagged,
+    gcroots_tagged: &mut HashMap<PathBuf, ToBeRemoved>,
+    verbose_count: u8,
+    dry_run: bool,
+) -> Result<()> {
+    let nix_interface = crate::nix_interface::NixInterface::new(verbose_count, dry_run);
+
+    let mut labels = Vec::new();
+    let mut gcroot_candidates = Vec::new();
+    for (path, tbr) in gcroots_tagged.iter() {
+        if !*tbr {
+            continue;
+        }
+        let size = nix_interface.closure_size_cached(path).unwrap_or(0);
+        labels.push(format!("[gcroot] {} ({})", path.display(), crate::tables::format_bytes(size)));
+        gcroot_candidates.push(path.clone());
+    }
+
+    let mut generation_candidates = Vec::new();
+    for (profile, generations_tagged) in profiles_tagged.iter() {
+        for (gen, tbr) in generations_tagged.iter() {
+            if !*tbr {
+                continue;
+            }
+            let size = nix_interface.closure_size_cached(&gen.path).unwrap_or(0);
+            labels.push(format!(
+                "[generation {}] {} ({})",
+                gen.number,
+                profile.to_string_lossy(),
+                crate::tables::format_bytes(size)
+            ));
+            generation_candidates.push((profile.clone(), gen.number));
+        }
+    }
+
+    if labels.is_empty() {
+        info!("Nothing eligible for cleanup");
+        return Ok(());
+    }
+
+    let defaults = vec![true; labels.len()];
+    let selected: std::collections::HashSet<usize> = dialoguer::MultiSelect::new()
+        .with_prompt("Select items to delete (space to toggle, enter to confirm)")
+        .items(&labels)
+        .defaults(&defaults)
+        .interact()?
+        .into_iter()
+        .collect();
+
+    for tbr in gcroots_tagged.values_mut() {
+        *tbr = false;
+    }
+    for generations_tagged in profiles_tagged.values_mut() {
+        for tbr in generations_tagged.values_mut() {
+            *tbr = false;
+        }
+    }
+
+    for (idx, path) in gcroot_candidates.iter().enumerate() {
+        if selected.contains(&idx) {
+            *gcroots_tagged.get_mut(path).context("Looking up selected gcroot")? = true;
+        }
+    }
+    for (idx, (profile, number)) in generation_candidates.iter().enumerate() {
+        let offset = gcroot_candidates.len() + idx;
+        if !selected.contains(&offset) {
+            continue;
+        }
+        let generations_tagged = profiles_tagged
+            .get_mut(profile)
+            .context("Looking up selected generation's profile")?;
+        for (gen, tbr) in generations_tagged.iter_mut() {
+            if gen.number == *number {
+                *tbr = true;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn remove_path_nofail(path: &Path) {
     info!("Removing {}", path.to_string_lossy());
     if let Err(err) = std::fs::remove_file(path) {
@@ -0,0 +1,167 @@
+//! Generates (and optionally installs) a systemd timer or launchd plist that runs `ng clean` on
+//! a schedule, so recurring GC configuration doesn't require hand-writing units.
+
+use std::path::PathBuf;
+
+use color_eyre::eyre::WrapErr;
+use tracing::info;
+
+use crate::interface::{CleanScheduleArgs, CleanScheduleMode};
+use crate::Result;
+
+const SYSTEMD_SERVICE_PATH: &str = "/etc/systemd/system/ng-clean.service";
+const SYSTEMD_TIMER_PATH: &str = "/etc/systemd/system/ng-clean.timer";
+const LAUNCHD_LABEL: &str = "org.nixos.ng-clean";
+const LAUNCHD_PLIST_PATH: &str = "/Library/LaunchDaemons/org.nixos.ng-clean.plist";
+
+pub fn run(args: &CleanScheduleArgs, _verbose_count: u8) -> Result<()> {
+    let frequency = if args.daily {
+        Frequency::Daily
+    } else if args.monthly {
+        Frequency::Monthly
+    } else {
+        Frequency::Weekly
+    };
+
+    let ng_bin = std::env::current_exe().wrap_err("Failed to determine path to the `ng` binary")?;
+
+    if cfg!(target_os = "macos") {
+        let plist = launchd_plist(&ng_bin, args.mode, args.keep, frequency);
+
+        if !args.install {
+            println!("{plist}");
+            info!(
+                "Not installed. Re-run with --install to write this to {} and load it.",
+                LAUNCHD_PLIST_PATH
+            );
+            return Ok(());
+        }
+
+        if !nix::unistd::Uid::effective().is_root() {
+            crate::self_elevate();
+        }
+
+        std::fs::write(LAUNCHD_PLIST_PATH, plist)
+            .wrap_err_with(|| format!("Failed to write {LAUNCHD_PLIST_PATH}"))?;
+        crate::commands::Command::new("launchctl")
+            .args(["load", "-w", LAUNCHD_PLIST_PATH])
+            .run()?;
+        info!("Installed and loaded {} ({})", LAUNCHD_LABEL, LAUNCHD_PLIST_PATH);
+    } else {
+        let (service, timer) = systemd_units(&ng_bin, args.mode, args.keep, frequency);
+
+        if !args.install {
+            println!("# {SYSTEMD_SERVICE_PATH}\n{service}");
+            println!("# {SYSTEMD_TIMER_PATH}\n{timer}");
+            info!(
+                "Not installed. Re-run with --install to write these units and enable the timer."
+            );
+            return Ok(());
+        }
+
+        if !nix::unistd::Uid::effective().is_root() {
+            crate::self_elevate();
+        }
+
+        std::fs::write(SYSTEMD_SERVICE_PATH, service)
+            .wrap_err_with(|| format!("Failed to write {SYSTEMD_SERVICE_PATH}"))?;
+        std::fs::write(SYSTEMD_TIMER_PATH, timer)
+            .wrap_err_with(|| format!("Failed to write {SYSTEMD_TIMER_PATH}"))?;
+
+        crate::commands::Command::new("systemctl")
+            .arg("daemon-reload")
+            .run()?;
+        crate::commands::Command::new("systemctl")
+            .args(["enable", "--now", "ng-clean.timer"])
+            .run()?;
+        info!("Installed and enabled ng-clean.timer");
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl Frequency {
+    fn on_calendar(self) -> &'static str {
+        match self {
+            Frequency::Daily => "daily",
+            Frequency::Weekly => "weekly",
+            Frequency::Monthly => "monthly",
+        }
+    }
+}
+
+fn systemd_units(
+    ng_bin: &PathBuf,
+    mode: CleanScheduleMode,
+    keep: u32,
+    frequency: Frequency,
+) -> (String, String) {
+    let service = format!(
+        "[Unit]\n\
+Description=ng clean ({mode})\n\
+\n\
+[Service]\n\
+Type=oneshot\n\
+ExecStart={} clean {mode} --keep {keep}\n",
+        ng_bin.display()
+    );
+
+    let timer = format!(
+        "[Unit]\n\
+Description=Run ng clean on a schedule\n\
+\n\
+[Timer]\n\
+OnCalendar={}\n\
+Persistent=true\n\
+\n\
+[Install]\n\
+WantedBy=timers.target\n",
+        frequency.on_calendar()
+    );
+
+    (service, timer)
+}
+
+fn launchd_plist(ng_bin: &PathBuf, mode: CleanScheduleMode, keep: u32, frequency: Frequency) -> String {
+    let calendar_interval = match frequency {
+        Frequency::Daily => "<key>Hour</key><integer>3</integer><key>Minute</key><integer>0</integer>".to_string(),
+        Frequency::Weekly => "<key>Weekday</key><integer>0</integer><key>Hour</key><integer>3</integer><key>Minute</key><integer>0</integer>".to_string(),
+        Frequency::Monthly => "<key>Day</key><integer>1</integer><key>Hour</key><integer>3</integer><key>Minute</key><integer>0</integer>".to_string(),
+    };
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{LAUNCHD_LABEL}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{ng_bin}</string>
+        <string>clean</string>
+        <string>{mode}</string>
+        <string>--keep</string>
+        <string>{keep}</string>
+    </array>
+    <key>StartCalendarInterval</key>
+    <dict>
+        {calendar_interval}
+    </dict>
+    <key>StandardOutPath</key>
+    <string>/var/log/ng-clean.log</string>
+    <key>StandardErrorPath</key>
+    <string>/var/log/ng-clean.log</string>
+</dict>
+</plist>
+"#,
+        ng_bin = ng_bin.display(),
+    )
+}
@@ -24,8 +24,8 @@ pub(crate) mod test_support {
         static TEST_MODE_ENABLED: RefCell<bool> = RefCell::new(false);
         static RECORDED_COMMANDS: RefCell<Vec<String>> = RefCell::new(Vec::new());
         static MOCK_RUN_RESULTS_QUEUE: RefCell<Vec<Result<()>>> = RefCell::new(Vec::new());
-        static MOCK_CAPTURE_STDOUT: RefCell<Option<Result<Option<String>>>> = RefCell::new(None);
-        static MOCK_CAPTURE_ERROR: RefCell<Option<Result<Option<String>>>> = RefCell::new(None);
+        static MOCK_CAPTURE_STDOUT_QUEUE: RefCell<Vec<String>> = RefCell::new(Vec::new());
+        static MOCK_CAPTURE_ERROR_QUEUE: RefCell<Vec<Report>> = RefCell::new(Vec::new());
         // Corrected to use std::process::Output
         static MOCK_PROCESS_OUTPUT_QUEUE: RefCell<Vec<Result<StdProcessOutput, Report>>> = RefCell::new(Vec::new());
     }
@@ -34,8 +34,8 @@ pub(crate) mod test_support {
         TEST_MODE_ENABLED.with(|flag| *flag.borrow_mut() = true);
         RECORDED_COMMANDS.with(|cmds| cmds.borrow_mut().clear());
         MOCK_RUN_RESULTS_QUEUE.with(|q| q.borrow_mut().clear());
-        MOCK_CAPTURE_STDOUT.with(|mock| *mock.borrow_mut() = None);
-        MOCK_CAPTURE_ERROR.with(|mock| *mock.borrow_mut() = None);
+        MOCK_CAPTURE_STDOUT_QUEUE.with(|q| q.borrow_mut().clear());
+        MOCK_CAPTURE_ERROR_QUEUE.with(|q| q.borrow_mut().clear());
         MOCK_PROCESS_OUTPUT_QUEUE.with(|q| q.borrow_mut().clear()); // Updated to new queue
         eprintln!("[DEBUG TEST_SUPPORT] Test mode enabled and mocks cleared.");
     }
@@ -47,8 +47,8 @@ pub(crate) mod test_support {
         TEST_MODE_ENABLED.with(|flag| *flag.borrow_mut() = false);
         RECORDED_COMMANDS.with(|cmds| cmds.borrow_mut().clear());
         MOCK_RUN_RESULTS_QUEUE.with(|q| q.borrow_mut().clear());
-        MOCK_CAPTURE_STDOUT.with(|mock| *mock.borrow_mut() = None);
-        MOCK_CAPTURE_ERROR.with(|mock| *mock.borrow_mut() = None);
+        MOCK_CAPTURE_STDOUT_QUEUE.with(|q| q.borrow_mut().clear());
+        MOCK_CAPTURE_ERROR_QUEUE.with(|q| q.borrow_mut().clear());
         MOCK_PROCESS_OUTPUT_QUEUE.with(|q| q.borrow_mut().clear()); // Updated to new queue
         eprintln!("[DEBUG TEST_SUPPORT] Test mode explicitly disabled now.");
     }
@@ -106,26 +106,37 @@ pub(crate) mod test_support {
         })
     }
 
-    // For run_capture's stdout
+    // For run_capture's stdout. Pushes to the back of the queue (FIFO), so a test driving a
+    // path that issues several `run_capture()` calls in sequence (e.g. `nix --version` then
+    // `nix config show ...`) can queue up a distinct answer for each.
     pub fn set_mock_capture_stdout(stdout: String) {
-        MOCK_CAPTURE_STDOUT.with(|cell| *cell.borrow_mut() = Some(Ok(Some(stdout))));
+        MOCK_CAPTURE_STDOUT_QUEUE.with(|q| q.borrow_mut().push(stdout));
     }
-    
-    // For run_capture returning an error
+
+    // For run_capture returning an error. Also FIFO, ahead of the stdout queue: an error queued
+    // for a given call is consumed before that call falls through to the stdout queue.
     pub fn set_mock_capture_error(error_message: String) {
-         MOCK_CAPTURE_ERROR.with(|cell| *cell.borrow_mut() = Some(Err(eyre!(error_message))));
+        MOCK_CAPTURE_ERROR_QUEUE.with(|q| q.borrow_mut().push(eyre!(error_message)));
     }
 
-    // Gets combined result for run_capture
+    // Gets combined result for run_capture. Removes from the front of whichever queue has the
+    // next answer (FIFO), errors taking priority so `set_mock_capture_error` still fails the
+    // very next call regardless of what's queued in the stdout queue.
     pub(crate) fn get_mock_capture_result() -> Result<Option<String>> {
-        if let Some(error_res) = MOCK_CAPTURE_ERROR.with(|cell| cell.borrow_mut().take()) {
-            return error_res;
+        if let Some(err) = MOCK_CAPTURE_ERROR_QUEUE.with(|q| {
+            let mut queue = q.borrow_mut();
+            (!queue.is_empty()).then(|| queue.remove(0))
+        }) {
+            return Err(err);
         }
-        MOCK_CAPTURE_STDOUT.with(|cell| {
-            cell.borrow_mut().take().unwrap_or_else(|| {
-                warn!("Mock capture stdout was not set or already consumed, defaulting to Ok(None).");
-                Ok(None) // Default to Ok(None) if no specific mock stdout was set
-            })
+        MOCK_CAPTURE_STDOUT_QUEUE.with(|q| {
+            let mut queue = q.borrow_mut();
+            if queue.is_empty() {
+                warn!("Mock capture stdout queue was empty, defaulting to Ok(None).");
+                Ok(None)
+            } else {
+                Ok(Some(queue.remove(0)))
+            }
         })
     }
     
@@ -218,6 +229,9 @@ pub struct Command {
     args: Vec<OsString>,
     elevate: bool,
     current_working_dir: Option<PathBuf>, // ADDED
+    log_file: Option<PathBuf>,
+    envs: Vec<(OsString, OsString)>,
+    env_clear: bool,
 }
 
 impl Command {
@@ -229,9 +243,47 @@ impl Command {
             args: vec![],
             elevate: false,
             current_working_dir: None, // ADDED
+            log_file: None,
+            envs: vec![],
+            env_clear: false,
         }
     }
 
+    /// Sets an environment variable for the child process, mirroring
+    /// `std::process::Command::env`.
+    pub fn env<K: AsRef<OsStr>, V: AsRef<OsStr>>(mut self, key: K, value: V) -> Self {
+        self.envs.push((key.as_ref().to_os_string(), value.as_ref().to_os_string()));
+        self
+    }
+
+    /// Sets multiple environment variables, mirroring `std::process::Command::envs`.
+    pub fn envs<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        for (key, value) in vars {
+            self.envs.push((key.as_ref().to_os_string(), value.as_ref().to_os_string()));
+        }
+        self
+    }
+
+    /// Clears the child process's environment before applying `.env()`/`.envs()`,
+    /// mirroring `std::process::Command::env_clear`.
+    pub fn env_clear(mut self, clear: bool) -> Self {
+        self.env_clear = clear;
+        self
+    }
+
+    /// Duplicates the command's stdout/stderr into `path` in addition to the
+    /// terminal, so a run's full output survives after the terminal
+    /// scrollback is gone. Only takes effect for [`Command::run`].
+    pub fn log_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.log_file = Some(path.as_ref().to_path_buf());
+        self
+    }
+
     pub fn get_command_name(&self) -> &std::ffi::OsStr {
         &self.command
     }
@@ -281,6 +333,44 @@ impl Command {
         self
     }
 
+    /// Applies `.env_clear()`/`.env()`/`.envs()` to a `std::process::Command`.
+    fn apply_env(&self, cmd: &mut StdCommand) {
+        if self.env_clear {
+            cmd.env_clear();
+        }
+        cmd.envs(self.envs.iter().map(|(k, v)| (k, v)));
+    }
+
+    /// Builds the `sudo <command> <args>` invocation used when `self.elevate` is set,
+    /// including the macOS `--preserve-env=PATH`/`--set-home` handling `sudo` needs there.
+    fn build_sudo_command(&self) -> StdCommand {
+        let mut sudo_cmd = StdCommand::new("sudo");
+        if cfg!(target_os = "macos") {
+            let mut check_cmd = StdCommand::new("sudo");
+            check_cmd.arg("--help");
+            match util::run_cmd(&mut check_cmd) {
+                Ok(output) => {
+                    let output_str = String::from_utf8_lossy(&output.stdout);
+                    if output_str.contains("--preserve-env") {
+                        sudo_cmd.args(["--set-home", "--preserve-env=PATH", "env"]);
+                    } else {
+                        sudo_cmd.arg("--set-home");
+                    }
+                },
+                Err(_) => {
+                    sudo_cmd.arg("--set-home");
+                }
+            }
+        }
+        if let Some(cwd) = &self.current_working_dir {
+            sudo_cmd.current_dir(cwd);
+        }
+        sudo_cmd.arg(&self.command);
+        sudo_cmd.args(&self.args);
+        self.apply_env(&mut sudo_cmd);
+        sudo_cmd
+    }
+
     pub fn to_command_string(&self) -> String {
         let mut s = self.command.to_string_lossy().into_owned();
         for arg in &self.args {
@@ -315,43 +405,33 @@ impl Command {
 
         // Actual execution logic follows here from the original function...
         // Check 3: Actual execution
-        let mut cmd_to_execute = if self.elevate { 
-            let mut sudo_cmd = StdCommand::new("sudo");
-            if cfg!(target_os = "macos") {
-                let mut check_cmd = StdCommand::new("sudo");
-                check_cmd.arg("--help");
-                match util::run_cmd(&mut check_cmd) {
-                    Ok(output) => {
-                        let output_str = String::from_utf8_lossy(&output.stdout);
-                        if output_str.contains("--preserve-env") {
-                            sudo_cmd.args(["--set-home", "--preserve-env=PATH", "env"]);
-                        } else {
-                            sudo_cmd.arg("--set-home");
-                        }
-                    },
-                    Err(_) => {
-                        sudo_cmd.arg("--set-home");
-                    }
-                }
-            }
-            if let Some(cwd) = &self.current_working_dir {
-                sudo_cmd.current_dir(cwd);
-            }
-            sudo_cmd.arg(&self.command);
-            sudo_cmd.args(&self.args);
-            sudo_cmd
+        let mut cmd_to_execute = if self.elevate {
+            self.build_sudo_command()
         } else {
             let mut std_cmd = StdCommand::new(&self.command);
             if let Some(cwd) = &self.current_working_dir {
                 std_cmd.current_dir(cwd);
             }
             std_cmd.args(&self.args);
+            self.apply_env(&mut std_cmd);
             std_cmd
         };
 
         debug!("Executing command: {:?}", cmd_to_execute);
-        
-        match util::run_cmd_inherit_stdio(&mut cmd_to_execute) {
+
+        // Elevated commands get a pty instead of plain inherited stdio: `sudo` needs a real
+        // terminal to prompt for a password, which it won't get if our own stdin/stdout have
+        // been redirected (e.g. by the nom pipeline in `Build::run`).
+        let run_result = if self.elevate && self.log_file.is_none() {
+            util::run_cmd_pty(&mut cmd_to_execute).map(|output| output.status)
+        } else {
+            match &self.log_file {
+                Some(log_path) => util::run_cmd_tee_stdio(&mut cmd_to_execute, log_path),
+                None => util::run_cmd_inherit_stdio(&mut cmd_to_execute),
+            }
+        };
+
+        match run_result {
             Ok(_) => Ok(()),
             Err(e) => {
                 if let Some(m) = &self.message {
@@ -386,16 +466,30 @@ impl Command {
         }
 
         // Actual execution logic follows...
-        let mut cmd = StdCommand::new(&self.command);
-        cmd.args(&self.args);
+        let mut cmd = if self.elevate {
+            self.build_sudo_command()
+        } else {
+            let mut c = StdCommand::new(&self.command);
+            c.args(&self.args);
+            if let Some(cwd) = &self.current_working_dir {
+                c.current_dir(cwd);
+            }
+            self.apply_env(&mut c);
+            c
+        };
 
-        if let Some(cwd) = &self.current_working_dir { // ADDED
-            cmd.current_dir(cwd);                    // ADDED
-        }                                              // ADDED
-        
         debug!("Executing command: {:?}", cmd);
-        
-        match util::run_cmd(&mut cmd) {
+
+        // Elevated commands still need a real terminal for `sudo` to prompt on, even though
+        // we're capturing the output here; a pty gives it one while we read the transcript
+        // back into `stdout`.
+        let capture_result = if self.elevate {
+            util::run_cmd_pty(&mut cmd)
+        } else {
+            util::run_cmd(&mut cmd)
+        };
+
+        match capture_result {
             Ok(output) => {
                 let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
                 Ok(Some(stdout))
@@ -461,20 +555,25 @@ impl Command {
         }
 
         // Actual execution
-        let mut cmd_to_run = StdCommand::new(&self.command);
-        cmd_to_run.args(&self.args);
+        let mut cmd_to_run = if self.elevate {
+            self.build_sudo_command()
+        } else {
+            let mut c = StdCommand::new(&self.command);
+            c.args(&self.args);
+            if let Some(cwd) = &self.current_working_dir {
+                c.current_dir(cwd);
+            }
+            self.apply_env(&mut c);
+            c
+        };
+
+        debug!("Executing command for full output capture: {:?}", cmd_to_run);
 
-        if let Some(cwd) = &self.current_working_dir { // ADDED
-            cmd_to_run.current_dir(cwd);             // ADDED
-        }                                              // ADDED
-        
         if self.elevate {
-            // util::run_cmd does not handle sudo. This path needs a sudo-aware counterpart of util::run_cmd
-            // or this method should not support elevate=true.
-            return Err(eyre!("run_capture_output with elevate=true is not supported as util::run_cmd does not handle sudo. Refactor required."));
+            // Give sudo a pty so it can prompt for a password even though we're capturing the
+            // output; stdout/stderr come back merged, the way they would on a real terminal.
+            return util::run_cmd_pty(&mut cmd_to_run).map_err(Into::into);
         }
-
-        debug!("Executing command for full output capture: {:?}", cmd_to_run);
         util::run_cmd(&mut cmd_to_run).map_err(Into::into) // util::run_cmd returns Result<std::process::Output, UtilCommandError>
     }
 }
@@ -523,6 +622,39 @@ impl Build {
         self
     }
 
+    /// Like [`Self::run`], but passes `--json` and returns the parsed build results (drv paths
+    /// and realized output paths) instead of just success/failure, so callers can obtain the
+    /// authoritative build result without relying on an out-link or a separate `nix path-info`
+    /// call. Stderr is inherited so build progress still prints live; only stdout (nix's JSON
+    /// summary, printed once the build finishes) is captured.
+    pub fn run_json(&self) -> Result<Vec<crate::json::BuildResult>> {
+        use std::process::Stdio;
+
+        if let Some(m) = &self.message {
+            info!("{}", m);
+        }
+
+        let mut cmd = StdCommand::new("nix");
+        cmd.arg("build")
+            .args(&self.installable.to_args())
+            .args(&self.extra_args)
+            .arg("--json");
+
+        debug!("Executing command: {:?}", cmd);
+
+        let output = cmd
+            .stderr(Stdio::inherit())
+            .output()
+            .map_err(|e| eyre!("Failed to run `nix build --json`: {e}"))?;
+
+        if !output.status.success() {
+            return Err(eyre!("nix build --json exited with {}", output.status));
+        }
+
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| eyre!("Failed to parse `nix build --json` output: {e}"))
+    }
+
     pub fn run(&self) -> Result<()> {
         if let Some(m) = &self.message {
             info!("{}", m);
@@ -1,5 +1,6 @@
 use serde::Deserialize;
 use toml;
+use std::collections::HashMap;
 use std::path::PathBuf; // Required for load_from_path
 
 #[derive(Debug, Deserialize, Default, Clone)]
@@ -7,6 +8,202 @@ use std::path::PathBuf; // Required for load_from_path
 pub struct NgConfig {
     #[serde(default)]
     pub pre_flight: PreFlightConfig,
+    #[serde(default)]
+    pub logging: RunLoggingConfig,
+    #[serde(default)]
+    pub remote_builders: RemoteBuildersConfig,
+    #[serde(default)]
+    pub binary_cache: BinaryCacheConfig,
+    #[serde(default)]
+    pub ui: UiConfig,
+    #[serde(default)]
+    pub elevation: ElevationConfig,
+    #[serde(default)]
+    pub clean: CleanConfig,
+    #[serde(default)]
+    pub dev: DevConfig,
+    /// Command aliases, e.g. `up = "os switch --update"`. Expanded against `argv[1]` by
+    /// [`crate::interface::Main::expand_aliases`] before clap ever parses the arguments.
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+    #[serde(default)]
+    pub known_failures: KnownFailuresConfig,
+}
+
+/// Configuration for `ng develop`.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DevConfig {
+    /// Installable to `nix develop` into, e.g. `".#devShells.x86_64-linux.default"`. Overrides
+    /// the default `devShells.<system>.default` resolution when set.
+    pub shell: Option<String>,
+}
+
+/// Terminal UI configuration, e.g. color theming.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UiConfig {
+    #[serde(default)]
+    pub theme: ThemeConfig,
+}
+
+/// Maps semantic UI roles to colors, consumed by `ui_style::Colors`. `preset` selects a
+/// built-in palette ("dark", the default, or "light" for white terminal backgrounds); the
+/// remaining fields override individual roles on top of the preset.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub preset: Option<String>,
+    pub success: Option<String>,
+    pub error: Option<String>,
+    pub warning: Option<String>,
+    pub info: Option<String>,
+    pub prompt: Option<String>,
+    pub code: Option<String>,
+    pub emphasis: Option<String>,
+}
+
+/// Configuration for `self_elevate`'s re-exec via `sudo`, and for privilege elevation during a
+/// rebuild workflow (the "profile_update" and "activation" stages run privileged commands).
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ElevationConfig {
+    /// Extra environment variables to pass through with `sudo --preserve-env=`, on top of the
+    /// built-in allowlist (`NIX_PATH`, `SSH_AUTH_SOCK`, and any `NH_*` variable already set).
+    #[serde(default)]
+    pub preserve_env: Vec<String>,
+    /// Which rebuild stages ("build", "profile_update", "activation") are allowed to elevate
+    /// via `sudo`. Defaults to allowing all of them; naming a stage that never needs to elevate
+    /// (e.g. "build") is harmless. When unset, every stage may elevate.
+    pub allow_stages: Option<Vec<String>>,
+    /// Run `sudo -v` once up front, before the workflow's first privileged stage, and keep
+    /// sudo's timestamp alive for the rest of the run, so the password prompt happens once
+    /// instead of potentially interrupting the run again mid-build or mid-activation.
+    #[serde(default)]
+    pub preauth: bool,
+}
+
+impl ElevationConfig {
+    /// Whether `stage` (e.g. `"activation"`) is allowed to elevate under this configuration.
+    pub fn may_elevate(&self, stage: &str) -> bool {
+        match &self.allow_stages {
+            None => true,
+            Some(stages) => stages.iter().any(|s| s == stage),
+        }
+    }
+}
+
+/// Configuration for `ng clean`.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CleanConfig {
+    /// Glob patterns (only `*` wildcards are supported) matched against each profile's or
+    /// generation link's path, e.g. `"*-backup"` or `"per-user/alice/*"`. A match means that
+    /// profile/generation is never deleted by `ng clean`, regardless of `--keep`/`--keep-since`.
+    #[serde(default)]
+    pub protect: Vec<String>,
+}
+
+impl CleanConfig {
+    /// Whether `path` matches any configured protect pattern. Patterns are matched against any
+    /// portion of `path` (an implicit `*` is added on both ends), so `"per-user/alice/*"`
+    /// matches `/nix/var/nix/profiles/per-user/alice/profile-3-link` even though the pattern
+    /// doesn't spell out the full absolute path.
+    pub fn is_protected(&self, path: &std::path::Path) -> bool {
+        let path_str = path.to_string_lossy();
+        self.protect
+            .iter()
+            .any(|pattern| glob_match(&format!("*{pattern}*"), &path_str))
+    }
+}
+
+/// Matches `text` against `pattern`, where `*` matches any sequence of characters (including
+/// none) and every other character is literal. No support for `?`, character classes, or
+/// escaping — deliberately minimal, since the only consumer is [`CleanConfig::protect`].
+fn glob_match(pattern: &str, text: &str) -> bool {
+    fn match_here(pattern: &[u8], text: &[u8]) -> bool {
+        match pattern.first() {
+            None => text.is_empty(),
+            Some(b'*') => {
+                (0..=text.len()).any(|i| match_here(&pattern[1..], &text[i..]))
+            }
+            Some(&c) => !text.is_empty() && text[0] == c && match_here(&pattern[1..], &text[1..]),
+        }
+    }
+    match_here(pattern.as_bytes(), text.as_bytes())
+}
+
+#[cfg(test)]
+mod glob_match_tests {
+    use super::glob_match;
+
+    #[test]
+    fn matches_trailing_wildcard() {
+        assert!(glob_match("*-backup", "system-42-backup"));
+        assert!(!glob_match("*-backup", "system-42"));
+    }
+
+    #[test]
+    fn matches_path_prefix_with_trailing_wildcard() {
+        assert!(glob_match("per-user/alice/*", "per-user/alice/profile"));
+        assert!(!glob_match("per-user/alice/*", "per-user/bob/profile"));
+    }
+
+    #[test]
+    fn matches_exact_pattern() {
+        assert!(glob_match("system", "system"));
+        assert!(!glob_match("system", "systemd"));
+    }
+}
+
+/// Configuration for pushing successful builds to a binary cache via `nix copy`.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BinaryCacheConfig {
+    /// `nix copy --to` target, e.g. `s3://my-cache` or `ssh://cache.example.com`.
+    pub push_to: Option<String>,
+    /// Extra arguments passed through to `nix copy`.
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+/// Configuration for `nix build --builders`-style remote build machines.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteBuildersConfig {
+    /// Builder specs in nix's `ssh://user@host system /path/to/key maxjobs speedFactor` syntax.
+    pub builders: Option<Vec<String>>,
+    /// Whether to SSH-ping each configured builder before a build and warn on failures.
+    #[serde(default)]
+    pub health_check: bool,
+}
+
+/// Configuration for tee-ing build/command output to a per-run log file.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RunLoggingConfig {
+    /// Directory to write per-run log files into. When unset, no tee-ing happens.
+    pub log_dir: Option<String>,
+    /// Print a build statistics summary (wall time, built vs substituted, download size, closure size)
+    /// after a successful build.
+    #[serde(default)]
+    pub build_stats: bool,
+    /// Also write all tracing output (debug level) to a rotating log file under
+    /// `~/.local/state/ng/logs/`, so full logs can be attached to bug reports even when the
+    /// terminal only showed info level.
+    #[serde(default)]
+    pub file_logging: bool,
+    /// Also emit structured logs to journald (Linux only), tagged with syslog identifier
+    /// "ng" so `journalctl -t ng` shows deployment history. Ignored on non-Linux platforms.
+    #[serde(default)]
+    pub journald: bool,
+    /// Export workflow spans (pre-flight, eval, build, diff, activate) to an OTLP collector,
+    /// so fleets running many hosts can trace rebuild durations in their observability stack.
+    #[serde(default)]
+    pub otel: bool,
+    /// OTLP HTTP endpoint to export spans to. Falls back to `OTEL_EXPORTER_OTLP_ENDPOINT`,
+    /// then `http://localhost:4318`, when unset.
+    pub otel_endpoint: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Default, Clone)]
@@ -19,12 +216,47 @@ pub struct PreFlightConfig {
     pub format: FormatConfig,
     #[serde(default)]
     pub external_linters: ExternalLintersConfig, // ADDED
+    #[serde(default)]
+    pub homebrew: HomebrewConfig,
+    #[serde(default)]
+    pub security_advisories: SecurityAdvisoriesConfig,
+    #[serde(default)]
+    pub disk_space: DiskSpaceConfig,
+    #[serde(default)]
+    pub memory: MemoryConfig,
+}
+
+/// Configuration for the "Memory Availability" pre-flight check. Not part of the default check
+/// selection; opt in via `pre_flight.checks` in ng.toml.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryConfig {
+    /// Minimum combined RAM+swap, in bytes, required before a build starts. Defaults to 2 GiB
+    /// when unset.
+    pub min_available_bytes: Option<u64>,
+}
+
+/// Configuration for the "Disk Space" pre-flight check.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DiskSpaceConfig {
+    /// Extra free space, in bytes, required beyond the build's own estimated size before a build
+    /// starts. Defaults to 1 GiB when unset.
+    pub min_headroom_bytes: Option<u64>,
+    /// Treat insufficient space as a hard failure instead of a warning. Defaults to `false`.
+    #[serde(default)]
+    pub strict: bool,
 }
 
 #[derive(Debug, Deserialize, Default, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct FormatConfig {
     pub tool: Option<String>,
+    /// Show a trimmed unified diff for each unformatted file the format pre-flight check finds,
+    /// instead of just its path. Defaults to `false`.
+    pub show_diff: Option<bool>,
+    /// Max number of diff hunks to show per file when `show_diff` is enabled. Defaults to 3.
+    pub diff_hunks: Option<usize>,
 }
 
 // ADDED NEW STRUCT
@@ -38,6 +270,110 @@ pub struct ExternalLintersConfig {
     pub deadnix_args: Option<Vec<String>>,
 }
 
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HomebrewConfig {
+    /// Path to the `brew` binary, if not on `PATH`.
+    pub brew_path: Option<String>,
+}
+
+/// User-extendable advisories for the "Flake Input Advisories" pre-flight check, on top of the
+/// bundled defaults in [`crate::pre_flight::BUNDLED_ADVISORIES`].
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SecurityAdvisoriesConfig {
+    #[serde(default)]
+    pub extra_advisories: Vec<FlakeInputAdvisory>,
+}
+
+/// A single known-bad locked revision for a named flake input, e.g. one pinned by a compromised
+/// or later-yanked commit.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FlakeInputAdvisory {
+    /// Name of the flake input this advisory applies to, e.g. "nixpkgs".
+    pub input: String,
+    /// The known-bad locked revision (commit hash, or other `locked.rev` value).
+    pub rev: String,
+    /// Human-readable explanation shown when the advisory matches.
+    pub reason: String,
+}
+
+/// User-extendable entries for [`crate::error_handler::scan_log_for_recommendations`], on top of
+/// the bundled defaults in [`crate::error_handler::BUNDLED_KNOWN_FAILURES`].
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct KnownFailuresConfig {
+    #[serde(default)]
+    pub extra_patterns: Vec<crate::error_handler::KnownFailurePattern>,
+}
+
+/// A single valid `ng.toml` key, as listed by `ng config keys`. Kept in sync by hand: Rust has
+/// no runtime struct reflection (and `Option` fields would vanish from a serialized instance
+/// rather than list themselves), so this mirrors [`NgConfig`] and its nested structs field for
+/// field instead of being derived automatically.
+pub struct ConfigKeyInfo {
+    pub path: &'static str,
+    pub type_name: &'static str,
+    pub description: &'static str,
+}
+
+/// All valid `ng.toml` configuration keys, in the same nesting order as [`NgConfig`].
+pub fn all_config_keys() -> Vec<ConfigKeyInfo> {
+    macro_rules! key {
+        ($path:literal, $type_name:literal, $description:literal) => {
+            ConfigKeyInfo {
+                path: $path,
+                type_name: $type_name,
+                description: $description,
+            }
+        };
+    }
+    vec![
+        key!("pre_flight.checks", "array<string>", "Explicit list of pre-flight checks to run, overriding the default/--medium/--full selection"),
+        key!("pre_flight.strict_lint", "boolean", "Treat lint warnings as failures"),
+        key!("pre_flight.strict_format", "boolean", "Treat formatting issues as failures"),
+        key!("pre_flight.format.tool", "string", "Formatter to run, e.g. \"nixfmt\" or \"alejandra\""),
+        key!("pre_flight.format.show_diff", "boolean", "Show a unified diff for unformatted files"),
+        key!("pre_flight.format.diff_hunks", "integer", "Max diff hunks to show per file (default 3)"),
+        key!("pre_flight.external_linters.enable", "array<string>", "Linters to run, e.g. [\"statix\", \"deadnix\"]"),
+        key!("pre_flight.external_linters.statix_path", "string", "Path to the statix binary, if not on PATH"),
+        key!("pre_flight.external_linters.deadnix_path", "string", "Path to the deadnix binary, if not on PATH"),
+        key!("pre_flight.external_linters.statix_args", "array<string>", "Extra arguments passed to statix"),
+        key!("pre_flight.external_linters.deadnix_args", "array<string>", "Extra arguments passed to deadnix"),
+        key!("pre_flight.homebrew.brew_path", "string", "Path to the brew binary, if not on PATH"),
+        key!("pre_flight.security_advisories.extra_advisories", "array<table>", "Extra flake input advisories, each a table with input/rev/reason"),
+        key!("pre_flight.disk_space.min_headroom_bytes", "integer", "Extra free space required beyond the build's estimated size, in bytes (default 1 GiB)"),
+        key!("pre_flight.disk_space.strict", "boolean", "Treat insufficient disk space as a hard failure instead of a warning"),
+        key!("pre_flight.memory.min_available_bytes", "integer", "Minimum combined RAM+swap required before a build starts, in bytes (default 2 GiB)"),
+        key!("logging.log_dir", "string", "Directory to write per-run log files into"),
+        key!("logging.build_stats", "boolean", "Print a build statistics summary after a successful build"),
+        key!("logging.file_logging", "boolean", "Write debug-level tracing output to a rotating log file"),
+        key!("logging.journald", "boolean", "Emit structured logs to journald (Linux only)"),
+        key!("logging.otel", "boolean", "Export workflow spans to an OTLP collector"),
+        key!("logging.otel_endpoint", "string", "OTLP HTTP endpoint to export spans to"),
+        key!("remote_builders.builders", "array<string>", "Builder specs in nix's `ssh://user@host system /path/to/key maxjobs speedFactor` syntax"),
+        key!("remote_builders.health_check", "boolean", "SSH-ping each configured builder before a build and warn on failures"),
+        key!("binary_cache.push_to", "string", "`nix copy --to` target, e.g. \"s3://my-cache\""),
+        key!("binary_cache.extra_args", "array<string>", "Extra arguments passed through to `nix copy`"),
+        key!("ui.theme.preset", "string", "Built-in color palette: \"dark\" (default) or \"light\""),
+        key!("ui.theme.success", "string", "Color override for success messages"),
+        key!("ui.theme.error", "string", "Color override for error messages"),
+        key!("ui.theme.warning", "string", "Color override for warning messages"),
+        key!("ui.theme.info", "string", "Color override for info messages"),
+        key!("ui.theme.prompt", "string", "Color override for interactive prompts"),
+        key!("ui.theme.code", "string", "Color override for inline code/paths"),
+        key!("ui.theme.emphasis", "string", "Color override for emphasized text"),
+        key!("elevation.preserve_env", "array<string>", "Extra environment variables to pass through with `sudo --preserve-env=`"),
+        key!("elevation.allow_stages", "array<string>", "Which rebuild stages (\"build\", \"profile_update\", \"activation\") are allowed to elevate via sudo"),
+        key!("elevation.preauth", "boolean", "Run `sudo -v` once up front and keep the timestamp alive for the whole run"),
+        key!("clean.protect", "array<string>", "Glob patterns protecting matching profiles/generations from `ng clean`"),
+        key!("dev.shell", "string", "Installable to `nix develop` into, e.g. \".#devShells.x86_64-linux.default\""),
+        key!("aliases", "table<string, string>", "Command aliases, e.g. up = \"os switch --update\""),
+        key!("known_failures.extra_patterns", "array<table>", "Extra known-failure patterns, each a table with id/regex/explanation/fix, checked on top of the bundled database"),
+    ]
+}
+
 impl NgConfig {
     pub fn from_str(toml_content: &str) -> Result<Self, toml::de::Error> {
         toml::from_str(toml_content)
@@ -0,0 +1,31 @@
+//! `ng config`: introspection over ng's own `ng.toml` schema, so editing the file (or scripting
+//! `ng config keys | grep ...`) doesn't require reading this crate's source.
+
+use crate::config::all_config_keys;
+use crate::interface::{ConfigArgs, ConfigKeysArgs, ConfigSubcommand};
+use crate::tables::ReportTable;
+use crate::Result;
+
+impl ConfigArgs {
+    pub fn run(self) -> Result<()> {
+        match self.subcommand {
+            ConfigSubcommand::Keys(args) => args.run(),
+        }
+    }
+}
+
+impl ConfigKeysArgs {
+    fn run(self) -> Result<()> {
+        let rows = all_config_keys()
+            .into_iter()
+            .map(|key| vec![key.path.to_string(), key.type_name.to_string(), key.description.to_string()])
+            .collect();
+
+        let table = ReportTable::new(vec!["Key", "Type", "Description"], rows).wide(self.wide);
+        if let Err(e) = table.render(self.format) {
+            tracing::debug!("Failed to display config keys: {}", e);
+        }
+
+        Ok(())
+    }
+}
@@ -34,12 +34,17 @@ mod tests {
             medium: false,
             full: false,
             dry: true,
-            // Note: clap_common_args.ask is bool, but its clap default is true.
-            // This test helper sets it to false, which is fine if not testing 'ask' specifically.
-            ask: false,
+            // This test helper doesn't ask for confirmation at all, which is fine if not
+            // testing '--ask' specifically.
+            ask: Vec::new(),
+            no_ask: true,
             no_nom: true,
             out_link: None,
             clean: false,
+            keep_going: false,
+            json: false,
+            plan: false,
+            no_group: false,
         };
 
         if let Some(modifier) = cli_args_modifier {
@@ -57,11 +62,19 @@ mod tests {
             medium_checks: clap_common_args.medium,
             full_checks: clap_common_args.full,
             dry_run: clap_common_args.dry,
-            ask_confirmation: clap_common_args.ask,
+            confirm_stages: if clap_common_args.no_ask {
+                Vec::new()
+            } else {
+                clap_common_args.ask.clone()
+            },
             no_nom: clap_common_args.no_nom,
             out_link: clap_common_args.out_link.clone(),
             clean_after: clap_common_args.clean,
             extra_build_args: Vec::new(),
+            keep_going: clap_common_args.keep_going,
+            json: clap_common_args.json,
+            plan: clap_common_args.plan,
+            no_group: clap_common_args.no_group,
         };
 
         static DUMMY_UPDATE_ARGS: UpdateArgs = UpdateArgs {
@@ -88,6 +101,14 @@ mod tests {
         fs::write(temp_dir.path().join("good.nix"), "{ value = 1; }").unwrap();
         test_support::enable_test_mode();
         test_support::set_mock_run_result(Ok(()));
+        // The default check list includes `NixConfigSanityPreFlightCheck` and
+        // `NixImplementationPreFlightCheck`, which both resolve `op_ctx.nix_environment()`
+        // (cached after the first call) via `nix --version` then `nix config show
+        // experimental-features`. Queue answers for those two so it resolves successfully;
+        // Sanity's further `run_capture()` calls (store ping, config show substituters/
+        // trusted-public-keys) already degrade to their defaults when unmocked.
+        test_support::set_mock_capture_stdout("nix (Nix) 2.18.1".to_string());
+        test_support::set_mock_capture_stdout("nix-command flakes".to_string());
 
         let op_ctx = create_op_ctx_for_preflight(&temp_dir, "[pre_flight]", None);
         let mock_strategy = MockPlatformStrategy;
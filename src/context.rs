@@ -1,7 +1,11 @@
 use crate::workflow_types::CommonRebuildArgs;
 use crate::interface::UpdateArgs;
-use crate::nix_interface::NixInterface;
+use crate::nix_analyzer::NixAnalysisContext;
+use crate::nix_interface::{NixEnvironmentInfo, NixInterface};
 use crate::config::NgConfig;
+use crate::Result;
+use once_cell::sync::OnceCell;
+use std::cell::RefCell;
 use std::sync::Arc;
 use std::path::PathBuf; // For project_root
 
@@ -15,6 +19,8 @@ pub struct OperationContext<'a> {
     pub nix_interface: NixInterface,
     pub config: Arc<NgConfig>,
     pub project_root: Option<PathBuf>, // Added for specifying root in tests
+    nix_env: OnceCell<NixEnvironmentInfo>,
+    nix_analysis: OnceCell<RefCell<NixAnalysisContext>>,
 }
 
 impl<'a> OperationContext<'a> {
@@ -33,11 +39,30 @@ impl<'a> OperationContext<'a> {
             nix_interface,
             config,
             project_root,
+            nix_env: OnceCell::new(),
+            nix_analysis: OnceCell::new(),
         }
     }
-    
+
     // Helper to get the effective project root
     pub fn get_effective_project_root(&self) -> PathBuf {
-        self.project_root.clone().unwrap_or_else(|| PathBuf::from("."))
+        self.project_root
+            .clone()
+            .or_else(|| self.common_args.installable.project_root())
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// Returns the detected nix/Lix version and experimental features,
+    /// detecting and caching them on first access.
+    pub fn nix_environment(&self) -> Result<&NixEnvironmentInfo> {
+        self.nix_env.get_or_try_init(|| self.nix_interface.detect_environment())
+    }
+
+    /// Returns the single [`NixAnalysisContext`] shared by every check in this run (e.g.
+    /// `NixParsePreFlightCheck` and `SemanticPreFlightCheck`), created lazily on first access,
+    /// so a file parsed by one check is already cached for the next instead of being re-parsed
+    /// from scratch.
+    pub fn nix_analysis_context(&self) -> &RefCell<NixAnalysisContext> {
+        self.nix_analysis.get_or_init(|| RefCell::new(NixAnalysisContext::new()))
     }
 }
\ No newline at end of file
@@ -4,6 +4,7 @@ use crate::context::OperationContext;
 use crate::workflow_strategy::{PlatformRebuildStrategy, ActivationMode};
 use crate::interface::DarwinRebuildArgs;
 use std::path::{Path, PathBuf};
+use tracing::warn;
 
 #[derive(Debug)]
 pub struct DarwinPlatformStrategy;
@@ -40,32 +41,55 @@ impl PlatformRebuildStrategy for DarwinPlatformStrategy {
 
     fn activate_configuration(
         &self,
-        _op_ctx: &OperationContext, // Prefixed
-        _platform_args: &Self::PlatformArgs, // Prefixed
+        op_ctx: &OperationContext,
+        platform_args: &Self::PlatformArgs,
         built_profile_path: &Path,
         activation_mode: &ActivationMode,
     ) -> Result<()> {
         match activation_mode {
             ActivationMode::Switch => {
-                // Mimic `darwin-rebuild switch`
-                // 1. Update /run/current-system (or equivalent) to point to the new profile.
-                //    This is often done by the `activate` script in the Nix profile.
-                // 2. Run the activation script.
+                // Older nix-darwin generations ship a root-owned `activate` script plus a
+                // separate `activate-user` script that must run as the invoking (non-root)
+                // user first, since it configures launchd agents and user defaults that a
+                // root-owned process can't touch. Newer nix-darwin versions fold the user
+                // half into `activate` itself and don't ship `activate-user` at all, so detect
+                // what the built closure actually provides rather than hard-coding both.
                 let activate_script = built_profile_path.join("activate");
+                let activate_user_script = built_profile_path.join("activate-user");
                 if !activate_script.exists() {
                     return Err(color_eyre::eyre::eyre!("activate script not found in built profile: {}", activate_script.display()));
                 }
 
-                println!("Simulating activation for Darwin (switch):");
-                println!("  System profile would be updated (conceptually).");
-                println!("  Running activation script: {}", activate_script.display());
+                if let Some(name) = &platform_args.profile_name {
+                    warn!("--profile-name ('{name}') is not yet supported for Darwin activation; the default system profile will be used.");
+                }
+
+                if op_ctx.common_args.dry_run {
+                    println!("DRY-RUN: Would run the following activation sequence:");
+                    if activate_user_script.exists() {
+                        println!("  {}", activate_user_script.display());
+                    }
+                    println!("  sudo {}", activate_script.display());
+                    return Ok(());
+                }
+
+                if !op_ctx.config.elevation.may_elevate("activation") {
+                    return Err(color_eyre::eyre::eyre!(
+                        "Darwin activation requires sudo, but the \"activation\" stage is not in elevation.allow_stages in ng.toml."
+                    ));
+                }
 
-                if !_op_ctx.common_args.dry_run {
-                    // In a real scenario:
-                    // Command::new(&activate_script).elevate(true).run()?;
-                    // sudo /nix/var/nix/profiles/system/bin/darwin-activate (example of what it might do)
-                    println!("DRY-RUN: Would execute {}", activate_script.display());
+                if activate_user_script.exists() {
+                    crate::commands::Command::new(&activate_user_script)
+                        .message("Activating user configuration")
+                        .run()?;
                 }
+
+                crate::commands::Command::new(&activate_script)
+                    .elevate(true)
+                    .message("Activating system configuration")
+                    .run()?;
+
                 Ok(())
             }
             ActivationMode::Build => {
@@ -82,6 +106,49 @@ impl PlatformRebuildStrategy for DarwinPlatformStrategy {
     }
 
     fn post_rebuild_hook(&self, _op_ctx: &OperationContext, _platform_args: &Self::PlatformArgs) -> Result<()> {
+        // Non-critical: a service that failed to load shouldn't fail an activation that already
+        // succeeded, so issues are surfaced as warnings rather than propagated as an error.
+        let labels = crate::launchd::list_managed_labels();
+        if labels.is_empty() {
+            return Ok(());
+        }
+
+        match crate::launchd::check_service_health(&labels) {
+            Ok(unhealthy) if unhealthy.is_empty() => {}
+            Ok(unhealthy) => {
+                warn!("Some launchd services managed by this configuration look unhealthy:");
+                for service in &unhealthy {
+                    warn!("  - {} ({})", service.label, service.reason);
+                }
+            }
+            Err(e) => warn!("Failed to check launchd service health: {}", e),
+        }
+
         Ok(())
     }
+
+    fn get_homebrew_options_installable(
+        &self,
+        op_ctx: &OperationContext,
+        platform_args: &Self::PlatformArgs,
+    ) -> Option<Installable> {
+        let hostname = platform_args
+            .hostname
+            .clone()
+            .or_else(|| crate::util::get_hostname().ok())?;
+
+        let mut installable = op_ctx.common_args.installable.clone();
+        match &mut installable {
+            Installable::Flake { attribute, .. } => {
+                if attribute.is_empty() {
+                    attribute.push("darwinConfigurations".to_string());
+                    attribute.push(hostname);
+                }
+                attribute.push("config".to_string());
+                attribute.push("homebrew".to_string());
+                Some(installable)
+            }
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,61 @@
+//! `ng develop`: resolves the flake's devShell and execs `nix develop`, so contributors get one
+//! entry point instead of remembering `nix develop .#devShells.<system>.default` by hand.
+
+use color_eyre::eyre::eyre;
+
+use crate::commands::Command;
+use crate::config::NgConfig;
+use crate::installable::Installable;
+use crate::interface::DevelopArgs;
+use crate::Result;
+
+impl DevelopArgs {
+    pub fn run(&self, verbose_count: u8) -> Result<()> {
+        let installable = self.resolve_shell_installable(verbose_count)?;
+
+        Command::new("nix")
+            .arg("develop")
+            .args(installable.to_args())
+            .args(&self.extra_args)
+            .add_verbosity_flags(verbose_count)
+            .run()
+    }
+
+    /// Picks the devShell installable to enter, in order of precedence: `--shell`, `dev.shell` in
+    /// `ng.toml`, then `devShells.<system>.default` on the flake `installable` already resolved
+    /// from the command line.
+    fn resolve_shell_installable(&self, verbose_count: u8) -> Result<Installable> {
+        let config = NgConfig::load();
+        let shell_ref = self.shell.clone().or(config.dev.shell);
+
+        if let Some(shell_ref) = shell_ref {
+            let mut elems = shell_ref.splitn(2, '#');
+            let reference = elems.next().unwrap_or_default().to_owned();
+            let attribute = elems
+                .next()
+                .map(|s| s.split('.').filter(|p| !p.is_empty()).map(String::from).collect())
+                .unwrap_or_default();
+            return Ok(Installable::Flake { reference, attribute });
+        }
+
+        match &self.installable {
+            Installable::Flake { reference, attribute } if attribute.is_empty() => {
+                let system = detect_current_system(verbose_count)?;
+                Ok(Installable::Flake {
+                    reference: reference.clone(),
+                    attribute: vec!["devShells".to_string(), system, "default".to_string()],
+                })
+            }
+            other => Ok(other.clone()),
+        }
+    }
+}
+
+fn detect_current_system(verbose_count: u8) -> Result<String> {
+    Command::new("nix")
+        .args(["eval", "--impure", "--raw", "--expr", "builtins.currentSystem"])
+        .add_verbosity_flags(verbose_count)
+        .run_capture()?
+        .map(|s| s.trim().to_string())
+        .ok_or_else(|| eyre!("Failed to detect the current Nix system"))
+}
@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use color_eyre::eyre::{bail, eyre, WrapErr};
+use tracing::debug;
+
+use crate::installable::{parse_attribute, Installable};
+use crate::interface::DiffArgs;
+use crate::nix_interface::NixInterface;
+use crate::Result;
+
+impl DiffArgs {
+    pub fn run(&self, verbose_count: u8) -> Result<()> {
+        let nix_interface = NixInterface::new(verbose_count, false);
+
+        let left = resolve_diff_target(&self.left, &nix_interface)
+            .wrap_err_with(|| format!("Failed to resolve diff target '{}'", self.left))?;
+        let right = resolve_diff_target(&self.right, &nix_interface)
+            .wrap_err_with(|| format!("Failed to resolve diff target '{}'", self.right))?;
+
+        if self.json {
+            let diff = nix_interface.diff_closures_summary(&left, &right)?;
+            let summary = crate::json::DiffCommandSummary {
+                left,
+                right,
+                diff: diff.into(),
+            };
+            println!("{}", serde_json::to_string(&summary)?);
+            return Ok(());
+        }
+
+        crate::workflow_executor::show_platform_diff(&left, &right, verbose_count)
+    }
+}
+
+/// Resolves a `ng diff` target string to a concrete filesystem path: a generation reference
+/// (`<profile>#<number>`), an existing path (a store path or a symlink to one, e.g.
+/// `/run/current-system`), or a flake installable, which is built if needed.
+pub(crate) fn resolve_diff_target(spec: &str, nix_interface: &NixInterface) -> Result<PathBuf> {
+    if let Some(path) = resolve_generation_reference(spec)? {
+        return Ok(path);
+    }
+
+    if let Ok(canonical) = std::fs::canonicalize(spec) {
+        return Ok(canonical);
+    }
+
+    debug!("'{}' is not a generation reference or existing path; building it", spec);
+    let mut elems = spec.splitn(2, '#');
+    let reference = elems.next().unwrap().to_owned();
+    let attribute = elems.next().map(parse_attribute).unwrap_or_default();
+    let installable = Installable::Flake { reference, attribute };
+
+    nix_interface.build_configuration(&installable, &[], true, None)
+}
+
+/// Parses `spec` as `<profile>#<generation-number>`, where `profile` is the shorthand `system`
+/// or `home-manager`, or an explicit profile path (e.g. `/nix/var/nix/profiles/system`).
+/// Returns `Ok(None)` if `spec` isn't shaped like a generation reference at all, so the caller
+/// can fall through to other resolution strategies.
+fn resolve_generation_reference(spec: &str) -> Result<Option<PathBuf>> {
+    let Some((profile_spec, generation)) = spec.split_once('#') else {
+        return Ok(None);
+    };
+    if generation.is_empty() || !generation.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(None);
+    }
+
+    let profile = match profile_spec {
+        "system" => PathBuf::from("/nix/var/nix/profiles/system"),
+        "home-manager" => crate::home::default_profile()
+            .ok_or_else(|| eyre!("Could not locate a home-manager profile on this machine"))?,
+        other => PathBuf::from(other),
+    };
+
+    let profile_name = profile
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("system");
+    let dir = profile
+        .parent()
+        .ok_or_else(|| eyre!("Profile path '{}' has no parent directory", profile.display()))?;
+    let generation_path = dir.join(format!("{profile_name}-{generation}-link"));
+
+    if !generation_path.exists() {
+        bail!(
+            "Generation {} not found for profile {} (expected {})",
+            generation,
+            profile.display(),
+            generation_path.display()
+        );
+    }
+
+    Ok(Some(generation_path))
+}
@@ -0,0 +1,30 @@
+//! `ng du`: disk-usage breakdown of a closure, via `nix path-info --recursive`.
+
+use color_eyre::eyre::WrapErr;
+
+use crate::interface::DuArgs;
+use crate::nix_interface::NixInterface;
+use crate::Result;
+
+impl DuArgs {
+    pub fn run(&self, verbose_count: u8) -> Result<()> {
+        let nix_interface = NixInterface::new(verbose_count, false);
+
+        let path = nix_interface
+            .build_configuration(&self.installable, &[], true, None)
+            .wrap_err("Failed to resolve installable to a store path")?;
+
+        let mut paths = nix_interface
+            .closure_path_sizes(&path)
+            .wrap_err_with(|| format!("Failed to query closure of {}", path.display()))?;
+
+        if self.json {
+            paths.sort_unstable_by(|a, b| b.nar_size.cmp(&a.nar_size));
+            println!("{}", serde_json::to_string(&paths)?);
+            return Ok(());
+        }
+
+        crate::tables::display_disk_usage(&paths, self.top);
+        Ok(())
+    }
+}
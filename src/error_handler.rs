@@ -4,6 +4,7 @@ use color_eyre::eyre::{Result, eyre}; // Removed Context
 use lazy_static::lazy_static;
 use owo_colors::OwoColorize;
 use regex::Regex;
+use serde::Deserialize;
 // StdCommand, StdProcessOutput, ExitStatusExt removed as create_mock_output is in test module
 use crate::commands::Command as NgCommand;
 use tracing::{error, info}; 
@@ -17,11 +18,13 @@ lazy_static! {
     
     // Match Nix builder failures: error: builder for '/nix/store/...drv' failed
     static ref RE_BUILDER_FAILED: Regex = Regex::new(r"error: builder for '(/nix/store/.*?\.drv)' failed").unwrap();
-    
-    // Match common error patterns in build logs
-    static ref RE_MISSING_PACKAGE: Regex = Regex::new(r"package.*not found").unwrap();
-    static ref RE_PERMISSION_ERROR: Regex = Regex::new(r"permission denied").unwrap();
-    static ref RE_NETWORK_ERROR: Regex = Regex::new(r"(network|connection|timeout)").unwrap();
+
+    // Bundled known-failure patterns (regex -> explanation/fix), parsed once from
+    // `assets/known_failures.toml`. See `scan_log_for_recommendations`.
+    static ref PARSED_BUNDLED_KNOWN_FAILURES: Vec<KnownFailurePattern> =
+        toml::from_str::<KnownFailuresFile>(BUNDLED_KNOWN_FAILURES)
+            .expect("assets/known_failures.toml is malformed")
+            .pattern;
 }
 
 /// Parses Nix evaluation errors from stderr.
@@ -100,6 +103,84 @@ pub fn fetch_nix_trace(flake_ref: &str, attribute_path_slice: &[String], verbose
     }
 }
 
+/// A single frame parsed from a `nix --show-trace` trace blob: the "while evaluating/calling
+/// …" description, plus the `file:line:column` location on the following line, if present.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceFrame {
+    pub description: String,
+    pub location: Option<String>,
+}
+
+lazy_static! {
+    // Trace frame lines look like: "       … while evaluating the attribute 'foo'"
+    static ref RE_TRACE_FRAME: Regex = Regex::new(r"…\s*(.*)").unwrap();
+    // Followed by a location line: "         at /path/to/file.nix:12:3:"
+    static ref RE_TRACE_LOCATION: Regex = Regex::new(r"^\s*at\s+(.+):(\d+):(\d+):").unwrap();
+}
+
+/// Parses the frame descriptions and locations out of a raw `nix --show-trace` blob, in
+/// outermost-first order (matching nix's own output order).
+pub fn parse_trace_frames(raw_trace: &str) -> Vec<TraceFrame> {
+    let mut frames = Vec::new();
+    let mut lines = raw_trace.lines().peekable();
+    while let Some(line) = lines.next() {
+        if let Some(caps) = RE_TRACE_FRAME.captures(line) {
+            let description = caps[1].trim().to_string();
+            if description.is_empty() {
+                continue;
+            }
+            let location = lines
+                .peek()
+                .and_then(|next| RE_TRACE_LOCATION.captures(next))
+                .map(|c| format!("{}:{}:{}", &c[1], &c[2], &c[3]));
+            frames.push(TraceFrame { description, location });
+        }
+    }
+    frames
+}
+
+/// Renders a `nix --show-trace` blob as an indented, colorized tree instead of the raw
+/// wall-of-text nix prints. Consecutive duplicate frames (the same "while evaluating …" firing
+/// over and over, e.g. inside a `map`) are collapsed into one line with a "(×N)" count, and the
+/// innermost frame — the one closest to the actual error — is highlighted.
+///
+/// Falls back to the raw trace unchanged if no frames could be parsed out of it.
+pub fn format_trace_tree(raw_trace: &str) -> String {
+    let frames = parse_trace_frames(raw_trace);
+    if frames.is_empty() {
+        return raw_trace.to_string();
+    }
+
+    let mut collapsed: Vec<(TraceFrame, usize)> = Vec::new();
+    for frame in frames {
+        match collapsed.last_mut() {
+            Some((last, count)) if *last == frame => *count += 1,
+            _ => collapsed.push((frame, 1)),
+        }
+    }
+
+    let last_index = collapsed.len() - 1;
+    let mut out = String::new();
+    for (i, (frame, count)) in collapsed.iter().enumerate() {
+        let indent = "  ".repeat(i);
+        let innermost = i == last_index;
+        let branch = if innermost { "└─" } else { "├─" };
+        let count_suffix = if *count > 1 { format!(" (×{count})") } else { String::new() };
+
+        let description = if innermost {
+            Colors::error(&frame.description)
+        } else {
+            frame.description.clone()
+        };
+        out.push_str(&format!("{indent}{branch} {description}{count_suffix}\n"));
+
+        if let Some(location) = &frame.location {
+            out.push_str(&format!("{indent}     {} {}\n", Colors::info("at"), location));
+        }
+    }
+    out
+}
+
 /// Finds failed derivation paths in Nix build stderr.
 ///
 /// # Arguments
@@ -147,54 +228,131 @@ pub fn fetch_and_format_nix_log(drv_path: &str, verbose_count: u8) -> Result<Str
     ))
 }
 
-/// Scans a log for common issues and provides recommendations.
+/// Known-failure patterns bundled with `ng` itself, on top of whatever the user adds via
+/// `known_failures.extra_patterns` in `ng.toml`. See `scan_log_for_recommendations`.
+pub const BUNDLED_KNOWN_FAILURES: &str = include_str!("../assets/known_failures.toml");
+
+/// One `[[pattern]]` entry from `assets/known_failures.toml` (or a user's
+/// `[[known_failures.extra_patterns]]` in `ng.toml`).
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct KnownFailurePattern {
+    /// Short, stable identifier, e.g. "hash-mismatch". Only shown in the warning logged when
+    /// `regex` fails to compile; otherwise it just keeps entries self-documenting.
+    pub id: String,
+    /// Regex matched against the log content; matched anywhere, not anchored.
+    pub regex: String,
+    /// Shown when `regex` matches.
+    pub explanation: String,
+    /// Shown alongside `explanation` when present.
+    pub fix: Option<String>,
+}
+
+/// Shape of `assets/known_failures.toml`: a bare `[[pattern]]` array at the top level.
+#[derive(Debug, Deserialize)]
+struct KnownFailuresFile {
+    pattern: Vec<KnownFailurePattern>,
+}
+
+/// Scans a log for known failure patterns (bundled + user-extended via
+/// `known_failures.extra_patterns` in `ng.toml`) and returns a recommendation per match.
 ///
 /// # Arguments
 ///
 /// * `log_content` - The log content to scan.
+/// * `extra_patterns` - Additional patterns from `ng.toml`, checked after the bundled ones.
 ///
 /// # Returns
 ///
-/// * `Vec<String>` - A list of recommendations.
-pub fn scan_log_for_recommendations(log_content: &str) -> Vec<String> {
+/// * `Vec<String>` - A list of recommendations. Falls back to a generic pair of suggestions if
+///   nothing in the database matches.
+pub fn scan_log_for_recommendations(log_content: &str, extra_patterns: &[KnownFailurePattern]) -> Vec<String> {
     let mut recommendations = Vec::new();
-    
-    // Check for common error patterns
-    if RE_MISSING_PACKAGE.is_match(log_content) {
-        recommendations.push("A package dependency appears to be missing. Check your inputs and package names.".to_string());
-    }
-    
-    if RE_PERMISSION_ERROR.is_match(log_content) {
-        recommendations.push("Permission errors detected. Check file permissions or if you need elevated privileges.".to_string());
-    }
-    
-    if RE_NETWORK_ERROR.is_match(log_content) {
-        recommendations.push("Network-related errors detected. Check your internet connection or proxy settings.".to_string());
-    }
-    
-    // Add general recommendations
-    if log_content.contains("error: attribute") {
-        recommendations.push("An attribute error was detected. Verify that all attribute paths exist in your configuration.".to_string());
-    }
-    
-    if log_content.contains("syntax error") {
-        recommendations.push("Syntax errors detected. Check for missing semicolons, brackets, or other syntax issues.".to_string());
+
+    for known_failure in PARSED_BUNDLED_KNOWN_FAILURES.iter().chain(extra_patterns) {
+        let matched = match Regex::new(&known_failure.regex) {
+            Ok(re) => re.is_match(log_content),
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping known-failure pattern '{}': invalid regex '{}': {}",
+                    known_failure.id,
+                    known_failure.regex,
+                    e
+                );
+                continue;
+            }
+        };
+
+        if matched {
+            recommendations.push(match &known_failure.fix {
+                Some(fix) => format!("{} Fix: {}", known_failure.explanation, fix),
+                None => known_failure.explanation.clone(),
+            });
+        }
     }
-    
+
     // If no specific recommendations, add a generic one
     if recommendations.is_empty() {
         recommendations.push("Review the full log for specific error details.".to_string());
         recommendations.push("Run 'ng doctor' to check your Nix installation.".to_string());
     }
-    
+
     recommendations
 }
 
 
+fn severity_str(severity: crate::nix_analyzer::NgSeverity) -> String {
+    match severity {
+        crate::nix_analyzer::NgSeverity::Error => "Error".red().bold().to_string(),
+        crate::nix_analyzer::NgSeverity::Warning => "Warning".yellow().bold().to_string(),
+    }
+}
+
+fn diagnostic_location(diag: &NgDiagnostic) -> String {
+    if let (Some(line), Some(col)) = (diag.line, diag.column) {
+        format!("{}:{}:{}", diag.file_path.display(), line, col)
+    } else if let Some(line) = diag.line {
+        format!("{}:{}", diag.file_path.display(), line)
+    } else {
+        format!("{}", diag.file_path.display())
+    }
+}
+
+/// Diagnostics sharing the same message, collapsed into one entry with every location they
+/// occurred at (e.g. the same formatter warning repeated across dozens of files).
+struct DiagnosticGroup<'a> {
+    message: &'a str,
+    severity: crate::nix_analyzer::NgSeverity,
+    locations: Vec<String>,
+}
+
+/// Groups `diagnostics` by exact message text, preserving first-seen order.
+fn group_diagnostics_by_message(diagnostics: &[NgDiagnostic]) -> Vec<DiagnosticGroup<'_>> {
+    let mut groups: Vec<DiagnosticGroup> = Vec::new();
+    for diag in diagnostics {
+        match groups.iter_mut().find(|g| g.message == diag.message) {
+            Some(group) => group.locations.push(diagnostic_location(diag)),
+            None => groups.push(DiagnosticGroup {
+                message: &diag.message,
+                severity: diag.severity,
+                locations: vec![diagnostic_location(diag)],
+            }),
+        }
+    }
+    groups
+}
+
+/// Prints a diagnostic report for `diagnostics`, found by `check_name` (e.g. "External
+/// Linters").
+///
+/// When `group` is `true` (the default; `--no-group` sets it to `false`), diagnostics sharing
+/// the exact same message are collapsed into a single entry with a count and the list of
+/// affected locations, instead of printing one near-identical line per occurrence.
 pub fn report_ng_diagnostics(
     check_name: &str,
     diagnostics: &[NgDiagnostic],
     _analyzer_context: Option<&NixAnalysisContext>, // Analyzer context might be used for more details later
+    group: bool,
 ) {
     if diagnostics.is_empty() {
         return;
@@ -202,29 +360,39 @@ pub fn report_ng_diagnostics(
     eprintln!(); // Add a blank line for spacing, use eprintln for errors
     eprintln!("{}", header(&format!("{} Found Issues:", check_name)).underline());
 
-    for diag in diagnostics {
-        let severity_str = match diag.severity {
-            crate::nix_analyzer::NgSeverity::Error => "Error".red().bold().to_string(),
-            crate::nix_analyzer::NgSeverity::Warning => "Warning".yellow().bold().to_string(),
-        };
-        
-        let location_str = if let (Some(line), Some(col)) = (diag.line, diag.column) {
-            format!("{}:{}:{}", diag.file_path.display(), line, col)
-        } else if let Some(line) = diag.line {
-            format!("{}:{}", diag.file_path.display(), line)
-        } else {
-            format!("{}", diag.file_path.display())
-        };
-
-        let output_str = format!(
-            "  [{}] {} - {}",
-            severity_str,
-            location_str,
-            diag.message
-        );
-        eprintln!("{}", output_str);
-        // TODO: Print code snippet (requires file content access via NgDiagnostic or context)
+    if group {
+        for diag_group in group_diagnostics_by_message(diagnostics) {
+            let count = diag_group.locations.len();
+            if count == 1 {
+                eprintln!(
+                    "  [{}] {} - {}",
+                    severity_str(diag_group.severity),
+                    diag_group.locations[0],
+                    diag_group.message
+                );
+            } else {
+                eprintln!(
+                    "  [{}] {} ({} occurrences)",
+                    severity_str(diag_group.severity),
+                    diag_group.message,
+                    count
+                );
+                for location in &diag_group.locations {
+                    eprintln!("    - {}", location);
+                }
+            }
+        }
+    } else {
+        for diag in diagnostics {
+            eprintln!(
+                "  [{}] {} - {}",
+                severity_str(diag.severity),
+                diagnostic_location(diag),
+                diag.message
+            );
+        }
     }
+    // TODO: Print code snippet (requires file content access via NgDiagnostic or context)
     eprintln!(); // Add a blank line for spacing
 }
 /// Reports a failure with structured error information.
@@ -454,42 +622,42 @@ mod tests {
     #[test]
     fn test_scan_log_missing_package() {
         let log = "some log output... package 'openssl' not found ... more logs";
-        let recs = scan_log_for_recommendations(log);
+        let recs = scan_log_for_recommendations(log, &[]);
         assert!(recs.iter().any(|r| r.contains("package dependency appears to be missing")));
     }
 
     #[test]
     fn test_scan_log_permission_denied() {
         let log = "Error: permission denied while trying to access /nix/store";
-        let recs = scan_log_for_recommendations(log);
+        let recs = scan_log_for_recommendations(log, &[]);
         assert!(recs.iter().any(|r| r.contains("Permission errors detected")));
     }
 
     #[test]
     fn test_scan_log_network_error() {
         let log = "failed to download ... connection timed out ... blah";
-        let recs = scan_log_for_recommendations(log);
+        let recs = scan_log_for_recommendations(log, &[]);
         assert!(recs.iter().any(|r| r.contains("Network-related errors detected")));
     }
 
     #[test]
     fn test_scan_log_attribute_error() {
         let log = "error: attribute 'system' missing at /flake.nix:10:1";
-        let recs = scan_log_for_recommendations(log);
+        let recs = scan_log_for_recommendations(log, &[]);
         assert!(recs.iter().any(|r| r.contains("attribute error was detected")));
     }
 
     #[test]
     fn test_scan_log_syntax_error_keyword() {
         let log = "there is a syntax error in your configuration";
-        let recs = scan_log_for_recommendations(log);
+        let recs = scan_log_for_recommendations(log, &[]);
         assert!(recs.iter().any(|r| r.contains("Syntax errors detected")));
     }
 
     #[test]
     fn test_scan_log_multiple_issues() {
         let log = "package not found, also error: attribute 'foo' missing, and permission denied";
-        let recs = scan_log_for_recommendations(log);
+        let recs = scan_log_for_recommendations(log, &[]);
         assert_eq!(recs.len(), 3); // Missing package, attribute error, permission error
         assert!(recs.iter().any(|r| r.contains("package dependency")));
         assert!(recs.iter().any(|r| r.contains("attribute error")));
@@ -499,7 +667,7 @@ mod tests {
     #[test]
     fn test_scan_log_no_specific_issues() {
         let log = "this log has no specific keywords, just a general failure notice.";
-        let recs = scan_log_for_recommendations(log);
+        let recs = scan_log_for_recommendations(log, &[]);
         assert!(recs.iter().any(|r| r.contains("Review the full log")));
         assert!(recs.iter().any(|r| r.contains("Run 'ng doctor'")));
         assert_eq!(recs.len(), 2);
@@ -508,12 +676,39 @@ mod tests {
     #[test]
     fn test_scan_log_empty_log() {
         let log = "";
-        let recs = scan_log_for_recommendations(log);
+        let recs = scan_log_for_recommendations(log, &[]);
         assert!(recs.iter().any(|r| r.contains("Review the full log")));
         assert!(recs.iter().any(|r| r.contains("Run 'ng doctor'")));
         assert_eq!(recs.len(), 2);
     }
 
+    #[test]
+    fn test_scan_log_hash_mismatch() {
+        let log = "error: hash mismatch in fixed-output derivation '/nix/store/xxx-src.drv':\n  wanted: sha256:aaa\n  got:    sha256:bbb";
+        let recs = scan_log_for_recommendations(log, &[]);
+        assert!(recs.iter().any(|r| r.contains("pinned hash") && r.contains("Fix:")));
+    }
+
+    #[test]
+    fn test_scan_log_missing_allow_unfree() {
+        let log = "error: Package 'unrar-7.0.9' has an unfree license, refusing to evaluate";
+        let recs = scan_log_for_recommendations(log, &[]);
+        assert!(recs.iter().any(|r| r.contains("allowUnfree") || r.contains("unfree license")));
+    }
+
+    #[test]
+    fn test_scan_log_extra_pattern_from_config() {
+        let log = "custom failure marker: WIDGET_EXPLODED";
+        let extra = vec![KnownFailurePattern {
+            id: "widget-exploded".to_string(),
+            regex: "WIDGET_EXPLODED".to_string(),
+            explanation: "The widget exploded.".to_string(),
+            fix: None,
+        }];
+        let recs = scan_log_for_recommendations(log, &extra);
+        assert!(recs.iter().any(|r| r.contains("The widget exploded.")));
+    }
+
     #[test]
     fn test_enhance_syntax_error_output_typical() {
         let error_details = "Error in /path/to/my/file.nix: \nerror: syntax error, unexpected ID, expecting SEMI or INHERIT at /path/to/my/file.nix:10:5\n\n   10|     some_attr = value: another_attr;
@@ -737,4 +932,78 @@ mod tests {
         assert!(recs.iter().any(|r| r.contains("Nix formatter")));
         assert_eq!(recs.len(), 2);
     }
+
+    #[test]
+    fn test_parse_trace_frames_basic() {
+        let raw = [
+            "error: infinite recursion encountered",
+            "       … while evaluating the attribute 'system.build.toplevel'",
+            "         at /etc/nixos/flake.nix:10:5:",
+            "       … while calling the 'toString' builtin",
+            "         at /etc/nixos/configuration.nix:3:1:",
+        ]
+        .join("\n");
+        let frames = parse_trace_frames(&raw);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].description, "while evaluating the attribute 'system.build.toplevel'");
+        assert_eq!(frames[0].location.as_deref(), Some("/etc/nixos/flake.nix:10:5"));
+        assert_eq!(frames[1].description, "while calling the 'toString' builtin");
+        assert_eq!(frames[1].location.as_deref(), Some("/etc/nixos/configuration.nix:3:1"));
+    }
+
+    #[test]
+    fn test_format_trace_tree_collapses_duplicates() {
+        let raw = [
+            "       … while evaluating a branch condition",
+            "         at /a.nix:1:1:",
+            "       … while evaluating a branch condition",
+            "         at /a.nix:1:1:",
+            "       … while evaluating the final result",
+            "         at /b.nix:2:2:",
+        ]
+        .join("\n");
+        let tree = format_trace_tree(&raw);
+        assert!(tree.contains("(×2)"));
+        assert!(tree.contains("while evaluating the final result"));
+    }
+
+    #[test]
+    fn test_format_trace_tree_falls_back_on_unparseable_input() {
+        let raw = "some completely unrelated text with no trace frames";
+        assert_eq!(format_trace_tree(raw), raw);
+    }
+
+    fn diag(path: &str, message: &str) -> NgDiagnostic {
+        NgDiagnostic {
+            tool_name: None,
+            file_path: std::path::PathBuf::from(path),
+            line: Some(1),
+            column: Some(1),
+            message: message.to_string(),
+            severity: crate::nix_analyzer::NgSeverity::Warning,
+        }
+    }
+
+    #[test]
+    fn test_group_diagnostics_by_message_collapses_repeats() {
+        let diagnostics = vec![
+            diag("a.nix", "trailing whitespace"),
+            diag("b.nix", "trailing whitespace"),
+            diag("c.nix", "unused binding 'x'"),
+        ];
+        let groups = group_diagnostics_by_message(&diagnostics);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].message, "trailing whitespace");
+        assert_eq!(groups[0].locations.len(), 2);
+        assert_eq!(groups[1].message, "unused binding 'x'");
+        assert_eq!(groups[1].locations.len(), 1);
+    }
+
+    #[test]
+    fn test_group_diagnostics_by_message_preserves_distinct_messages() {
+        let diagnostics = vec![diag("a.nix", "one"), diag("b.nix", "two")];
+        let groups = group_diagnostics_by_message(&diagnostics);
+        assert_eq!(groups.len(), 2);
+        assert!(groups.iter().all(|g| g.locations.len() == 1));
+    }
 }
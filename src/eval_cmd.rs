@@ -0,0 +1,65 @@
+//! `ng eval`: a thin, pretty-printing wrapper around `nix eval` that routes failures through
+//! [`crate::error_handler`] for a structured trace instead of raw nix stderr.
+
+use color_eyre::eyre::{bail, WrapErr};
+
+use crate::commands::Command;
+use crate::error_handler;
+use crate::installable::{parse_attribute, Installable};
+use crate::interface::EvalArgs;
+use crate::Result;
+
+impl EvalArgs {
+    pub fn run(&self, verbose_count: u8) -> Result<()> {
+        let mut installable = self.installable.clone();
+        if let Some(attr) = &self.attr {
+            match &mut installable {
+                Installable::Flake { attribute, .. }
+                | Installable::File { attribute, .. }
+                | Installable::Expression { attribute, .. } => {
+                    attribute.extend(parse_attribute(attr));
+                }
+                Installable::Store { .. } => bail!("--attr cannot be used with a store path installable"),
+            }
+        }
+
+        let output = Command::new("nix")
+            .arg("eval")
+            .arg(if self.raw { "--raw" } else { "--json" })
+            .arg(installable.to_args().join(" "))
+            .add_verbosity_flags(verbose_count)
+            .run_capture_output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+
+            if let Installable::Flake { reference, attribute } = &installable {
+                if let Ok(trace) = error_handler::fetch_nix_trace(reference, attribute, verbose_count) {
+                    eprint!("{}", error_handler::format_trace_tree(&trace));
+                }
+            }
+
+            if let Some((message, file, line, column)) = error_handler::parse_nix_eval_error(&stderr) {
+                bail!("{} at {}:{}:{}", message, file, line, column);
+            }
+            bail!("nix eval failed:\n{}", stderr);
+        }
+
+        if self.raw {
+            print!("{}", String::from_utf8_lossy(&output.stdout));
+            return Ok(());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let value: serde_json::Value = serde_json::from_str(stdout.trim())
+            .wrap_err("Failed to parse `nix eval --json` output")?;
+
+        if self.json {
+            println!("{}", serde_json::to_string(&value)?);
+        } else {
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        }
+
+        Ok(())
+    }
+}
@@ -1,8 +1,10 @@
 // src/format_logic.rs
+use crate::config::NgConfig;
 use crate::interface::FormatArgs;
 use crate::Result;
 use crate::ui_style;
-use std::path::PathBuf;
+use color_eyre::eyre::eyre;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use walkdir::WalkDir;
 
@@ -16,6 +18,11 @@ pub fn run_formatting(args: &FormatArgs, verbose_count: u8) -> Result<()> {
         );
     }
 
+    let config = NgConfig::load();
+    if config.pre_flight.format.tool.as_deref() == Some("treefmt") {
+        return run_treefmt(args, verbose_count);
+    }
+
     // 1. Determine the absolute path to format
     let path_to_format = PathBuf::from(&args.path);
     let absolute_path = if path_to_format.is_absolute() {
@@ -30,7 +37,7 @@ pub fn run_formatting(args: &FormatArgs, verbose_count: u8) -> Result<()> {
 
     // 2. Find all .nix files in the path
     let mut files_to_format = Vec::new();
-    
+
     if absolute_path.is_file() && absolute_path.extension().map_or(false, |ext| ext == "nix") {
         files_to_format.push(absolute_path.clone());
     } else if absolute_path.is_dir() {
@@ -44,6 +51,15 @@ pub fn run_formatting(args: &FormatArgs, verbose_count: u8) -> Result<()> {
         return Ok(());
     }
 
+    if args.changed_only {
+        match git_changed_nix_files(&absolute_path) {
+            Ok(changed) => files_to_format.retain(|f| changed.contains(f)),
+            Err(e) => {
+                println!("Failed to determine changed files via git: {e}. Falling back to all files.");
+            }
+        }
+    }
+
     if files_to_format.is_empty() {
         println!("No Nix files found in '{}'", args.path);
         return Ok(());
@@ -62,15 +78,15 @@ pub fn run_formatting(args: &FormatArgs, verbose_count: u8) -> Result<()> {
 
         // Use nixfmt-rfc-style as the formatter
         let mut cmd = Command::new("nixfmt-rfc-style");
-        
+
         if !args.apply {
             cmd.arg("--check");
         }
-        
+
         cmd.arg(file_path);
-        
+
         let output = cmd.output();
-        
+
         match output {
             Ok(output) => {
                 if output.status.success() {
@@ -85,7 +101,16 @@ pub fn run_formatting(args: &FormatArgs, verbose_count: u8) -> Result<()> {
                 } else {
                     had_errors = true;
                     if !args.apply {
-                        println!("✗ {} needs formatting", file_path.display());
+                        if args.check {
+                            println!("--- {}", file_path.display());
+                            match crate::util::unified_diff_after_format(file_path, "nixfmt-rfc-style") {
+                                Ok(diff) if !diff.is_empty() => print!("{diff}"),
+                                Ok(_) => println!("(formatter reported changes but produced no diff output)"),
+                                Err(e) => println!("(failed to compute diff: {e})"),
+                            }
+                        } else {
+                            println!("✗ {} needs formatting", file_path.display());
+                        }
                         if verbose_count > 1 {
                             println!("{}", String::from_utf8_lossy(&output.stderr));
                         }
@@ -122,3 +147,79 @@ pub fn run_formatting(args: &FormatArgs, verbose_count: u8) -> Result<()> {
 
     Ok(())
 }
+
+/// Runs `treefmt` on `args.path`, delegating file selection to `treefmt.toml` instead of the
+/// `.nix`-only `WalkDir` scan above, since a treefmt setup commonly formats non-Nix files too.
+fn run_treefmt(args: &FormatArgs, verbose_count: u8) -> Result<()> {
+    let mut cmd = Command::new("treefmt");
+    if !args.apply {
+        cmd.arg("--fail-on-change");
+    }
+    cmd.arg(&args.path);
+
+    let output = cmd.output();
+
+    match output {
+        Ok(output) => {
+            if verbose_count > 1 {
+                print!("{}", String::from_utf8_lossy(&output.stdout));
+                eprint!("{}", String::from_utf8_lossy(&output.stderr));
+            }
+            if output.status.success() {
+                let message = if args.apply {
+                    "Formatted with treefmt"
+                } else {
+                    "All files are formatted correctly"
+                };
+                println!("{}", ui_style::success_message("Format", message));
+                Ok(())
+            } else if !args.apply {
+                println!("Some files need formatting. Run with --apply to format them.");
+                std::process::exit(1);
+            } else {
+                println!("treefmt reported errors while formatting:");
+                println!("{}", String::from_utf8_lossy(&output.stderr));
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            println!("Error executing treefmt: {}", e);
+            println!("Make sure treefmt is installed and in your PATH");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Lists absolute paths of `.nix` files with uncommitted changes against `HEAD` (staged or not)
+/// anywhere in the git repository containing `root`, via `git diff --name-only`. Used for
+/// `--changed-only`; the result is not filtered to paths under `root`.
+fn git_changed_nix_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let repo_root = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(root)
+        .output()?;
+    if !repo_root.status.success() {
+        return Err(eyre!(
+            "not inside a git repository: {}",
+            String::from_utf8_lossy(&repo_root.stderr).trim()
+        ));
+    }
+    let repo_root = PathBuf::from(String::from_utf8_lossy(&repo_root.stdout).trim());
+
+    let output = Command::new("git")
+        .args(["diff", "--name-only", "HEAD"])
+        .current_dir(&repo_root)
+        .output()?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "git diff failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.ends_with(".nix"))
+        .map(|line| repo_root.join(line))
+        .collect())
+}
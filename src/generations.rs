@@ -4,7 +4,9 @@ use std::path::{Path, PathBuf};
 use std::process;
 
 use chrono::{DateTime, Local, TimeZone, Utc};
-use tracing::debug;
+use clap::ValueEnum;
+use regex::Regex;
+use tracing::{debug, info, warn};
 
 #[derive(Debug)]
 pub struct GenerationInfo {
@@ -31,6 +33,322 @@ pub struct GenerationInfo {
     pub current: bool,
 }
 
+/// Field to sort a generation listing by, selected via `ng os info --sort` or `ng home generations --sort`.
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Default)]
+pub enum GenerationSortField {
+    /// Generation number (default)
+    #[default]
+    Number,
+    /// Build date
+    Date,
+}
+
+/// Finds every generation of `profile` (e.g. `/nix/var/nix/profiles/system`) and describes it,
+/// skipping any generation whose metadata can't be read.
+pub fn list_generations(profile: &Path) -> Vec<GenerationInfo> {
+    let dir = match profile.parent() {
+        Some(dir) => dir,
+        None => return Vec::new(),
+    };
+    let profile_name = profile
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("system");
+    let link_regex = match Regex::new(&format!(r"^{}-(\d+)-link$", regex::escape(profile_name))) {
+        Ok(r) => r,
+        Err(e) => {
+            warn!("Failed to build generation regex for {profile_name}: {e}");
+            return Vec::new();
+        }
+    };
+
+    let read_dir = match dir.read_dir() {
+        Ok(read_dir) => read_dir,
+        Err(e) => {
+            warn!("Failed to read profiles directory {}: {}", dir.display(), e);
+            return Vec::new();
+        }
+    };
+
+    read_dir
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|s| s.to_str())
+                .map(|name| link_regex.is_match(name))
+                .unwrap_or(false)
+        })
+        .filter_map(|generation_dir| describe(&generation_dir, profile))
+        .collect()
+}
+
+/// Path to a specific generation's profile symlink (e.g. `/nix/var/nix/profiles/system-19-link`
+/// for `profile = /nix/var/nix/profiles/system, number = 19`), regardless of whether it exists.
+pub fn generation_link_path(profile: &Path, number: u32) -> PathBuf {
+    let dir = profile.parent().unwrap_or_else(|| Path::new("."));
+    let profile_name = profile
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("system");
+    dir.join(format!("{profile_name}-{number}-link"))
+}
+
+/// Rolls back (or forward) to a specific generation, finer-grained than `nixos-rebuild`'s
+/// built-in "previous generation" rollback (which can only step back one): points `profile` at
+/// generation `number`'s link with `nix-env --profile --set`, then runs that generation's own
+/// `switch-to-configuration switch` so the running system matches it immediately.
+pub fn switch_to_generation(
+    profile: &Path,
+    number: u32,
+    verbose_count: u8,
+    config: &crate::config::NgConfig,
+) -> crate::Result<()> {
+    use color_eyre::eyre::{bail, WrapErr};
+
+    let link = generation_link_path(profile, number);
+    if !link.exists() {
+        bail!(
+            "Generation {number} not found at {} (profile: {})",
+            link.display(),
+            profile.display()
+        );
+    }
+
+    if !config.elevation.may_elevate("profile_update") {
+        bail!(
+            "Setting {} to generation {number} requires sudo, but the \"profile_update\" stage \
+             is not in elevation.allow_stages in ng.toml.",
+            profile.display()
+        );
+    }
+    crate::commands::Command::new("nix-env")
+        .arg("--profile")
+        .arg(profile)
+        .arg("--set")
+        .arg(&link)
+        .elevate(true)
+        .message(format!("Setting {} to generation {number}", profile.display()))
+        .add_verbosity_flags(verbose_count)
+        .run()
+        .wrap_err_with(|| format!("Failed to point {} at generation {number}", profile.display()))?;
+
+    let switch_script = link.join("bin/switch-to-configuration");
+    if !switch_script.exists() {
+        bail!(
+            "Activation script 'bin/switch-to-configuration' not found in generation {number} at {}",
+            link.display()
+        );
+    }
+    if !config.elevation.may_elevate("activation") {
+        bail!(
+            "Activating generation {number} requires sudo, but the \"activation\" stage is not \
+             in elevation.allow_stages in ng.toml."
+        );
+    }
+    crate::commands::Command::new(switch_script)
+        .arg("switch")
+        .elevate(true)
+        .message(format!("Activating generation {number}"))
+        .run()
+        .wrap_err_with(|| format!("Failed to activate generation {number}"))?;
+
+    info!("Switched {} to generation {number}", profile.display());
+    Ok(())
+}
+
+/// Deletes generations of `profile` last modified more than `older_than` ago, via `nix-env
+/// --delete-generations`. Never considers the current generation a candidate. Prints the
+/// candidate list and asks for confirmation unless `yes` or `dry_run` is set.
+pub fn prune_generations(
+    profile: &Path,
+    older_than: std::time::Duration,
+    dry_run: bool,
+    yes: bool,
+    verbose_count: u8,
+    config: &crate::config::NgConfig,
+) -> crate::Result<()> {
+    use color_eyre::eyre::bail;
+
+    let found = list_generations(profile);
+    let now = std::time::SystemTime::now();
+    let mut to_prune = Vec::new();
+
+    for generation in &found {
+        if generation.current {
+            continue;
+        }
+        let Ok(number) = generation.number.parse::<u32>() else {
+            continue;
+        };
+        let link = generation_link_path(profile, number);
+        let modified = match link.symlink_metadata().and_then(|m| m.modified()) {
+            Ok(m) => m,
+            Err(err) => {
+                warn!(?link, ?err, "Failed to read generation metadata, skipping");
+                continue;
+            }
+        };
+        match now.duration_since(modified) {
+            Ok(age) if age >= older_than => to_prune.push(number),
+            _ => {}
+        }
+    }
+
+    if to_prune.is_empty() {
+        info!(
+            "No generations of {} older than the given threshold were found",
+            profile.display()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "The following generations of {} will be deleted:",
+        profile.display()
+    );
+    for number in &to_prune {
+        println!("  - {number}");
+    }
+
+    if dry_run {
+        info!("Dry run: not deleting anything");
+        return Ok(());
+    }
+
+    if !yes && !dialoguer::Confirm::new().with_prompt("Proceed?").default(false).interact()? {
+        bail!("User rejected the prune plan");
+    }
+
+    if !config.elevation.may_elevate("profile_update") {
+        bail!(
+            "Pruning generations of {} requires sudo, but the \"profile_update\" stage is not \
+             in elevation.allow_stages in ng.toml.",
+            profile.display()
+        );
+    }
+
+    crate::commands::Command::new("nix-env")
+        .arg("--profile")
+        .arg(profile)
+        .arg("--delete-generations")
+        .args(to_prune.iter().map(u32::to_string))
+        .elevate(true)
+        .message(format!(
+            "Deleting generation(s) {} of {}",
+            to_prune.iter().map(u32::to_string).collect::<Vec<_>>().join(", "),
+            profile.display()
+        ))
+        .add_verbosity_flags(verbose_count)
+        .run()?;
+
+    info!(
+        "Pruned {} generation(s) from {}",
+        to_prune.len(),
+        profile.display()
+    );
+    Ok(())
+}
+
+/// Sorts `generations` by `sort` (ascending), then reverses the order when `reverse` is set,
+/// and drops any generation whose version/kernel/revision/specialisations don't contain
+/// `filter` (case-insensitive substring match).
+pub fn sort_and_filter(
+    mut generations: Vec<GenerationInfo>,
+    sort: GenerationSortField,
+    reverse: bool,
+    filter: Option<&str>,
+) -> Vec<GenerationInfo> {
+    if let Some(needle) = filter {
+        let needle = needle.to_lowercase();
+        generations.retain(|gen| {
+            gen.nixos_version.to_lowercase().contains(&needle)
+                || gen.kernel_version.to_lowercase().contains(&needle)
+                || gen.configuration_revision.to_lowercase().contains(&needle)
+                || gen
+                    .specialisations
+                    .iter()
+                    .any(|s| s.to_lowercase().contains(&needle))
+        });
+    }
+
+    match sort {
+        GenerationSortField::Number => {
+            generations.sort_by_key(|gen| gen.number.parse::<u64>().unwrap_or(0))
+        }
+        GenerationSortField::Date => generations.sort_by(|a, b| a.date.cmp(&b.date)),
+    }
+
+    if reverse {
+        generations.reverse();
+    }
+
+    generations
+}
+
+/// Rich, at-a-glance summary of the currently running system, shown by `ng os info`.
+#[derive(Debug)]
+pub struct SystemStatus {
+    pub generation_number: String,
+    pub nixos_version: String,
+    pub kernel_version: String,
+    pub active_specialisation: String,
+    pub running_revision: String,
+    pub repo_head_revision: String,
+    pub uptime: String,
+}
+
+/// Gathers a [`SystemStatus`] for `profile`'s current generation, comparing its
+/// `configurationRevision` against the git HEAD of `project_root`.
+pub fn system_status(profile: &Path, project_root: &Path) -> SystemStatus {
+    let current = list_generations(profile).into_iter().find(|gen| gen.current);
+
+    let active_specialisation = std::env::var("NIXOS_SPECIALISATION")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "(base)".to_string());
+
+    let repo_head_revision = crate::check_git::git_command()
+        .current_dir(project_root)
+        .args(["rev-parse", "--short", "HEAD"])
+        .run_capture()
+        .ok()
+        .flatten()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let uptime = read_uptime().unwrap_or_else(|| "Unknown".to_string());
+
+    match current {
+        Some(gen) => SystemStatus {
+            generation_number: gen.number,
+            nixos_version: gen.nixos_version,
+            kernel_version: gen.kernel_version,
+            active_specialisation,
+            running_revision: gen.configuration_revision,
+            repo_head_revision,
+            uptime,
+        },
+        None => SystemStatus {
+            generation_number: "Unknown".to_string(),
+            nixos_version: "Unknown".to_string(),
+            kernel_version: "Unknown".to_string(),
+            active_specialisation,
+            running_revision: "Unknown".to_string(),
+            repo_head_revision,
+            uptime,
+        },
+    }
+}
+
+/// Reads `/proc/uptime` and formats the system uptime as a human-readable duration.
+fn read_uptime() -> Option<String> {
+    let contents = fs::read_to_string("/proc/uptime").ok()?;
+    let seconds: f64 = contents.split_whitespace().next()?.parse().ok()?;
+    Some(humantime::format_duration(std::time::Duration::from_secs(seconds as u64)).to_string())
+}
+
 pub fn from_dir(generation_dir: &Path) -> Option<u64> {
     generation_dir
         .file_name()
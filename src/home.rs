@@ -1,5 +1,5 @@
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use color_eyre::eyre::bail;
 use color_eyre::Result;
@@ -8,7 +8,7 @@ use tracing::{debug, info, warn};
 use crate::commands;
 use crate::commands::Command;
 use crate::installable::Installable;
-use crate::interface::{self, HomeRebuildArgs, HomeReplArgs, HomeSubcommand};
+use crate::interface::{self, HomeGenerationsArgs, HomeRebuildArgs, HomeReplArgs, HomeSubcommand};
 use crate::update::update;
 use crate::util::get_hostname;
 
@@ -18,16 +18,104 @@ impl interface::HomeArgs {
         match self.subcommand {
             HomeSubcommand::Switch(args) => args.rebuild(Switch, verbose_count),
             HomeSubcommand::Build(args) => {
-                if args.common.common.ask || args.common.common.dry {
+                if args.common.common.asks_anything() || args.common.common.dry {
                     warn!("`--ask` and `--dry` have no effect for `ng home build`");
                 }
                 args.rebuild(Build, verbose_count)
             }
             HomeSubcommand::Repl(args) => args.run(verbose_count),
+            HomeSubcommand::Generations(args) => args.run(),
         }
     }
 }
 
+/// The home-manager profile paths probed by `rebuild()` below, in the same preference order.
+pub(crate) fn default_profile() -> Option<PathBuf> {
+    prev_generation_profile(&env::var("USER").ok()?, &PathBuf::from(env::var("HOME").ok()?))
+}
+
+/// Same probing as [`default_profile`], but for an arbitrary user/home pair so `--user` can
+/// look up another user's profile instead of always assuming the invoking user's own.
+fn prev_generation_profile(username: &str, home: &Path) -> Option<PathBuf> {
+    [
+        PathBuf::from("/nix/var/nix/profiles/per-user")
+            .join(username)
+            .join("home-manager"),
+        home.join(".local/state/nix/profiles/home-manager"),
+    ]
+    .into_iter()
+    .find(|next| next.exists())
+}
+
+/// Resolves the home directory to operate against: the invoking user's `$HOME` unless `user`
+/// names someone else, in which case their home directory is looked up via `getpwnam`.
+fn resolve_home_dir(user: Option<&str>) -> Result<PathBuf> {
+    let current_user = env::var("USER").ok();
+    match user {
+        None => Ok(PathBuf::from(env::var("HOME")?)),
+        Some(name) if current_user.as_deref() == Some(name) => Ok(PathBuf::from(env::var("HOME")?)),
+        Some(name) => {
+            let user = nix::unistd::User::from_name(name)?
+                .ok_or_else(|| color_eyre::eyre::eyre!("No such user: {name}"))?;
+            Ok(user.dir)
+        }
+    }
+}
+
+/// Prints a [`crate::json::OperationSummary`] for a home-manager rebuild when `--json` is set.
+/// Unlike the shared `os`/`darwin` workflow, home-manager's legacy rebuild path doesn't compute
+/// a structured package diff, so `diff` is always `None` here.
+fn print_json_summary(
+    json_enabled: bool,
+    mode: &str,
+    built_path: &std::path::Path,
+    generation: Option<String>,
+    checks_run: Vec<String>,
+    started_at: std::time::Instant,
+) {
+    if !json_enabled {
+        return;
+    }
+
+    let summary = crate::json::OperationSummary {
+        platform: "home-manager".to_string(),
+        mode: mode.to_string(),
+        built_path: built_path.to_path_buf(),
+        generation,
+        diff: None,
+        duration_secs: started_at.elapsed().as_secs_f64(),
+        checks_run,
+    };
+    match serde_json::to_string(&summary) {
+        Ok(json) => println!("{json}"),
+        Err(e) => warn!("Failed to serialize operation summary: {}", e),
+    }
+}
+
+impl HomeGenerationsArgs {
+    pub fn run(self) -> Result<()> {
+        let profile = match self.profile.map(PathBuf::from).or_else(default_profile) {
+            Some(profile) => profile,
+            None => bail!(
+                "Could not find a home-manager profile; pass one explicitly with --profile"
+            ),
+        };
+        debug!("Listing generations for profile: {}", profile.display());
+
+        let found = crate::generations::list_generations(&profile);
+        let generations =
+            crate::generations::sort_and_filter(found, self.sort, self.reverse, self.filter.as_deref());
+
+        if generations.is_empty() {
+            info!("No generations found for profile {}", profile.display());
+            return Ok(());
+        }
+
+        crate::tables::display_generations_as(&generations, self.format, self.wide);
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 enum HomeRebuildVariant {
     Build,
@@ -37,9 +125,16 @@ enum HomeRebuildVariant {
 impl HomeRebuildArgs {
     fn rebuild(self, variant: HomeRebuildVariant, verbose_count: u8) -> Result<()> {
         use HomeRebuildVariant::*;
-    
+
+        let workflow_started_at = std::time::Instant::now();
+
         // Add pre-flight checks
         let run_preflight = !self.common.common.no_preflight;
+        let checks_run: Vec<String> = if run_preflight {
+            vec!["Git Check".to_string(), "Parse Check".to_string(), "Lint Check".to_string()]
+        } else {
+            Vec::new()
+        };
         if run_preflight {
             // Git Check
             let pb = crate::progress::start_spinner("[🔍 Git] Checking status...");
@@ -137,7 +232,11 @@ impl HomeRebuildArgs {
         }
     
         if self.update_args.update {
-            update(&self.common.installable, self.update_args.update_input)?;
+            update(
+                &self.common.installable,
+                self.update_args.update_input,
+                &std::path::PathBuf::from("."),
+            )?;
         }
 
         let out_path: Box<dyn crate::util::MaybeTempPath> = match self.common.common.out_link {
@@ -169,18 +268,27 @@ impl HomeRebuildArgs {
             self.common.installable.clone()
         };
 
-        let toplevel = toplevel_for(installable, true, &self.extra_args)?;
-        
+        let toplevel = toplevel_for(installable, true, &self.extra_args, self.user.as_deref())?;
+
+        let use_nom = !self.common.common.no_nom
+            && crate::nix_interface::NixInterface::new(verbose_count, false)
+                .detect_environment()
+                .map(|env| env.supports_nom())
+                .unwrap_or(true);
+        if !use_nom && !self.common.common.no_nom {
+            warn!("Detected Lix: disabling nix-output-monitor integration, since its internal-json log format isn't fully compatible with nom");
+        }
+
         // Add progress indicator for build
         let pb_build = crate::progress::start_spinner("[🔨 Build] Building configuration...");
-        
+
         // Use the existing build mechanism but enhance error handling
         let build_result = commands::Build::new(toplevel)
             .extra_arg("--out-link")
             .extra_arg(out_path.get_path())
             .extra_args(&self.extra_args)
             .message("Building Home-Manager configuration")
-            .nom(!self.common.common.no_nom)
+            .nom(use_nom)
             .run();
             
         if let Err(e) = build_result {
@@ -216,12 +324,17 @@ impl HomeRebuildArgs {
             out_path.get_path().display()
         ));
 
+        let target_username = match &self.user {
+            Some(user) => user.clone(),
+            None => env::var("USER").expect("Couldn't get username"),
+        };
+        let target_home = resolve_home_dir(self.user.as_deref())?;
+
         let prev_generation: Option<PathBuf> = [
             PathBuf::from("/nix/var/nix/profiles/per-user")
-                .join(env::var("USER").expect("Couldn't get username"))
+                .join(&target_username)
                 .join("home-manager"),
-            PathBuf::from(env::var("HOME").expect("Couldn't get home directory"))
-                .join(".local/state/nix/profiles/home-manager"),
+            target_home.join(".local/state/nix/profiles/home-manager"),
         ]
         .into_iter()
         .find(|next| next.exists());
@@ -229,7 +342,7 @@ impl HomeRebuildArgs {
         debug!(?prev_generation);
 
         let spec_location =
-            PathBuf::from(std::env::var("HOME")?).join(".local/share/home-manager/specialisation");
+            target_home.join(".local/share/home-manager/specialisation");
 
         let current_specialisation = std::fs::read_to_string(spec_location.to_str().unwrap()).ok();
 
@@ -277,13 +390,21 @@ impl HomeRebuildArgs {
         }
 
         if self.common.common.dry || matches!(variant, Build) {
-            if self.common.common.ask {
+            if self.common.common.asks_anything() {
                 warn!("--ask has no effect as dry run was requested");
             }
+            print_json_summary(
+                self.common.common.json,
+                if matches!(variant, Build) { "Build" } else { "Switch" },
+                target_profile.get_path(),
+                None,
+                checks_run,
+                workflow_started_at,
+            );
             return Ok(());
         }
 
-        if self.common.common.ask {
+        if self.common.common.should_ask(crate::interface::ConfirmStage::Activate) {
             info!("Apply the config?");
             let confirmation = dialoguer::Confirm::new().default(false).interact()?;
 
@@ -292,17 +413,31 @@ impl HomeRebuildArgs {
             }
         }
 
-        if let Some(ext) = &self.backup_extension {
-            info!("Using {} as the backup extension", ext);
-            env::set_var("HOME_MANAGER_BACKUP_EXT", ext);
+        let activating_other_user = self.user.as_deref().is_some_and(|u| u != target_username);
+        if activating_other_user && !crate::config::NgConfig::load().elevation.may_elevate("activation") {
+            bail!(
+                "Activating {target_username}'s home-manager configuration requires sudo, but \
+                 the \"activation\" stage is not in elevation.allow_stages in ng.toml."
+            );
         }
 
         // Add progress indicator for activation
         let pb_activate = crate::progress::start_spinner("[🚀 Activate] Activating configuration...");
-        
-        let activate_result = Command::new(target_profile.get_path().join("activate"))
-            .message("Activating configuration")
-            .run();
+
+        let mut activate_cmd = if activating_other_user {
+            Command::new("sudo")
+                .arg("--user")
+                .arg(&target_username)
+                .arg(target_profile.get_path().join("activate"))
+        } else {
+            Command::new(target_profile.get_path().join("activate"))
+        }
+        .message("Activating configuration");
+        if let Some(ext) = &self.backup_extension {
+            info!("Using {} as the backup extension", ext);
+            activate_cmd = activate_cmd.env("HOME_MANAGER_BACKUP_EXT", ext);
+        }
+        let activate_result = activate_cmd.run();
             
         if let Err(e) = activate_result {
             crate::progress::finish_spinner_fail(&pb_activate);
@@ -320,6 +455,25 @@ impl HomeRebuildArgs {
         
         crate::progress::finish_spinner_success(&pb_activate, "[✅ Activate] Configuration activated successfully");
 
+        // `Build` already returned above, so reaching here means this was a real switch.
+        crate::home_news::show_unread_news(target_profile.get_path());
+
+        let new_generation = prev_generation_profile(&target_username, &target_home)
+            .and_then(|profile| {
+                crate::generations::list_generations(&profile)
+                    .into_iter()
+                    .find(|gen| gen.current)
+                    .map(|gen| gen.number)
+            });
+        print_json_summary(
+            self.common.common.json,
+            "Switch",
+            target_profile.get_path(),
+            new_generation,
+            checks_run,
+            workflow_started_at,
+        );
+
         // Add cleanup if requested
         if self.common.common.clean {
             let pb_clean = crate::progress::start_spinner("[🧹 Clean] Cleaning up old generations...");
@@ -357,10 +511,47 @@ impl HomeRebuildArgs {
     }
 }
 
+/// Best-effort check for the confusing case where a flake defines home-manager only as a
+/// NixOS module (`home-manager.users.<user>` inside `nixosConfigurations.<hostname>`) rather
+/// than a standalone `homeConfigurations.<user>` output. When that's true, `ng home switch`
+/// can never succeed no matter what attribute is tried, so pointing the user at `ng os switch`
+/// up front beats leaving them stuck on a generic "configuration not found" error.
+fn suggest_module_based_redirect(
+    reference: &str,
+    username: &str,
+    hostname: &str,
+    extra_args: &[std::ffi::OsString],
+) -> Option<String> {
+    let predicate = format!(
+        r#" x: (x.nixosConfigurations or {{}}) ? "{hostname}" && ((x.nixosConfigurations."{hostname}".config.home-manager.users or {{}}) ? "{username}") "#
+    );
+    let is_module_based = commands::Command::new("nix")
+        .arg("eval")
+        .args(extra_args)
+        .arg("--apply")
+        .arg(predicate)
+        .args(
+            (Installable::Flake {
+                reference: reference.to_string(),
+                attribute: vec![],
+            })
+            .to_args(),
+        )
+        .run_capture()
+        .ok()??;
+
+    if is_module_based.trim() == "true" {
+        Some(format!("ng os switch --hostname {hostname}"))
+    } else {
+        None
+    }
+}
+
 fn toplevel_for<I, S>(
     installable: Installable,
     push_drv: bool,
     extra_args: I,
+    user: Option<&str>,
 ) -> Result<Installable>
 where
     I: IntoIterator<Item = S>,
@@ -392,7 +583,10 @@ where
             attribute.push(String::from("homeConfigurations"));
 
             // check for <user> and <user@hostname>
-            let username = std::env::var("USER").expect("Couldn't get username");
+            let username = match user {
+                Some(user) => user.to_owned(),
+                None => std::env::var("USER").expect("Couldn't get username"),
+            };
             let hostname = get_hostname()?;
 
             let flake_reference = reference.clone();
@@ -448,6 +642,16 @@ where
                 .collect::<Vec<_>>()
                 .join(", ");
 
+            if let Some(redirect) =
+                suggest_module_based_redirect(&flake_reference, &username, &hostname, &extra_args)
+            {
+                bail!(
+                    "Couldn't find a standalone home-manager configuration (tried {tried_str}), \
+                     but this flake configures home-manager as a NixOS module for \
+                     {username}@{hostname} instead. Try `{redirect}` instead."
+                );
+            }
+
             bail!("Couldn't find home-manager configuration, tried {tried_str}");
         }
         Installable::File {
@@ -491,11 +695,26 @@ impl HomeReplArgs {
             self.installable
         };
 
-        let toplevel = toplevel_for(installable, false, &self.extra_args)?;
+        let toplevel = toplevel_for(installable, false, &self.extra_args, None)?;
+
+        let (reference, attribute) = match toplevel {
+            Installable::Flake {
+                reference,
+                attribute,
+            } => (reference, attribute),
+            other => {
+                // Not a flake installable (e.g. --file/--expr); fall back to the old behavior of
+                // just repl-ing directly on it, since `builtins.getFlake` doesn't apply.
+                Command::new("nix").arg("repl").args(other.to_args()).run()?;
+                return Ok(());
+            }
+        };
 
         Command::new("nix")
             .arg("repl")
-            .args(toplevel.to_args())
+            .arg("--impure")
+            .arg("--expr")
+            .arg(crate::util::preloaded_repl_expr(&reference, &attribute))
             .run()?;
 
         Ok(())
@@ -0,0 +1,38 @@
+//! Surfaces `home-manager news` after a successful `ng home switch`.
+//!
+//! Home Manager tracks which news entries a user has already seen and prints unread ones via its
+//! own `news` subcommand, which ships as `home-path/bin/home-manager` in the generation when
+//! `programs.home-manager.enable` is set. Rather than re-implementing that read-id bookkeeping
+//! here, this just shells out to whichever copy of that subcommand is available.
+
+use std::path::{Path, PathBuf};
+
+use tracing::debug;
+
+use crate::commands::Command;
+
+/// Runs `home-manager news` from the newly-activated generation, if it ships one. Does nothing
+/// (beyond a debug log) if `programs.home-manager.enable` isn't set for this configuration and no
+/// `home-manager` is on `PATH` either, since not every home-manager user opts into that module.
+pub fn show_unread_news(target_profile: &Path) {
+    let bin = match home_manager_bin(target_profile) {
+        Some(bin) => bin,
+        None => {
+            debug!("No `home-manager` binary found; skipping news check.");
+            return;
+        }
+    };
+
+    if let Err(e) = Command::new(&bin).arg("news").run() {
+        debug!("Failed to run `{} news`: {}", bin.display(), e);
+    }
+}
+
+fn home_manager_bin(target_profile: &Path) -> Option<PathBuf> {
+    let in_profile = target_profile.join("home-path/bin/home-manager");
+    if in_profile.exists() {
+        return Some(in_profile);
+    }
+
+    crate::util::command_exists("home-manager").then(|| PathBuf::from("home-manager"))
+}
@@ -0,0 +1,225 @@
+//! `ng init`: scaffolds a minimal flake configuration for a new user of the tool.
+//!
+//! Writes `flake.nix`, `ng.toml` and, for the NixOS templates, a `hardware-configuration.nix`
+//! placeholder. The `configuration.nix`/`home.nix` modules that the generated `flake.nix`
+//! imports are not created, so the flake does not evaluate until they are added.
+
+use std::fs;
+use std::path::Path;
+
+use color_eyre::eyre::bail;
+use tracing::info;
+
+use crate::interface::{InitArgs, InitTemplate};
+use crate::Result;
+
+pub fn run(args: &InitArgs) -> Result<()> {
+    let template = match args.template {
+        Some(template) => template,
+        None => prompt_template()?,
+    };
+
+    let hostname = match &args.hostname {
+        Some(hostname) => hostname.clone(),
+        None => prompt_text("Hostname", &crate::util::get_hostname().unwrap_or_else(|_| "nixos".to_string()))?,
+    };
+
+    let username = match template {
+        InitTemplate::NixosHome | InitTemplate::StandaloneHome => Some(match &args.username {
+            Some(username) => username.clone(),
+            None => prompt_text("Username", &std::env::var("USER").unwrap_or_else(|_| "user".to_string()))?,
+        }),
+        InitTemplate::Nixos | InitTemplate::Darwin => None,
+    };
+
+    fs::create_dir_all(&args.directory)?;
+
+    write_file(
+        &args.directory.join("flake.nix"),
+        &flake_nix(template, &hostname, username.as_deref()),
+        args.force,
+    )?;
+
+    if matches!(template, InitTemplate::Nixos | InitTemplate::NixosHome) {
+        write_file(
+            &args.directory.join("hardware-configuration.nix"),
+            HARDWARE_PLACEHOLDER,
+            args.force,
+        )?;
+    }
+
+    write_file(&args.directory.join("ng.toml"), NG_TOML, args.force)?;
+
+    info!(
+        "Scaffolded a {:?} configuration for '{}' in {}",
+        template,
+        hostname,
+        args.directory.display()
+    );
+    Ok(())
+}
+
+fn prompt_template() -> Result<InitTemplate> {
+    let options = [
+        ("NixOS system configuration", InitTemplate::Nixos),
+        (
+            "NixOS system configuration with home-manager as a module",
+            InitTemplate::NixosHome,
+        ),
+        ("nix-darwin system configuration", InitTemplate::Darwin),
+        (
+            "Standalone home-manager configuration",
+            InitTemplate::StandaloneHome,
+        ),
+    ];
+    let labels: Vec<&str> = options.iter().map(|(label, _)| *label).collect();
+    let selection = dialoguer::Select::new()
+        .with_prompt("What kind of configuration do you want to scaffold?")
+        .items(&labels)
+        .default(0)
+        .interact()?;
+    Ok(options[selection].1)
+}
+
+fn prompt_text(prompt: &str, default: &str) -> Result<String> {
+    Ok(dialoguer::Input::<String>::new()
+        .with_prompt(prompt)
+        .default(default.to_string())
+        .interact_text()?)
+}
+
+fn write_file(path: &Path, contents: &str, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        bail!(
+            "{} already exists; pass --force to overwrite",
+            path.display()
+        );
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+const HARDWARE_PLACEHOLDER: &str = r#"# Replace this file with the one generated by `nixos-generate-config`,
+# or with your own hardware-specific configuration.
+{ config, lib, pkgs, modulesPath, ... }:
+
+{
+  imports = [ ];
+
+  boot.loader.grub.device = "nodev";
+  fileSystems."/" = {
+    device = "/dev/disk/by-label/nixos";
+    fsType = "ext4";
+  };
+}
+"#;
+
+const NG_TOML: &str = r#"# ng configuration. See the ng documentation for the full set of options.
+
+[pre_flight]
+checks = ["Nix Syntax Parse", "Nix Semantic Check", "Nix Code Format"]
+"#;
+
+fn flake_nix(template: InitTemplate, hostname: &str, username: Option<&str>) -> String {
+    match template {
+        InitTemplate::Nixos => format!(
+            r#"{{
+  description = "NixOS configuration for {hostname}";
+
+  inputs = {{
+    nixpkgs.url = "github:NixOS/nixpkgs/nixos-unstable";
+  }};
+
+  outputs = {{ self, nixpkgs, ... }}: {{
+    nixosConfigurations.{hostname} = nixpkgs.lib.nixosSystem {{
+      system = "x86_64-linux";
+      modules = [
+        ./hardware-configuration.nix
+        ./configuration.nix
+      ];
+    }};
+  }};
+}}
+"#
+        ),
+        InitTemplate::NixosHome => {
+            let username = username.unwrap_or("user");
+            format!(
+                r#"{{
+  description = "NixOS configuration for {hostname}, with home-manager as a module";
+
+  inputs = {{
+    nixpkgs.url = "github:NixOS/nixpkgs/nixos-unstable";
+    home-manager = {{
+      url = "github:nix-community/home-manager";
+      inputs.nixpkgs.follows = "nixpkgs";
+    }};
+  }};
+
+  outputs = {{ self, nixpkgs, home-manager, ... }}: {{
+    nixosConfigurations.{hostname} = nixpkgs.lib.nixosSystem {{
+      system = "x86_64-linux";
+      modules = [
+        ./hardware-configuration.nix
+        ./configuration.nix
+        home-manager.nixosModules.home-manager
+        {{
+          home-manager.useGlobalPkgs = true;
+          home-manager.useUserPackages = true;
+          home-manager.users.{username} = import ./home.nix;
+        }}
+      ];
+    }};
+  }};
+}}
+"#
+            )
+        }
+        InitTemplate::Darwin => format!(
+            r#"{{
+  description = "nix-darwin configuration for {hostname}";
+
+  inputs = {{
+    nixpkgs.url = "github:NixOS/nixpkgs/nixpkgs-unstable";
+    nix-darwin = {{
+      url = "github:LnL7/nix-darwin";
+      inputs.nixpkgs.follows = "nixpkgs";
+    }};
+  }};
+
+  outputs = {{ self, nixpkgs, nix-darwin, ... }}: {{
+    darwinConfigurations.{hostname} = nix-darwin.lib.darwinSystem {{
+      system = "aarch64-darwin";
+      modules = [ ./configuration.nix ];
+    }};
+  }};
+}}
+"#
+        ),
+        InitTemplate::StandaloneHome => {
+            let username = username.unwrap_or("user");
+            format!(
+                r#"{{
+  description = "Standalone home-manager configuration for {username}@{hostname}";
+
+  inputs = {{
+    nixpkgs.url = "github:NixOS/nixpkgs/nixos-unstable";
+    home-manager = {{
+      url = "github:nix-community/home-manager";
+      inputs.nixpkgs.follows = "nixpkgs";
+    }};
+  }};
+
+  outputs = {{ self, nixpkgs, home-manager, ... }}: {{
+    homeConfigurations."{username}@{hostname}" = home-manager.lib.homeManagerConfiguration {{
+      pkgs = nixpkgs.legacyPackages.x86_64-linux;
+      modules = [ ./home.nix ];
+    }};
+  }};
+}}
+"#
+            )
+        }
+    }
+}
+
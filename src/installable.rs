@@ -9,7 +9,7 @@ use tracing::warn;
 
 // Reference: https://nix.dev/manual/nix/2.18/command-ref/new-cli/nix
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Installable {
     Flake {
         reference: String,
@@ -78,6 +78,9 @@ impl FromArgMatches for Installable {
         if let Some(i) = installable {
             let mut elems = i.splitn(2, '#');
             let reference = elems.next().unwrap().to_owned();
+            if let Err(e) = validate_flake_reference(&reference) {
+                return Err(clap::Error::raw(ErrorKind::InvalidValue, format!("{e}\n")));
+            }
             return Ok(Self::Flake {
                 reference,
                 attribute: parse_attr_for_installable(elems.next().map(|s| s.to_string()).unwrap_or_default()),
@@ -440,6 +443,194 @@ fn test_attribute_path_parser_comprehensive() {
     assert!(attribute_path_parser().then_ignore(end()).parse("foo..bar").is_err());
 }
 
+/// Recognized flakeref schemes (per `nix flake`'s URL syntax), checked against the part of the
+/// scheme before any `+` (so `git+https`, `git+ssh`, etc. are accepted via the `git` entry).
+const KNOWN_FLAKE_SCHEMES: &[&str] = &[
+    "path", "git", "file", "tarball", "http", "https", "github", "gitlab", "sourcehut", "flake",
+];
+
+/// A flakeref validation failure, naming the specific component that's malformed (a scheme, an
+/// owner/repo, a query parameter) rather than just echoing the whole reference back.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FlakeRefError {
+    pub component: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for FlakeRefError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid flake reference: {} (in '{}')", self.message, self.component)
+    }
+}
+
+fn is_valid_flake_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')
+}
+
+/// Best-effort syntax validation of a flake reference's `scheme:path[/rev-or-ref]` and `?query`
+/// parts, meant to catch common typos — an unrecognized scheme, a `github:` ref missing its
+/// `repo`, a query parameter with no `=` — before handing the string to `nix` and getting an
+/// obscure failure back. This is not a full reimplementation of `nix flake`'s reference
+/// resolution, which also consults the flake registry and accepts forms (e.g. registry aliases
+/// with custom lookup rules) this doesn't attempt to validate.
+pub fn validate_flake_reference(reference: &str) -> Result<(), FlakeRefError> {
+    if reference.is_empty() {
+        return Err(FlakeRefError {
+            component: reference.to_string(),
+            message: "flake reference is empty".to_string(),
+        });
+    }
+
+    let (main, query) = match reference.split_once('?') {
+        Some((m, q)) => (m, Some(q)),
+        None => (reference, None),
+    };
+
+    // Bare filesystem paths have no scheme and aren't validated further here; `nix` itself is
+    // the authority on whether they actually contain a flake.
+    let is_bare_path = main.starts_with('.') || main.starts_with('/') || main.starts_with('~');
+
+    if !is_bare_path {
+        match main.find(':') {
+            Some(colon_idx) => {
+                let scheme = &main[..colon_idx];
+                let rest = &main[colon_idx + 1..];
+                let base_scheme = scheme.split('+').next().unwrap_or(scheme);
+
+                if !KNOWN_FLAKE_SCHEMES.contains(&base_scheme) {
+                    return Err(FlakeRefError {
+                        component: scheme.to_string(),
+                        message: format!("unrecognized flake reference scheme '{scheme}'"),
+                    });
+                }
+
+                if matches!(base_scheme, "github" | "gitlab" | "sourcehut") {
+                    let mut parts = rest.splitn(3, '/');
+                    let owner = parts.next().unwrap_or("");
+                    let repo = parts.next().unwrap_or("");
+                    if owner.is_empty() || repo.is_empty() {
+                        return Err(FlakeRefError {
+                            component: rest.to_string(),
+                            message: format!(
+                                "'{scheme}:' references need an owner/repo, e.g. '{scheme}:owner/repo'"
+                            ),
+                        });
+                    }
+                    if !owner.chars().all(is_valid_flake_id_char)
+                        || !repo.chars().all(is_valid_flake_id_char)
+                    {
+                        return Err(FlakeRefError {
+                            component: format!("{owner}/{repo}"),
+                            message: "owner/repo may only contain letters, digits, '-', '_' and '.'"
+                                .to_string(),
+                        });
+                    }
+                    if let Some(rev_or_ref) = parts.next() {
+                        if rev_or_ref.is_empty() || !rev_or_ref.chars().all(is_valid_flake_id_char) {
+                            return Err(FlakeRefError {
+                                component: rev_or_ref.to_string(),
+                                message: "rev/ref may only contain letters, digits, '-', '_' and '.'"
+                                    .to_string(),
+                            });
+                        }
+                    }
+                } else if rest.is_empty() {
+                    return Err(FlakeRefError {
+                        component: scheme.to_string(),
+                        message: format!("'{scheme}:' reference is missing a path"),
+                    });
+                }
+            }
+            None => {
+                // No scheme, not a filesystem path: must be a flake registry id, optionally
+                // followed by a `/rev-or-ref` (e.g. "nixpkgs" or "nixpkgs/nixos-23.05").
+                let mut parts = main.splitn(2, '/');
+                let id = parts.next().unwrap_or("");
+                if id.is_empty() || !id.chars().all(is_valid_flake_id_char) {
+                    return Err(FlakeRefError {
+                        component: main.to_string(),
+                        message: "flake registry id may only contain letters, digits, '-', '_' and '.'"
+                            .to_string(),
+                    });
+                }
+                if let Some(rev_or_ref) = parts.next() {
+                    if rev_or_ref.is_empty() || !rev_or_ref.chars().all(is_valid_flake_id_char) {
+                        return Err(FlakeRefError {
+                            component: rev_or_ref.to_string(),
+                            message: "rev/ref may only contain letters, digits, '-', '_' and '.'"
+                                .to_string(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(query) = query {
+        if query.is_empty() {
+            return Err(FlakeRefError {
+                component: reference.to_string(),
+                message: "'?' in flake reference is not followed by any query parameters"
+                    .to_string(),
+            });
+        }
+        for param in query.split('&') {
+            match param.split_once('=') {
+                Some((key, _)) if !key.is_empty() => {}
+                _ => {
+                    return Err(FlakeRefError {
+                        component: param.to_string(),
+                        message: "query parameter must be in 'key=value' form".to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_flake_reference_accepts_valid_refs() {
+    for reference in [
+        ".",
+        "./sub/dir",
+        "/abs/path",
+        "path:/repo?dir=hosts/foo",
+        "github:owner/repo",
+        "github:owner/repo/some-branch",
+        "git+https://example.com/repo.git",
+        "nixpkgs",
+        "nixpkgs/nixos-23.05",
+    ] {
+        assert!(
+            validate_flake_reference(reference).is_ok(),
+            "expected '{reference}' to be valid"
+        );
+    }
+}
+
+#[test]
+fn test_validate_flake_reference_rejects_malformed_refs() {
+    assert_eq!(
+        validate_flake_reference("gethub:owner/repo").unwrap_err().component,
+        "gethub"
+    );
+    assert_eq!(
+        validate_flake_reference("github:owner").unwrap_err().component,
+        "owner"
+    );
+    assert_eq!(
+        validate_flake_reference("github:/repo").unwrap_err().component,
+        "/repo"
+    );
+    assert_eq!(
+        validate_flake_reference("path:/repo?dir").unwrap_err().component,
+        "dir"
+    );
+    assert_eq!(validate_flake_reference("").unwrap_err().message, "flake reference is empty");
+}
+
 impl Installable {
     pub fn to_args(&self) -> Vec<String> {
         let mut res = Vec::new();
@@ -491,7 +682,69 @@ fn test_installable_to_args() {
     );
 }
 
-fn join_attribute<I>(attribute: I) -> String
+#[test]
+fn test_installable_display() {
+    assert_eq!(
+        (Installable::Flake {
+            reference: String::from("w"),
+            attribute: ["x", "y.z"].into_iter().map(str::to_string).collect()
+        })
+        .to_string(),
+        r#"w#x."y.z""#
+    );
+    assert_eq!(
+        (Installable::File {
+            path: PathBuf::from("w.nix"),
+            attribute: vec!["x".to_string()]
+        })
+        .to_string(),
+        "-f w.nix x"
+    );
+    assert_eq!(
+        Installable::Store {
+            path: PathBuf::from("/nix/store/abc-thing")
+        }
+        .to_string(),
+        "/nix/store/abc-thing"
+    );
+}
+
+#[test]
+fn test_installable_round_trips_through_args() {
+    // `Installable::Store` isn't included here: reconstructing it from args requires
+    // `fs::canonicalize` to succeed against a real `/nix/store` path, which depends on the
+    // filesystem rather than anything `to_args`/`from_arg_matches` control.
+    let cases = vec![
+        Installable::Flake {
+            reference: "path:/repo?dir=hosts/foo".to_string(),
+            attribute: vec!["config".to_string(), "system.build".to_string()],
+        },
+        Installable::File {
+            path: PathBuf::from("./configuration.nix"),
+            attribute: vec!["a".to_string(), "b".to_string()],
+        },
+        Installable::Expression {
+            expression: "1 + 1".to_string(),
+            attribute: vec![],
+        },
+    ];
+
+    for installable in cases {
+        let mut full_args = vec!["ng".to_string()];
+        full_args.extend(installable.to_args());
+
+        let cmd = Installable::augment_args(clap::Command::new("ng"));
+        let matches = cmd
+            .try_get_matches_from(full_args)
+            .expect("to_args() output should be parseable by the same clap definition");
+        let round_tripped =
+            Installable::from_arg_matches(&matches).expect("failed to reconstruct Installable");
+
+        assert_eq!(round_tripped, installable);
+    }
+}
+
+pub(crate) fn join_attribute<I>(attribute: I) -> String
 where
     I: IntoIterator,
     I::Item: AsRef<str>,
@@ -527,6 +780,27 @@ fn test_join_attribute() {
     assert_eq!(join_attribute(vec!["foo", "bar.baz"]), r#"foo."bar.baz""#);
 }
 
+/// Renders the canonical `nix`-CLI form of an installable (`ref#attr`, `-f file attr`, ...) —
+/// the pieces `to_args()` produces, with `-f`/`-e` in place of `--file`/`--expr`, joined into the single string this repo's logs and
+/// prompts want instead of an argv vector. Used anywhere an installable needs to show up in a
+/// message, in place of `{:?}`.
+impl std::fmt::Display for Installable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Installable::Flake { reference, attribute } => {
+                write!(f, "{reference}#{}", join_attribute(attribute))
+            }
+            Installable::File { path, attribute } => {
+                write!(f, "-f {} {}", path.display(), join_attribute(attribute))
+            }
+            Installable::Expression { expression, attribute } => {
+                write!(f, "-e {} {}", expression, join_attribute(attribute))
+            }
+            Installable::Store { path } => write!(f, "{}", path.display()),
+        }
+    }
+}
+
 impl Installable {
     pub fn str_kind(&self) -> &str {
         match self {
@@ -536,4 +810,106 @@ impl Installable {
             Installable::Expression { .. } => "expression",
         }
     }
+
+    /// The directory this installable's Nix files actually live in, if it can be determined
+    /// without invoking Nix (e.g. for a pre-flight file walk). Handles `path:`-style flake
+    /// references with a `?dir=` query (`path:/repo?dir=hosts/foo`) and bare local paths (`.`,
+    /// `./sub`, `/abs/path`), falling back to `None` for anything that isn't resolvable to a
+    /// local directory without a network/registry lookup (e.g. `github:owner/repo`).
+    pub fn project_root(&self) -> Option<PathBuf> {
+        match self {
+            Installable::Flake { reference, .. } => resolve_flake_dir(reference),
+            Installable::File { path, .. } => {
+                let dir = path.parent().unwrap_or(path);
+                Some(if dir.as_os_str().is_empty() {
+                    PathBuf::from(".")
+                } else {
+                    dir.to_path_buf()
+                })
+            }
+            Installable::Store { .. } | Installable::Expression { .. } => None,
+        }
+    }
+}
+
+/// Resolves a flake reference to the local directory it points at, if any. Understands the
+/// `path:` scheme (with an optional `?dir=` subdirectory query, per the `nix flake` URL format)
+/// as well as bare local paths passed without a scheme (`.`, `./sub`, `/abs/path`, `~/repo`).
+/// Registry references like `github:owner/repo` or `nixpkgs` aren't backed by a local directory
+/// and resolve to `None`.
+fn resolve_flake_dir(reference: &str) -> Option<PathBuf> {
+    let (path_part, query) = match reference.split_once('?') {
+        Some((p, q)) => (p, Some(q)),
+        None => (reference, None),
+    };
+
+    let base = if let Some(rest) = path_part.strip_prefix("path:") {
+        rest
+    } else if path_part.contains(':') {
+        // Some other scheme (github:, git+https:, flake:, ...) — not a local directory.
+        return None;
+    } else if path_part.starts_with('.') || path_part.starts_with('/') || path_part.starts_with('~') {
+        path_part
+    } else {
+        // A bare identifier like `nixpkgs` is a flake registry shorthand, not a local path.
+        return None;
+    };
+
+    let base = if let Some(rest) = base.strip_prefix('~') {
+        PathBuf::from(env::var("HOME").ok()?).join(rest.trim_start_matches('/'))
+    } else {
+        PathBuf::from(base)
+    };
+
+    let dir_param = query.and_then(|q| {
+        q.split('&')
+            .find_map(|kv| kv.strip_prefix("dir=").map(str::to_string))
+    });
+
+    Some(match dir_param {
+        Some(dir) => base.join(dir),
+        None => base,
+    })
+}
+
+#[test]
+fn test_resolve_flake_dir() {
+    assert_eq!(resolve_flake_dir("."), Some(PathBuf::from(".")));
+    assert_eq!(
+        resolve_flake_dir("path:/repo?dir=hosts/foo"),
+        Some(PathBuf::from("/repo/hosts/foo"))
+    );
+    assert_eq!(
+        resolve_flake_dir("/abs/path?dir=sub"),
+        Some(PathBuf::from("/abs/path/sub"))
+    );
+    assert_eq!(resolve_flake_dir("github:owner/repo"), None);
+    assert_eq!(resolve_flake_dir("nixpkgs"), None);
+}
+
+#[test]
+fn test_installable_project_root() {
+    assert_eq!(
+        Installable::Flake {
+            reference: "path:/repo?dir=hosts/foo".to_string(),
+            attribute: vec![],
+        }
+        .project_root(),
+        Some(PathBuf::from("/repo/hosts/foo"))
+    );
+    assert_eq!(
+        Installable::File {
+            path: PathBuf::from("/repo/hosts/foo/configuration.nix"),
+            attribute: vec![],
+        }
+        .project_root(),
+        Some(PathBuf::from("/repo/hosts/foo"))
+    );
+    assert_eq!(
+        Installable::Store {
+            path: PathBuf::from("/nix/store/abc-thing")
+        }
+        .project_root(),
+        None
+    );
 }
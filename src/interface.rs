@@ -1,3 +1,4 @@
+use std::fmt;
 use std::path::PathBuf;
 
 use anstyle::Style;
@@ -36,10 +37,101 @@ pub struct Main {
     /// Increase verbosity (can be used multiple times)
     pub verbose: u8,
 
+    /// Progress display style. `plain` replaces spinners with timestamped log lines
+    /// (also auto-enabled when `CI=true` or stdout isn't a terminal).
+    #[arg(long, global = true, value_enum, default_value_t = ProgressMode::Auto)]
+    pub progress: ProgressMode,
+
+    /// Log verbosity as a simple level (e.g. "debug") or a per-module `tracing-subscriber`
+    /// `EnvFilter`/`RUST_LOG`-style directive string (e.g. "ng::pre_flight=debug,ng::commands=trace").
+    /// Overrides `-v`/`NG_LOG`/`RUST_LOG` when set.
+    #[arg(long, global = true, alias = "log-level")]
+    pub log_filter: Option<String>,
+
+    /// Controls colored output. `auto` (default) disables color when stdout isn't a terminal
+    /// or `NO_COLOR` is set.
+    #[arg(long, global = true, value_enum, default_value_t = ColorMode::Auto)]
+    pub color: ColorMode,
+
+    /// Suppress spinners, info-level logs, and success banners; only warnings, errors, and the
+    /// final result path are printed. Useful for cron jobs and scripts that wrap `ng`.
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
     #[command(subcommand)]
     pub command: NGCommand,
 }
 
+impl Main {
+    /// Expands a leading `argv[1]` against `[aliases]` in `ng.toml` (e.g. `up = "os switch
+    /// --update"`) before clap ever sees the arguments, so a configured alias behaves exactly
+    /// like typing out the aliased subcommand and its arguments. `args[0]` (the binary name) and
+    /// anything past the alias are left untouched; a no-match returns `args` as-is.
+    pub fn expand_aliases(args: Vec<String>, aliases: &std::collections::HashMap<String, String>) -> Vec<String> {
+        let Some(first) = args.get(1) else {
+            return args;
+        };
+        let Some(expansion) = aliases.get(first) else {
+            return args;
+        };
+
+        let mut expanded = vec![args[0].clone()];
+        expanded.extend(expansion.split_whitespace().map(String::from));
+        expanded.extend(args.into_iter().skip(2));
+        expanded
+    }
+
+    /// Renders configured `[aliases]` for `--help`'s `after_help`, sorted by name. Returns `None`
+    /// if none are configured, so `--help` output is unchanged for everyone else.
+    pub fn aliases_help(aliases: &std::collections::HashMap<String, String>) -> Option<String> {
+        if aliases.is_empty() {
+            return None;
+        }
+
+        let mut names: Vec<&String> = aliases.keys().collect();
+        names.sort();
+
+        let mut out = String::from("Configured aliases (ng.toml [aliases]):\n");
+        for name in names {
+            out.push_str(&format!("  {name} = {}\n", aliases[name]));
+        }
+        Some(out.trim_end().to_string())
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ProgressMode {
+    /// Interactive spinners on a TTY, plain log lines otherwise or under CI
+    #[default]
+    Auto,
+    /// Always use interactive indicatif spinners
+    Fancy,
+    /// Always use timestamped, non-interactive log lines
+    Plain,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    /// Color when stdout is a terminal and `NO_COLOR` is unset
+    #[default]
+    Auto,
+    /// Always emit color
+    Always,
+    /// Never emit color
+    Never,
+}
+
+/// A stage in the rebuild workflow that `--ask` can require confirmation before.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfirmStage {
+    /// Before building the new configuration
+    Build,
+    /// Before activating the new configuration
+    Activate,
+    /// Before making the new configuration the boot default
+    Boot,
+}
+
 #[derive(Subcommand, Debug)]
 #[command(disable_help_subcommand = true)]
 pub enum NGCommand {
@@ -48,9 +140,25 @@ pub enum NGCommand {
     Darwin(DarwinArgs),
     Search(SearchArgs),
     Clean(CleanProxy),
+    #[command(visible_alias = "fmt")]
     Format(FormatArgs), // Added Format command
+    Diff(DiffArgs),
+    Eval(EvalArgs),
+    Check(CheckArgs),
+    WhyRebuild(WhyRebuildArgs),
+    Why(WhyArgs),
+    Du(DuArgs),
+    Store(StoreProxy),
     #[command(hide = true)]
     Completions(CompletionArgs),
+    /// Check for and apply updates to the ng binary itself
+    SelfUpdate(SelfUpdateArgs),
+    /// Scaffold a new flake configuration
+    Init(InitArgs),
+    /// Enter the configuration's devShell
+    Develop(DevelopArgs),
+    /// Inspect ng's own ng.toml configuration
+    Config(ConfigArgs),
 }
 
 impl NGCommand {
@@ -75,6 +183,17 @@ impl NGCommand {
                 // Call the formatting logic from the new module
                 crate::format_logic::run_formatting(&args, verbose_count)
             }
+            Self::Diff(args) => args.run(verbose_count),
+            Self::Eval(args) => args.run(verbose_count),
+            Self::Check(args) => args.run(verbose_count),
+            Self::WhyRebuild(args) => args.run(verbose_count),
+            Self::Why(args) => args.run(verbose_count),
+            Self::Du(args) => args.run(verbose_count),
+            Self::Store(proxy) => StoreMode::run(&proxy.command, verbose_count),
+            Self::SelfUpdate(args) => args.run(verbose_count),
+            Self::Init(args) => crate::init::run(&args),
+            Self::Develop(args) => args.run(verbose_count),
+            Self::Config(args) => args.run(),
         }
     }
 }
@@ -108,6 +227,65 @@ pub enum OsSubcommand {
 
     /// List available generations from profile path
     Info(OsGenerationsArgs),
+
+    /// List specialisations built into a generation
+    Specialisations(OsSpecialisationsArgs),
+
+    /// Generation management beyond listing (see `ng os info`)
+    Generations(OsGenerationsCommand),
+}
+
+#[derive(Args, Debug)]
+pub struct OsGenerationsCommand {
+    #[command(subcommand)]
+    pub subcommand: OsGenerationsSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum OsGenerationsSubcommand {
+    /// Roll back (or forward) to a specific generation number: points the profile at it and
+    /// runs its own `switch-to-configuration switch`. Finer-grained than `nixos-rebuild`'s
+    /// built-in "previous generation" rollback, which can only step back one.
+    SwitchTo(OsGenerationsSwitchToArgs),
+
+    /// Delete generations older than a given age, without a full `ng clean` invocation
+    Prune(OsGenerationsPruneArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct OsGenerationsSwitchToArgs {
+    /// Generation number to switch to
+    pub generation: u32,
+
+    /// Path to Nix' profiles directory
+    #[arg(long, short = 'P', default_value = "/nix/var/nix/profiles/system")]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Args)]
+#[clap(verbatim_doc_comment)]
+/// Delete system-profile generations older than a given age, via `nix-env --delete-generations`
+///
+/// Unlike `ng clean`, this only targets the given profile's generations by age — it doesn't
+/// touch gcroots, run garbage collection, or optimise the store. Prints the generations that
+/// would be deleted and asks for confirmation unless `--yes` or `--dry` is given.
+pub struct OsGenerationsPruneArgs {
+    /// Delete generations last modified more than this long ago (e.g. "30d", "2w"); see
+    /// humantime's duration syntax
+    #[arg(long)]
+    pub older_than: humantime::Duration,
+
+    /// Path to Nix' profiles directory
+    #[arg(long, short = 'P', default_value = "/nix/var/nix/profiles/system")]
+    pub profile: Option<String>,
+
+    /// Only print which generations would be deleted, without deleting them
+    #[arg(long, short = 'n')]
+    pub dry: bool,
+
+    /// Skip the confirmation prompt
+    #[arg(long, short)]
+    pub yes: bool,
 }
 
 #[derive(Debug, Args)]
@@ -122,8 +300,9 @@ pub struct OsRebuildArgs {
     #[arg(long, short = 'H', global = true)]
     pub hostname: Option<String>,
 
-    /// Explicitly select some specialisation
-    #[arg(long, short)]
+    /// Explicitly select some specialisation. Passed with no value (`--specialisation`) to
+    /// pick interactively from the ones built into this configuration.
+    #[arg(long, short, num_args = 0..=1, default_missing_value = "")]
     pub specialisation: Option<String>,
 
     /// Ignore specialisations
@@ -136,6 +315,30 @@ pub struct OsRebuildArgs {
     /// Don't panic if calling nh as root
     #[arg(short = 'R', long, env = "NG_BYPASS_ROOT_CHECK")]
     pub bypass_root_check: bool,
+
+    /// Deploy to a remote host instead of activating locally: copies the built closure there via
+    /// `nix copy` and runs `switch-to-configuration` over SSH, e.g. `--target-host root@server`
+    #[arg(long)]
+    pub target_host: Option<String>,
+
+    /// Evaluate and build the configuration on a remote machine instead of locally, e.g. a
+    /// beefier builder. The resulting closure is copied to `--target-host` directly if given, or
+    /// back to this machine otherwise.
+    #[arg(long)]
+    pub build_host: Option<String>,
+
+    /// Magic-rollback window, in seconds, for a `--target-host` deploy: activates the new
+    /// configuration immediately, but schedules an automatic rollback to the previous generation
+    /// on the target host unless you confirm within the window. Protects against a bad network
+    /// configuration locking you out before you can fix it. Requires `--target-host`.
+    #[arg(long, requires = "target_host")]
+    pub confirm_timeout: Option<u64>,
+
+    /// Install into `/nix/var/nix/profiles/system-profiles/<name>` instead of the default system
+    /// profile, so this configuration gets its own boot menu entry alongside the default one
+    /// instead of replacing it — handy for side-by-side experimental configs.
+    #[arg(long)]
+    pub profile_name: Option<String>,
 }
 
 #[derive(Debug, Args, Clone)]
@@ -178,10 +381,23 @@ pub struct CommonArgs {
     #[arg(long, short = 'n', global = true)]
     pub dry: bool,
     
-    /// Ask for confirmation before activating/committing changes (default: true)
-    #[arg(long = "no-ask", global = true, default_value_t = true, action = clap::ArgAction::SetFalse, help = "Do not ask for confirmation")]
-    pub ask: bool, // Field is 'ask', true by default. --no-ask flag sets it to false.
-    
+    /// Ask for confirmation before these stages, as a comma-separated list of `build`,
+    /// `activate`, `boot` (e.g. `--ask=activate,boot`). Bare `--ask` confirms before all three.
+    /// Defaults to confirming before activation only. See `--no-ask` to disable entirely.
+    #[arg(
+        long,
+        global = true,
+        value_delimiter = ',',
+        num_args = 0..=3,
+        default_value = "activate",
+        default_missing_value = "build,activate,boot"
+    )]
+    pub ask: Vec<ConfirmStage>,
+
+    /// Skip all confirmation prompts, regardless of `--ask`
+    #[arg(long, global = true)]
+    pub no_ask: bool,
+
     /// Don't use nix-output-monitor for the build process
     #[arg(long, global = true)]
     pub no_nom: bool,
@@ -193,6 +409,37 @@ pub struct CommonArgs {
     /// Run cleanup after successful activation (removes old gens, runs GC)
     #[arg(long, global = true)]
     pub clean: bool,
+
+    /// Keep building other derivations after one fails, then print a summary table of failures
+    #[arg(long, global = true)]
+    pub keep_going: bool,
+
+    /// Print a machine-readable JSON summary of the completed operation
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Print the ordered execution plan (checks, update, build, activation, cleanup), then run
+    /// the workflow as a dry run that prints each stage's exact command as it is reached.
+    /// Implies `--dry`.
+    #[arg(long, global = true)]
+    pub plan: bool,
+
+    /// Don't collapse repeated diagnostics (e.g. the same formatter warning in dozens of files)
+    /// into a single grouped entry; print every occurrence individually instead.
+    #[arg(long, global = true)]
+    pub no_group: bool,
+}
+
+impl CommonArgs {
+    /// Whether confirmation should be asked before `stage`, accounting for `--no-ask`.
+    pub fn should_ask(&self, stage: ConfirmStage) -> bool {
+        !self.no_ask && self.ask.contains(&stage)
+    }
+
+    /// Whether any confirmation would happen at all, ignoring which specific stage.
+    pub fn asks_anything(&self) -> bool {
+        !self.no_ask && !self.ask.is_empty()
+    }
 }
 
 #[derive(Debug, Args)]
@@ -219,6 +466,40 @@ pub struct OsGenerationsArgs {
     /// Path to Nix' profiles directory
     #[arg(long, short = 'P', default_value = "/nix/var/nix/profiles/system")]
     pub profile: Option<String>,
+
+    /// Field to sort generations by
+    #[arg(long, value_enum, default_value_t = crate::generations::GenerationSortField::Number)]
+    pub sort: crate::generations::GenerationSortField,
+
+    /// Reverse the sort order (default is ascending, so oldest first)
+    #[arg(long)]
+    pub reverse: bool,
+
+    /// Only show generations whose version/kernel/revision/specialisations contain this
+    /// substring (case-insensitive)
+    #[arg(long)]
+    pub filter: Option<String>,
+
+    /// Show each generation's closure size and the delta from the previous one, to spot when
+    /// the system started ballooning. Slower than a plain listing since it runs `nix path-info`
+    /// per generation (cached within the run, so repeats of the same store path are free).
+    #[arg(long)]
+    pub sizes: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = crate::tables::OutputFormat::Table)]
+    pub format: crate::tables::OutputFormat,
+
+    /// Don't truncate columns to fit the terminal width
+    #[arg(long)]
+    pub wide: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct OsSpecialisationsArgs {
+    /// Path to Nix' profiles directory
+    #[arg(long, short = 'P', default_value = "/nix/var/nix/profiles/system")]
+    pub profile: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -245,16 +526,212 @@ pub struct SearchArgs {
     /// Output results as JSON
     pub json: bool,
 
+    /// Only show packages whose license matches this (case-insensitive substring, e.g. "mit")
+    #[arg(long)]
+    pub license: Option<String>,
+
+    /// Only show packages available on this platform (e.g. "aarch64-linux")
+    #[arg(long)]
+    pub platform: Option<String>,
+
+    /// Only show packages that are (or aren't) marked broken
+    #[arg(long)]
+    pub broken: Option<bool>,
+
+    /// Find the package(s) that provide a program (binary) with this name, e.g. `gcc`. Cannot be
+    /// combined with a search query.
+    #[arg(long)]
+    pub program: Option<String>,
+
+    /// Print an install snippet for each hit, ready to paste into the given kind of config
+    #[arg(long, value_enum)]
+    pub snippet: Option<SearchSnippet>,
+
     /// Name of the package to search
     pub query: Vec<String>,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SearchSnippet {
+    /// A NixOS `environment.systemPackages` entry
+    Nixos,
+    /// A home-manager `home.packages` entry
+    Home,
+    /// A one-off `nix shell` invocation
+    Shell,
+}
+
 #[derive(Debug, Clone, ValueEnum)]
 pub enum SearchNixpkgsFrom {
     Flake,
     Path,
 }
 
+#[derive(Args, Debug)]
+#[clap(verbatim_doc_comment)]
+/// Compare two store paths, generations, or configurations
+///
+/// Each side accepts:
+///   - a /nix/store path (or a symlink to one, e.g. /run/current-system)
+///   - a generation reference, `<profile>#<number>` (profile may be the shorthand `system` or
+///     `home-manager`, or an explicit profile path), e.g. `system#42`
+///   - a flake installable, built if needed, e.g. `.#nixosConfigurations.myhost.config.system.build.toplevel`
+///
+/// Useful for "what would upgrading nixpkgs change" investigations without switching anything.
+pub struct DiffArgs {
+    /// First target to compare
+    pub left: String,
+
+    /// Second target to compare
+    pub right: String,
+
+    /// Print a machine-readable JSON summary instead of the human-readable `nvd` diff
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+#[clap(verbatim_doc_comment)]
+/// Trace why one closure depends on another, via `nix why-depends`
+///
+/// Accepts the same target syntax as `ng diff` for both sides (a generation reference, an
+/// existing path, or a flake installable to build), so you can point `package` at your system
+/// closure and `dependency` at whatever unwanted package you're trying to track down.
+pub struct WhyArgs {
+    /// The closure suspected of depending on `dependency`, e.g. `.#nixosConfigurations.myhost` or
+    /// `system#42`
+    pub package: String,
+
+    /// The suspected dependency to trace a path to
+    pub dependency: String,
+
+    /// Show the full dependency graph instead of stopping at the first path found
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+#[clap(verbatim_doc_comment)]
+/// Evaluate a Nix expression via `nix eval`
+///
+/// Pretty-prints attrsets by default. On failure, routes stderr through the same structured
+/// trace fetching used for build failures instead of dumping raw nix output.
+pub struct EvalArgs {
+    #[command(flatten)]
+    pub installable: Installable,
+
+    /// Extra attribute path to evaluate, appended to whatever `installable` already resolved to
+    #[arg(long)]
+    pub attr: Option<String>,
+
+    /// Print compact JSON instead of pretty-printed output
+    #[arg(long, conflicts_with = "raw")]
+    pub json: bool,
+
+    /// Print the raw string value (fails if the result isn't a string)
+    #[arg(long, conflicts_with = "json")]
+    pub raw: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+#[clap(verbatim_doc_comment)]
+/// Enter the configuration's devShell via `nix develop`
+///
+/// Resolves `devShells.<system>.default` for a flake installable (or `dev.shell` from
+/// `ng.toml`/`--shell` if set), then execs `nix develop` with the same installable resolution and
+/// env handling the rest of `ng` uses, so contributors have one entry point instead of remembering
+/// the underlying flake attribute path.
+pub struct DevelopArgs {
+    #[command(flatten)]
+    pub installable: Installable,
+
+    /// Devshell installable to enter, e.g. `.#devShells.x86_64-linux.default`. Overrides both the
+    /// default `devShells.<system>.default` resolution and `dev.shell` in `ng.toml`.
+    #[arg(long)]
+    pub shell: Option<String>,
+
+    /// Extra arguments passed through to `nix develop` (e.g. a command to run instead of an
+    /// interactive shell), after a literal `--`
+    #[arg(last = true)]
+    pub extra_args: Vec<String>,
+}
+
+/// Inspect ng's own `ng.toml` configuration
+#[derive(Debug, Args)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub subcommand: ConfigSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigSubcommand {
+    /// List every valid ng.toml key, its type, and what it does
+    Keys(ConfigKeysArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ConfigKeysArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = crate::tables::OutputFormat::Table)]
+    pub format: crate::tables::OutputFormat,
+
+    /// Don't truncate columns to fit the terminal width
+    #[arg(long)]
+    pub wide: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+#[clap(verbatim_doc_comment)]
+/// Run a flake's checks with enhanced failure reporting
+///
+/// Wraps `nix flake check --keep-going`, attributing each failure to its `checks.<system>`
+/// attribute and printing the failed derivation's build log inline, so a failure doesn't require
+/// a separate `nix log` invocation to diagnose.
+pub struct CheckArgs {
+    #[command(flatten)]
+    pub installable: Installable,
+}
+
+#[derive(Args, Debug, Clone)]
+#[clap(verbatim_doc_comment)]
+/// Explain why a derivation would rebuild, by diffing its inputs against the previous generation
+///
+/// Resolves `installable` to a derivation without building it, finds the derivation behind the
+/// currently-active generation, and compares the two with `nix-diff` if it's installed (falling
+/// back to a plain `nix derivation show` diff otherwise) to show which input or environment
+/// change is driving the rebuild.
+pub struct WhyRebuildArgs {
+    #[command(flatten)]
+    pub installable: Installable,
+
+    /// Profile to compare against instead of the current system/home-manager generation, e.g.
+    /// `/nix/var/nix/profiles/system` or a specific `...-link` generation path
+    #[arg(long)]
+    pub against: Option<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+#[clap(verbatim_doc_comment)]
+/// Show a disk-usage breakdown of a closure, via `nix path-info --recursive`
+///
+/// Lists every store path in `installable`'s closure with its own NAR size, sorted largest
+/// first. This is each path's own size, not its exclusive contribution to the closure — a
+/// library shared by two packages is counted in full under both, so the total of all rows can
+/// exceed the closure's actual disk footprint. Useful for spotting what's bloating a closure,
+/// not for precisely accounting for it.
+pub struct DuArgs {
+    #[command(flatten)]
+    pub installable: Installable,
+
+    /// Number of largest store paths to show
+    #[arg(long, short = 'n', default_value_t = 20)]
+    pub top: usize,
+
+    /// Print the full sorted list as JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
 // Needed a struct to have multiple sub-subcommands
 #[derive(Args, Debug, Clone)]
 pub struct CleanProxy {
@@ -271,6 +748,100 @@ pub enum CleanMode {
     User(CleanArgs),
     /// Clean a specific profile, run garbage collection, and optimize the nix store
     Profile(CleanProfileArgs),
+    /// Generate (and optionally install) a systemd timer or launchd plist to run `ng clean` on a schedule
+    Schedule(CleanScheduleArgs),
+}
+
+// Needed a struct to have multiple sub-subcommands
+#[derive(Args, Debug, Clone)]
+pub struct StoreProxy {
+    #[clap(subcommand)]
+    command: StoreMode,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+/// Low-level nix store maintenance
+pub enum StoreMode {
+    /// Deduplicate identical files in the nix store via hard-linking, with progress and a savings summary
+    Optimise(StoreOptimiseArgs),
+    /// Verify the integrity and signatures of store paths, reporting problems and repair commands
+    Verify(StoreVerifyArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+#[clap(verbatim_doc_comment)]
+/// Verify the integrity and signatures of store paths, via `nix store verify`
+///
+/// Verifies every path in the store by default; pass specific paths to check a subset instead.
+/// Corrupted and untrusted paths are reported in a table with a suggested `nix store repair`
+/// invocation for each, instead of leaving raw `nix store verify` output to parse by hand.
+pub struct StoreVerifyArgs {
+    /// Specific store paths to verify, instead of the whole store
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+#[clap(verbatim_doc_comment)]
+/// Deduplicate identical files in the nix store, via `nix store optimise`
+///
+/// Runs the same optimisation `ng clean` performs by default (unless `--nooptimise` is passed
+/// there), as a standalone command with a progress spinner and a final hard-linked/saved bytes
+/// summary. Useful for running optimisation on its own, without also touching generations or GC.
+pub struct StoreOptimiseArgs {
+    /// Only print the command that would run, without performing it
+    #[arg(long, short = 'n')]
+    pub dry: bool,
+}
+
+/// Which `ng clean` mode a scheduled unit should invoke.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CleanScheduleMode {
+    /// `ng clean all`
+    All,
+    /// `ng clean user`
+    User,
+}
+
+impl fmt::Display for CleanScheduleMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CleanScheduleMode::All => write!(f, "all"),
+            CleanScheduleMode::User => write!(f, "user"),
+        }
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+#[clap(verbatim_doc_comment)]
+/// Generate (and optionally install) a systemd timer or launchd plist invoking `ng clean` on a
+/// schedule, so automatic GC configuration doesn't require hand-writing units.
+///
+/// Without --install, the generated unit(s) are printed to stdout. With --install, they're
+/// written to the system unit directories (elevating via sudo if needed), then enabled.
+pub struct CleanScheduleArgs {
+    /// Run daily
+    #[arg(long, conflicts_with_all = ["weekly", "monthly"])]
+    pub daily: bool,
+
+    /// Run weekly
+    #[arg(long, conflicts_with_all = ["daily", "monthly"])]
+    pub weekly: bool,
+
+    /// Run monthly
+    #[arg(long, conflicts_with_all = ["daily", "weekly"])]
+    pub monthly: bool,
+
+    /// Which `ng clean` mode the scheduled unit should invoke
+    #[arg(long, value_enum, default_value_t = CleanScheduleMode::All)]
+    pub mode: CleanScheduleMode,
+
+    /// At least keep this number of generations, passed through to the scheduled `ng clean` invocation
+    #[arg(long, short, default_value = "5")]
+    pub keep: u32,
+
+    /// Write the unit(s) to the system and enable them, instead of just printing them
+    #[arg(long)]
+    pub install: bool,
 }
 
 #[derive(Args, Clone, Debug)]
@@ -313,6 +884,11 @@ pub struct CleanArgs {
     /// Don't clean gcroots
     #[arg(long)]
     pub nogcroots: bool,
+
+    /// Interactively choose which deletable items (old generations, dangling gcroots) to
+    /// remove, with their closure sizes shown, instead of the all-or-nothing default
+    #[arg(long, short)]
+    pub interactive: bool,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -341,6 +917,9 @@ pub enum HomeSubcommand {
 
     /// Load a home-manager configuration in a Nix REPL
     Repl(HomeReplArgs),
+
+    /// List available generations from profile path
+    Generations(HomeGenerationsArgs),
 }
 
 #[derive(Debug, Args)]
@@ -370,6 +949,44 @@ pub struct HomeRebuildArgs {
     /// Move existing files by backing up with this file extension
     #[arg(long, short = 'b')]
     pub backup_extension: Option<String>,
+
+    /// Activate another user's home-manager configuration instead of $USER's
+    ///
+    /// Overrides the $USER-based attribute probing in `toplevel_for` (tries
+    /// `<user>@<hostname>` then `<user>`) and the per-user profile path used for the
+    /// generation diff and activation. Activating a configuration for a user other than the
+    /// one running `ng` requires the "activation" stage to be allowed to elevate in ng.toml.
+    #[arg(long)]
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct HomeGenerationsArgs {
+    /// Path to the home-manager profile directory. Defaults to whichever of the per-user Nix
+    /// profile or the XDG state profile exists on this system.
+    #[arg(long, short = 'P')]
+    pub profile: Option<String>,
+
+    /// Field to sort generations by
+    #[arg(long, value_enum, default_value_t = crate::generations::GenerationSortField::Number)]
+    pub sort: crate::generations::GenerationSortField,
+
+    /// Reverse the sort order (default is ascending, so oldest first)
+    #[arg(long)]
+    pub reverse: bool,
+
+    /// Only show generations whose version/kernel/revision/specialisations contain this
+    /// substring (case-insensitive)
+    #[arg(long)]
+    pub filter: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = crate::tables::OutputFormat::Table)]
+    pub format: crate::tables::OutputFormat,
+
+    /// Don't truncate columns to fit the terminal width
+    #[arg(long)]
+    pub wide: bool,
 }
 
 #[derive(Debug, Args)]
@@ -429,6 +1046,12 @@ pub struct DarwinRebuildArgs {
     /// Extra arguments passed to nix build
     #[arg(last = true)]
     pub extra_args: Vec<String>,
+
+    /// Install into `/nix/var/nix/profiles/system-profiles/<name>` instead of the default system
+    /// profile, so this configuration gets its own boot menu entry alongside the default one
+    /// instead of replacing it — handy for side-by-side experimental configs.
+    #[arg(long)]
+    pub profile_name: Option<String>,
 }
 
 #[derive(Debug, Args)]
@@ -460,7 +1083,68 @@ pub struct FormatArgs {
     #[clap(long, short, action)]
     pub apply: bool,
 
+    /// Only consider files with uncommitted changes (`git diff --name-only` against HEAD),
+    /// instead of every file under `path`.
+    #[clap(long)]
+    pub changed_only: bool,
+
+    /// Print a unified diff of what would change for each unformatted file, instead of just
+    /// listing their paths. Implies check-only; combine with `--apply` to format and print
+    /// nothing.
+    #[clap(long)]
+    pub check: bool,
+
     /// The path to start formatting from (defaults to the current directory)
     #[clap(default_value = ".")]
     pub path: String,
 }
+
+#[derive(Debug, Args)]
+/// Check for and apply updates to the ng binary itself.
+///
+/// Detects how ng was installed (a mutable `nix profile`, a flake input, or a local `cargo`
+/// build) and performs the appropriate update, or reports what update is needed.
+pub struct SelfUpdateArgs {
+    /// Only report whether a newer release exists, without changing anything
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InitTemplate {
+    /// A NixOS system configuration
+    Nixos,
+    /// A NixOS system configuration with home-manager as a module
+    NixosHome,
+    /// A nix-darwin system configuration
+    Darwin,
+    /// A standalone home-manager configuration, with no system configuration
+    StandaloneHome,
+}
+
+#[derive(Debug, Args)]
+/// Scaffold a new flake configuration.
+///
+/// Writes a minimal `flake.nix`, a hardware placeholder (for the NixOS templates), and an
+/// `ng.toml`, prompting for anything not passed on the command line.
+pub struct InitArgs {
+    /// Which kind of configuration to scaffold
+    #[arg(long, short, value_enum)]
+    pub template: Option<InitTemplate>,
+
+    /// Hostname the configuration will be built for
+    #[arg(long, short = 'H')]
+    pub hostname: Option<String>,
+
+    /// Username the home-manager configuration will be built for
+    #[arg(long, short)]
+    pub username: Option<String>,
+
+    /// Directory to scaffold into
+    #[arg(default_value = ".")]
+    pub directory: PathBuf,
+
+    /// Overwrite files that already exist in the target directory
+    #[arg(long)]
+    pub force: bool,
+}
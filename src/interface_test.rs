@@ -5,6 +5,14 @@ mod tests {
     use clap::Parser;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_command_definition_is_valid() {
+        // Catches malformed arg definitions (e.g. duplicate flags, conflicting `required`/
+        // `default_value`) that would otherwise only surface at runtime the first time someone
+        // runs `ng --help` or `ng <subcommand> --help`.
+        <Main as clap::CommandFactory>::command().debug_assert();
+    }
+
     #[test]
     fn test_main_verbosity() {
         let args = vec!["nh", "-vv", "search", "query"];
@@ -22,7 +30,7 @@ mod tests {
             if let OsSubcommand::Switch(rebuild_args) = os_args.subcommand {
                 assert_eq!(rebuild_args.hostname.as_deref(), Some("myHost"));
                 assert!(rebuild_args.common.common.no_nom);
-                assert!(rebuild_args.common.common.ask); // ask is true by default
+                assert!(rebuild_args.common.common.should_ask(crate::interface::ConfirmStage::Activate)); // ask defaults to confirming activation
                 assert_eq!(rebuild_args.update_args.update, false); // Default
                 if let Installable::Flake { reference, attribute } = rebuild_args.common.installable {
                     assert_eq!(reference, ".");
@@ -148,6 +156,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_expand_aliases_expands_matching_first_arg() {
+        let mut aliases = std::collections::HashMap::new();
+        aliases.insert("up".to_string(), "os switch --update".to_string());
+
+        let args = vec!["nh".to_string(), "up".to_string(), "--ask".to_string()];
+        let expanded = Main::expand_aliases(args, &aliases);
+
+        assert_eq!(expanded, vec!["nh", "os", "switch", "--update", "--ask"]);
+    }
+
+    #[test]
+    fn test_expand_aliases_leaves_non_matching_args_untouched() {
+        let aliases = std::collections::HashMap::new();
+        let args = vec!["nh".to_string(), "search".to_string(), "query".to_string()];
+        let expanded = Main::expand_aliases(args.clone(), &aliases);
+
+        assert_eq!(expanded, args);
+    }
+
+    #[test]
+    fn test_aliases_help_none_when_empty() {
+        assert!(Main::aliases_help(&std::collections::HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_aliases_help_lists_configured_aliases() {
+        let mut aliases = std::collections::HashMap::new();
+        aliases.insert("up".to_string(), "os switch --update".to_string());
+
+        let help = Main::aliases_help(&aliases).unwrap();
+        assert!(help.contains("up = os switch --update"));
+    }
+
     // TODO: Add tests for DarwinArgs, CleanProxy/CleanArgs, CompletionsArgs
     // TODO: Add tests for more OsSubcommands like Repl, Info
     // TODO: Add tests for more HomeSubcommands like Build, Repl
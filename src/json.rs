@@ -1,4 +1,173 @@
 use std::fmt::Display;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Structured summary of a completed `os`/`darwin`/`home` rebuild, printed as a single JSON
+/// line when `--json` is passed so fleet tooling can ingest per-host results without scraping
+/// human-readable log output.
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationSummary {
+    /// Platform strategy name, e.g. "NixOS", "Darwin", or "home-manager".
+    pub platform: String,
+    /// Activation mode that was requested, e.g. "Switch", "Boot", "Test", "Build".
+    pub mode: String,
+    /// Store path of the built configuration.
+    pub built_path: PathBuf,
+    /// Number of the generation the activation created, if activation happened and the
+    /// platform tracks generations.
+    pub generation: Option<String>,
+    /// Package-level diff against the previously active generation, if one was computed.
+    pub diff: Option<DiffSummary>,
+    /// Wall-clock time for the whole operation, in seconds.
+    pub duration_secs: f64,
+    /// Names of the pre-flight checks configured to run for this invocation (empty if
+    /// `--no-preflight` was passed).
+    pub checks_run: Vec<String>,
+}
+
+/// Counts of packages added, removed, or version-changed between two closures.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct DiffSummary {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+impl From<crate::nix_interface::ClosureDiff> for DiffSummary {
+    fn from(diff: crate::nix_interface::ClosureDiff) -> Self {
+        Self {
+            added: diff.added,
+            removed: diff.removed,
+            changed: diff.changed,
+        }
+    }
+}
+
+/// Structured summary printed by `ng diff --json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffCommandSummary {
+    /// The resolved store path of `left`.
+    pub left: PathBuf,
+    /// The resolved store path of `right`.
+    pub right: PathBuf,
+    /// Package-level diff between `left` and `right`.
+    pub diff: DiffSummary,
+}
+
+/// A single entry from `nix path-info --json`. Lives here rather than in `nix_interface` so it
+/// sits alongside the rest of this module's typed nix JSON output models; `nix_interface`
+/// re-exports it under its old path for existing call sites.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StorePathInfo {
+    pub path: PathBuf,
+    #[serde(rename = "narHash")]
+    pub nar_hash: String,
+    #[serde(rename = "narSize")]
+    pub nar_size: u64,
+    #[serde(default)]
+    pub deriver: Option<PathBuf>,
+    #[serde(default, rename = "closureSize")]
+    pub closure_size: Option<u64>,
+}
+
+/// A single entry from `nix build --json`: the derivation and the store paths its outputs
+/// realized to. Used by [`crate::nix_interface::NixInterface::build_configuration`] to obtain the
+/// authoritative build result instead of scraping `--print-out-paths` stdout or trusting the
+/// `./result` symlink convention.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BuildResult {
+    #[serde(rename = "drvPath")]
+    pub drv_path: PathBuf,
+    pub outputs: std::collections::BTreeMap<String, PathBuf>,
+}
+
+impl BuildResult {
+    /// The realized store path for the `"out"` output, falling back to whichever output sorts
+    /// first if there's no `"out"` (e.g. a derivation that only names other outputs).
+    pub fn primary_output(&self) -> Option<&PathBuf> {
+        self.outputs.get("out").or_else(|| self.outputs.values().next())
+    }
+}
+
+/// A subset of `nix flake metadata --json` output. Not consumed anywhere yet — added as a typed
+/// target for a future feature that needs a flake's resolved revision or description (e.g.
+/// showing what a `path:`/`git+` reference actually pins), so that shape doesn't need
+/// re-litigating when the first real caller shows up.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FlakeMetadata {
+    pub description: Option<String>,
+    #[serde(rename = "lastModified")]
+    pub last_modified: Option<u64>,
+    /// The resolved `locked` input spec (type, owner/repo or url, rev, ...). Left untyped since
+    /// its shape already varies by input type and is handled ad hoc via `serde_json::Value`
+    /// elsewhere (see [`crate::release_notes::locked_nodes`]).
+    #[serde(default)]
+    pub locked: serde_json::Value,
+    #[serde(rename = "originalUrl")]
+    pub original_url: Option<String>,
+    pub path: Option<PathBuf>,
+    #[serde(rename = "resolvedUrl")]
+    pub resolved_url: Option<String>,
+    pub revision: Option<String>,
+    pub url: Option<String>,
+}
+
+/// A single node of `nix flake show --json`'s output tree: either a leaf describing one flake
+/// output (a derivation, app, etc.) or a further-nested set of attributes. Not consumed anywhere
+/// yet; see [`FlakeMetadata`] for why this is here ahead of a call site.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum FlakeShowNode {
+    Leaf {
+        #[serde(rename = "type")]
+        kind: String,
+        name: Option<String>,
+    },
+    Branch(std::collections::BTreeMap<String, FlakeShowNode>),
+}
+
+/// A single parsed line of `nix ... --log-format internal-json` output, with the `@nix ` prefix
+/// nix prepends to every structured line already stripped off. Not consumed anywhere yet: `ng`'s
+/// only internal-json producer (`commands::Build`) pipes the raw stream straight to an external
+/// renderer instead of parsing it itself. This is here so a future build-summary feature (e.g.
+/// reporting which derivations were substituted vs built, live, instead of from a pre-build dry
+/// run) doesn't need to reinvent the format.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum InternalLogEvent {
+    Start {
+        id: u64,
+        level: Option<u8>,
+        #[serde(rename = "type")]
+        kind: Option<u32>,
+        text: Option<String>,
+        #[serde(default)]
+        fields: Vec<serde_json::Value>,
+    },
+    Stop {
+        id: u64,
+    },
+    Result {
+        id: u64,
+        #[serde(rename = "type")]
+        kind: Option<u32>,
+        #[serde(default)]
+        fields: Vec<serde_json::Value>,
+    },
+    Msg {
+        level: Option<u8>,
+        msg: Option<String>,
+    },
+}
+
+/// Parses a single line of `nix --log-format internal-json` output, stripping the `@nix ` prefix
+/// nix adds to every structured line. Returns `None` for lines nix didn't tag this way (plain
+/// stderr text it may interleave) or that don't match a known `action`.
+pub fn parse_internal_json_line(line: &str) -> Option<InternalLogEvent> {
+    let json = line.strip_prefix("@nix ")?;
+    serde_json::from_str(json).ok()
+}
 
 #[derive(Debug, Clone)]
 pub struct Value<'v> {
@@ -50,6 +219,19 @@ impl<'v> Value<'v> {
     }
 }
 
+#[test]
+fn test_parse_internal_json_line() {
+    assert!(matches!(
+        parse_internal_json_line(r#"@nix {"action":"start","id":1,"level":4,"type":100,"text":"building foo","fields":[]}"#),
+        Some(InternalLogEvent::Start { id: 1, .. })
+    ));
+    assert!(matches!(
+        parse_internal_json_line(r#"@nix {"action":"stop","id":1}"#),
+        Some(InternalLogEvent::Stop { id: 1 })
+    ));
+    assert!(parse_internal_json_line("building '/nix/store/foo.drv'...").is_none());
+}
+
 #[test]
 fn test_value() {
     let input = serde_json::json!({
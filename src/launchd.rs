@@ -0,0 +1,103 @@
+//! Post-activation health check for launchd services managed by nix-darwin.
+//!
+//! nix-darwin's `launchd.agents`/`launchd.daemons` (and the higher-level `services.*` options
+//! built on them) install plists labelled `org.nixos.<name>` into the standard launchd search
+//! paths. This module cross-references those labels against `launchctl list` after activation,
+//! since a service that failed to load, or crashed immediately, otherwise goes unnoticed.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use tracing::debug;
+
+use crate::commands::Command;
+use crate::Result;
+
+const LAUNCHD_LABEL_PREFIX: &str = "org.nixos.";
+
+const PLIST_DIRS: &[&str] = &["/Library/LaunchDaemons", "/Library/LaunchAgents"];
+
+/// Labels of the launchd services nix-darwin has installed plists for, gathered from the
+/// system-wide search paths. `~/Library/LaunchAgents` is intentionally not scanned here, since
+/// this runs from the invoking user's context and per-user agents outside `sudo` wouldn't be
+/// the ones this activation just (re)installed.
+pub fn list_managed_labels() -> Vec<String> {
+    let mut labels = Vec::new();
+    for dir in PLIST_DIRS {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(std::result::Result::ok) {
+            let path = entry.path();
+            if let Some(label) = label_from_plist_path(&path) {
+                if label.starts_with(LAUNCHD_LABEL_PREFIX) {
+                    labels.push(label);
+                }
+            }
+        }
+    }
+    labels
+}
+
+fn label_from_plist_path(path: &Path) -> Option<String> {
+    if path.extension().and_then(|e| e.to_str()) != Some("plist") {
+        return None;
+    }
+    path.file_stem()?.to_str().map(str::to_string)
+}
+
+/// A launchd service that isn't healthy after activation.
+#[derive(Debug, Clone)]
+pub struct UnhealthyService {
+    pub label: String,
+    pub reason: String,
+}
+
+/// Cross-references `labels` against `launchctl list`, returning those that aren't loaded or
+/// that last exited with a non-zero status.
+pub fn check_service_health(labels: &[String]) -> Result<Vec<UnhealthyService>> {
+    if labels.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let output = Command::new("launchctl")
+        .arg("list")
+        .run_capture()?
+        .unwrap_or_default();
+
+    // Each line is "PID\tLastExitStatus\tLabel"; PID is "-" when not running.
+    let mut loaded: HashMap<String, String> = HashMap::new();
+    for line in output.lines().skip(1) {
+        let mut fields = line.split('\t');
+        let _pid = fields.next();
+        let Some(status) = fields.next() else {
+            continue;
+        };
+        let Some(label) = fields.next() else {
+            continue;
+        };
+        loaded.insert(label.trim().to_string(), status.trim().to_string());
+    }
+
+    let mut unhealthy = Vec::new();
+    for label in labels {
+        match loaded.get(label) {
+            None => unhealthy.push(UnhealthyService {
+                label: label.clone(),
+                reason: "not loaded".to_string(),
+            }),
+            Some(status) if status != "0" && status != "-" => unhealthy.push(UnhealthyService {
+                label: label.clone(),
+                reason: format!("last exit status {status}"),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    debug!(
+        "Checked {} launchd service(s), {} unhealthy",
+        labels.len(),
+        unhealthy.len()
+    );
+    Ok(unhealthy)
+}
@@ -43,12 +43,29 @@ pub mod nixos;
 pub mod home;
 pub mod darwin;
 pub mod clean;
+pub mod clean_schedule;
 pub mod search;
 pub mod update;
 pub mod generations;
+pub mod diff_cmd;
+pub mod eval_cmd;
+pub mod check_cmd;
+pub mod why_rebuild_cmd;
+pub mod why_depends_cmd;
+pub mod du_cmd;
+pub mod store_verify_cmd;
 pub mod installable;
 pub mod json;
 pub mod completion;
+pub mod self_update;
+pub mod release_notes;
+pub mod prefetch;
+pub mod specialisation;
+pub mod launchd;
+pub mod home_news;
+pub mod init;
+pub mod develop_cmd;
+pub mod config_cmd;
 
 // Re-export color_eyre::Result for convenience
 pub use color_eyre::Result;
@@ -57,13 +74,59 @@ pub use color_eyre::Result;
 pub const NG_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const NG_REV: Option<&str> = option_env!("NG_REV");
 
+/// Environment variables `self_elevate` always preserves across the `sudo` re-exec, on top of
+/// whatever the user adds via `elevation.preserve_env` in `ng.toml`. Without this, `sudo`'s
+/// default environment reset drops `NIX_PATH`, drops `SSH_AUTH_SOCK` (breaking builds that need
+/// an SSH agent, e.g. for private flake inputs), and drops every `NH_*` flake env var, so the
+/// elevated run silently behaves differently from the one the user actually typed.
+const DEFAULT_PRESERVED_ENV_VARS: &[&str] = &["NIX_PATH", "SSH_AUTH_SOCK"];
+
+fn preserved_env_vars(extra: &[String]) -> Vec<String> {
+    let mut vars: Vec<String> = DEFAULT_PRESERVED_ENV_VARS.iter().map(|s| s.to_string()).collect();
+    for (key, _) in std::env::vars() {
+        if key.starts_with("NH_") && !vars.contains(&key) {
+            vars.push(key);
+        }
+    }
+    for extra_var in extra {
+        if !vars.contains(extra_var) {
+            vars.push(extra_var.clone());
+        }
+    }
+    vars
+}
+
 /// Elevate privileges using sudo
 pub fn self_elevate() -> ! {
     use std::os::unix::process::CommandExt;
 
+    let preserve = preserved_env_vars(&config::NgConfig::load().elevation.preserve_env);
+
     let mut cmd = std::process::Command::new("sudo");
+    if !preserve.is_empty() {
+        cmd.arg(format!("--preserve-env={}", preserve.join(",")));
+    }
     cmd.args(std::env::args());
     tracing::debug!("{:?}", cmd);
     let err = cmd.exec();
     panic!("{}", err);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preserved_env_vars_includes_defaults_and_extra() {
+        let vars = preserved_env_vars(&["MY_CUSTOM_VAR".to_string()]);
+        assert!(vars.contains(&"NIX_PATH".to_string()));
+        assert!(vars.contains(&"SSH_AUTH_SOCK".to_string()));
+        assert!(vars.contains(&"MY_CUSTOM_VAR".to_string()));
+    }
+
+    #[test]
+    fn test_preserved_env_vars_dedupes_user_supplied_default() {
+        let vars = preserved_env_vars(&["NIX_PATH".to_string()]);
+        assert_eq!(vars.iter().filter(|v| *v == "NIX_PATH").count(), 1);
+    }
 }
\ No newline at end of file
@@ -1,4 +1,7 @@
+use color_eyre::eyre::eyre;
+use opentelemetry_otlp::WithExportConfig;
 use owo_colors::OwoColorize;
+use std::path::PathBuf;
 use tracing::Event;
 use tracing::Level;
 use tracing::Subscriber;
@@ -8,8 +11,11 @@ use tracing_subscriber::fmt;
 use tracing_subscriber::fmt::FormatEvent;
 use tracing_subscriber::fmt::FormatFields;
 use tracing_subscriber::prelude::*;
+use tracing_subscriber::layer::Filter;
 use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::EnvFilter;
+use tracing_subscriber::Layer;
+use tracing_subscriber::Registry;
 
 use crate::*;
 
@@ -52,28 +58,122 @@ where
     }
 }
 
-pub fn setup_logging(verbose_level: u8) -> Result<()> {
+/// Resolves `~/.local/state/ng/logs/`, returning `None` if `$HOME` can't be resolved.
+fn file_log_dir() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".local/state/ng/logs"))
+}
+
+/// Builds the journald layer when `enable_journald` is set and journald is reachable
+/// (Linux only). Entries are tagged with syslog identifier "ng" so `journalctl -t ng`
+/// shows deployment history.
+#[cfg(target_os = "linux")]
+fn journald_layer(enable_journald: bool) -> Option<tracing_journald::Layer> {
+    if !enable_journald {
+        return None;
+    }
+    match tracing_journald::layer() {
+        Ok(layer) => Some(layer.with_syslog_identifier("ng".to_string())),
+        Err(e) => {
+            eprintln!("Warning: journald logging requested but unavailable: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn journald_layer(enable_journald: bool) -> Option<()> {
+    if enable_journald {
+        eprintln!("Warning: journald logging is only supported on Linux, ignoring");
+    }
+    None
+}
+
+/// Builds an OTLP span exporter layer when `enable_otel` is set, so workflow stage spans
+/// (pre-flight, eval, build, diff, activate — see `workflow_executor::execute_rebuild_workflow`)
+/// can be traced by an observability stack. Uses a synchronous exporter (no async runtime is
+/// otherwise pulled into this CLI) so spans are sent as they end, not batched.
+fn otel_layer(
+    enable_otel: bool,
+    otel_endpoint: Option<&str>,
+) -> Option<tracing_opentelemetry::OpenTelemetryLayer<Registry, opentelemetry_sdk::trace::Tracer>> {
+    if !enable_otel {
+        return None;
+    }
+
+    let endpoint = otel_endpoint
+        .map(str::to_string)
+        .or_else(|| std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok())
+        .unwrap_or_else(|| "http://localhost:4318".to_string());
+
+    let provider = match opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().http().with_endpoint(&endpoint))
+        .with_trace_config(opentelemetry_sdk::trace::Config::default().with_resource(
+            opentelemetry_sdk::Resource::new(vec![opentelemetry::KeyValue::new(
+                "service.name",
+                "ng",
+            )]),
+        ))
+        .install_simple()
+    {
+        Ok(provider) => provider,
+        Err(e) => {
+            eprintln!("Warning: OTLP span export requested but exporter init failed: {e}");
+            return None;
+        }
+    };
+
+    let tracer = opentelemetry::trace::TracerProvider::tracer(&provider, "ng");
+    Some(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Sets up terminal logging plus, when `enable_file_logging` is set, a `debug`-level,
+/// ANSI-free log file rotated daily under `~/.local/state/ng/logs/`, so full logs can be
+/// attached to bug reports even when the terminal only showed info level.
+///
+/// `log_filter`, when set (via `--log-filter`/`--log-level`), takes a simple level name or an
+/// `EnvFilter`-syntax string (e.g. `"ng::pre_flight=debug,ng::commands=trace"`) and overrides
+/// `-v`, `NG_LOG`, and `RUST_LOG` for per-module verbosity control. Absent that, an event is
+/// shown if any of `NG_LOG`, the standard `RUST_LOG`, or `-v`'s debug/trace counting enables it.
+pub fn setup_logging(
+    verbose_level: u8,
+    enable_file_logging: bool,
+    log_filter: Option<&str>,
+    enable_journald: bool,
+    enable_otel: bool,
+    otel_endpoint: Option<&str>,
+    quiet: bool,
+) -> Result<()> {
     color_eyre::config::HookBuilder::default()
         .display_location_section(true)
         .panic_section("Please report the bug at https://github.com/viperML/ng/issues")
         .display_env_section(false)
         .install()?;
-    
+
     let is_debug = verbose_level > 0;
     let is_trace = verbose_level > 1;
-    
+
+    let debug_filter: Box<dyn Filter<Registry> + Send + Sync> = match log_filter {
+        Some(spec) => Box::new(
+            EnvFilter::try_new(spec).map_err(|e| eyre!("Invalid --log-filter '{spec}': {e}"))?,
+        ),
+        None => Box::new(
+            EnvFilter::from_env("NG_LOG")
+                .or(EnvFilter::from_env("RUST_LOG"))
+                .or(filter_fn(move |meta| {
+                    let level = *meta.level();
+                    (is_debug && level == Level::DEBUG) || (is_trace && level == Level::TRACE)
+                })),
+        ),
+    };
+
     let layer_debug = fmt::layer()
         .with_writer(std::io::stderr)
         .without_time()
         .compact()
         .with_line_number(true)
-        .with_filter(
-            EnvFilter::from_env("NG_LOG").or(filter_fn(move |meta| {
-                let level = *meta.level();
-                (is_debug && level == Level::DEBUG) ||
-                (is_trace && level == Level::TRACE)
-            }))
-        );
+        .with_filter(debug_filter);
 
     let layer_info = fmt::layer()
         .with_writer(std::io::stderr)
@@ -81,15 +181,39 @@ pub fn setup_logging(verbose_level: u8) -> Result<()> {
         .with_target(false)
         .with_level(false)
         .event_format(InfoFormatter)
-        .with_filter(filter_fn(|meta| {
+        .with_filter(filter_fn(move |meta| {
             let level = *meta.level();
-            (level == Level::INFO) || (level == Level::WARN)
+            (level == Level::INFO && !quiet) || (level == Level::WARN)
         }));
 
-    tracing_subscriber::registry()
-        .with(layer_debug)
-        .with(layer_info)
-        .init();
+    let layer_file = enable_file_logging.then(file_log_dir).flatten().map(|dir| {
+        let appender = tracing_appender::rolling::daily(dir, "ng.log");
+        fmt::layer()
+            .with_writer(appender)
+            .with_ansi(false)
+            .with_line_number(true)
+            .with_filter(filter_fn(|meta| {
+                matches!(*meta.level(), Level::ERROR | Level::WARN | Level::INFO | Level::DEBUG)
+            }))
+    });
+
+    let layer_journald = journald_layer(enable_journald);
+    let layer_otel = otel_layer(enable_otel, otel_endpoint);
+
+    // `layer_debug`'s filter is boxed as `dyn Filter<Registry>`, which pins its `Layer` impl to
+    // the bare `Registry` rather than any subscriber built up by chaining `.with()`. Boxing every
+    // layer down to `dyn Layer<Registry>` and combining them through a `Vec` (which itself
+    // implements `Layer<S>` for any `S` its elements support) sidesteps that instead of forcing
+    // every layer above to be generic.
+    let all_layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> = vec![
+        layer_debug.boxed(),
+        layer_info.boxed(),
+        layer_file.boxed(),
+        layer_journald.boxed(),
+        layer_otel.boxed(),
+    ];
+
+    tracing_subscriber::registry().with(all_layers).init();
 
     tracing::trace!("Logging OK");
 
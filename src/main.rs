@@ -1,13 +1,37 @@
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
 use color_eyre::eyre::Result;
 use ng::interface::Main;
 
 fn main() -> Result<()> {
-    color_eyre::install()?;
-    
-    // Parse command line arguments using the interface::Main struct
-    let cli = Main::parse();
-    
+    let config = ng::config::NgConfig::load();
+
+    // Expand any `[aliases]` from ng.toml before clap ever sees the arguments, and surface the
+    // configured aliases in `--help`.
+    let raw_args: Vec<String> = std::env::args().collect();
+    let expanded_args = Main::expand_aliases(raw_args, &config.aliases);
+
+    let mut command = Main::command();
+    if let Some(help) = Main::aliases_help(&config.aliases) {
+        command = command.after_help(help);
+    }
+    let matches = command.get_matches_from(expanded_args);
+    let cli = Main::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    ng::progress::init_progress_mode(cli.progress);
+    ng::progress::init_quiet_mode(cli.quiet);
+    ng::ui_style::init_color(cli.color);
+
+    ng::ui_style::init_theme(&config.ui.theme);
+    ng::logging::setup_logging(
+        cli.verbose,
+        config.logging.file_logging,
+        cli.log_filter.as_deref(),
+        config.logging.journald,
+        config.logging.otel,
+        config.logging.otel_endpoint.as_deref(),
+        cli.quiet,
+    )?;
+
     // Run the command with the specified verbosity level
     cli.command.run(cli.verbose)
 }
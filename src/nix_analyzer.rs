@@ -1,9 +1,12 @@
 use std::{
     collections::HashMap,
+    hash::{Hash, Hasher},
     path::{Path, PathBuf},
     sync::Arc,
+    time::SystemTime,
 };
 
+
 use ide::{
     AnalysisHost,
     Change,
@@ -41,11 +44,40 @@ pub struct NgDiagnostic {
     pub severity: NgSeverity,
 }
 
-/// Central hub for Nix code analysis
+/// A previously computed syntax parse for a file, kept around as long as the file's mtime and
+/// content hash haven't changed, so a second pre-flight check sharing this context doesn't
+/// re-parse a file the first check already parsed this run.
+struct CachedParse {
+    mtime: SystemTime,
+    content_hash: u64,
+    source_file: Arc<SourceFile>,
+    errors: Vec<SyntaxErrorFull>,
+}
+
+/// Central hub for Nix code analysis.
+///
+/// Meant to be constructed once per `ng` invocation (see
+/// [`crate::context::OperationContext::nix_analysis_context`]) and shared across every check
+/// that needs it, rather than each check building its own and re-parsing every file from
+/// scratch. `parse_file_with_syntax` skips re-parsing a file whose mtime and content hash match
+/// its last parse *within this context's lifetime*; there's currently no on-disk cache, so this
+/// doesn't carry over across separate `ng` invocations (`ide`/`syntax`'s parse trees and
+/// diagnostics aren't serializable, so caching them across processes isn't a small addition).
 pub struct NixAnalysisContext {
     db: AnalysisHost,
     file_map: HashMap<PathBuf, FileId>,
     next_file_id: u32,
+    parse_cache: HashMap<PathBuf, CachedParse>,
+}
+
+impl std::fmt::Debug for NixAnalysisContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NixAnalysisContext")
+            .field("file_map", &self.file_map)
+            .field("next_file_id", &self.next_file_id)
+            .field("parse_cache_len", &self.parse_cache.len())
+            .finish_non_exhaustive()
+    }
 }
 
 impl NixAnalysisContext {
@@ -54,6 +86,7 @@ impl NixAnalysisContext {
             db: AnalysisHost::default(), // Use AnalysisHost
             file_map: HashMap::new(),
             next_file_id: 0,
+            parse_cache: HashMap::new(),
         }
     }
 
@@ -70,12 +103,31 @@ impl NixAnalysisContext {
         file_id
     }
 
-    /// Parse a file with nil-syntax
+    /// Parse a file with nil-syntax, reusing the previous parse if `path`'s mtime and content
+    /// hash haven't changed since it was last parsed through this context.
     pub fn parse_file_with_syntax(
         &mut self,
         path: &Path,
         content: Arc<String>,
     ) -> (FileId, Arc<SourceFile>, Vec<SyntaxErrorFull>) {
+        let mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let content_hash = {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            content.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        if let Some(mtime) = mtime {
+            let cached_hit = self.parse_cache.get(path).and_then(|cached| {
+                (cached.mtime == mtime && cached.content_hash == content_hash)
+                    .then(|| (cached.source_file.clone(), cached.errors.clone()))
+            });
+            if let Some((source_file, errors)) = cached_hit {
+                let file_id = self.get_or_assign_file_id(path);
+                return (file_id, source_file, errors);
+            }
+        }
+
         let file_id = self.get_or_assign_file_id(path);
 
         let mut change = Change::default();
@@ -98,7 +150,20 @@ impl NixAnalysisContext {
         let source_file_ast: SourceFile = parse_result.root(); // SourceFile from syntax::ast
         let errors: Vec<SyntaxErrorFull> = parse_result.errors().to_vec(); // SyntaxErrorFull is alias for syntax::Error
 
-        (file_id, Arc::new(source_file_ast), errors)
+        let source_file = Arc::new(source_file_ast);
+        if let Some(mtime) = mtime {
+            self.parse_cache.insert(
+                path.to_path_buf(),
+                CachedParse {
+                    mtime,
+                    content_hash,
+                    source_file: source_file.clone(),
+                    errors: errors.clone(),
+                },
+            );
+        }
+
+        (file_id, source_file, errors)
     }
 
     /// Get semantic diagnostics for a file
@@ -110,6 +175,28 @@ impl NixAnalysisContext {
         self.db.snapshot().diagnostics(file_id)
     }
 
+    /// Runs semantic-diagnostics queries for many files, returning results in the same order as
+    /// `file_ids`.
+    ///
+    /// This runs one file at a time, like `parse_file_with_syntax`: the salsa `Snapshot` returned
+    /// by `AnalysisHost::snapshot()` isn't `Send` (it holds `rowan` syntax trees internally, which
+    /// use a non-atomic refcount), so it can't cross a rayon thread-pool boundary as-is.
+    ///
+    /// TODO: this was originally scoped to run concurrently across files; that isn't possible
+    /// without a `Send` snapshot. If this becomes the bottleneck on configurations with hundreds
+    /// of modules, revisit by identifying which sub-queries of `diagnostics()` are genuinely
+    /// `Send` (or can be made so), parallelizing only those with rayon, and collecting the
+    /// non-`Send` remainder sequentially.
+    pub fn get_semantic_diagnostics_batch(
+        &self,
+        file_ids: &[FileId],
+    ) -> Vec<Result<Vec<Diagnostic>, ide::Cancelled>> {
+        file_ids
+            .iter()
+            .map(|&file_id| self.db.snapshot().diagnostics(file_id))
+            .collect()
+    }
+
     /// Get the content of a file from the database
     // pub fn get_file_content(&self, file_id: FileId) -> Option<Arc<String>> {
     //     // AnalysisHost does not directly expose file_text.
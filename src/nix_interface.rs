@@ -4,8 +4,8 @@
 use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 
-use color_eyre::eyre::eyre;
-use tracing::info;
+use color_eyre::eyre::{eyre, WrapErr};
+use tracing::{debug, info};
 
 // use nix_interop; // Not used in current implementation
 use crate::commands::Command;
@@ -16,6 +16,10 @@ use crate::Result;
 pub struct NixInterface {
     verbose_count: u8,
     dry_run: bool,
+    log_dir: Option<PathBuf>,
+    eval_cache: std::sync::Mutex<std::collections::HashMap<(String, String), String>>,
+    closure_size_cache: std::sync::Mutex<std::collections::HashMap<PathBuf, u64>>,
+    remote_builders: Vec<String>,
 } // **** CLOSING BRACE FOR struct NixInterface ****
 
 // **** IMPL BLOCK MOVED OUTSIDE AND AFTER THE STRUCT DEFINITION ****
@@ -24,9 +28,33 @@ impl NixInterface {
         Self {
             verbose_count,
             dry_run,
+            log_dir: None,
+            eval_cache: std::sync::Mutex::new(std::collections::HashMap::new()),
+            closure_size_cache: std::sync::Mutex::new(std::collections::HashMap::new()),
+            remote_builders: Vec::new(),
         }
     }
 
+    /// Sets the directory that build output should also be tee'd into, one
+    /// timestamped log file per build. `None` disables tee-ing.
+    pub fn with_log_dir(mut self, log_dir: Option<PathBuf>) -> Self {
+        self.log_dir = log_dir;
+        self
+    }
+
+    /// Sets the remote builder specs (nix `--builders` syntax) that builds
+    /// should be able to offload to.
+    pub fn with_remote_builders(mut self, builders: Vec<String>) -> Self {
+        self.remote_builders = builders;
+        self
+    }
+
+    fn build_log_path(&self) -> Option<PathBuf> {
+        let dir = self.log_dir.as_ref()?;
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        Some(dir.join(format!("ng-build-{timestamp}.log")))
+    }
+
     pub fn build_configuration(
         &self,
         installable: &Installable,
@@ -40,12 +68,22 @@ impl NixInterface {
             .add_verbosity_flags(self.verbose_count)
             .dry(self.dry_run);
 
+        if let Some(log_path) = self.build_log_path() {
+            build_cmd = build_cmd.log_file(log_path);
+        }
+
+        if !self.remote_builders.is_empty() {
+            build_cmd = build_cmd
+                .arg("--builders")
+                .arg(self.remote_builders.join(" ; "));
+        }
+
         let mut capture_stdout_for_path = false;
 
         if !no_nom {
             build_cmd = build_cmd.arg("--no-link");
             if out_link.is_none() && !self.dry_run {
-                build_cmd = build_cmd.arg("--print-out-paths");
+                build_cmd = build_cmd.arg("--json");
                 capture_stdout_for_path = true;
             }
         }
@@ -82,18 +120,35 @@ impl NixInterface {
             }
         }
 
+        if extra_build_args.iter().any(|a| a == "--keep-going") {
+            let output = build_cmd.run_capture_output()?;
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let failures = parse_failed_derivations(&stderr);
+            if !failures.is_empty() {
+                if let Err(e) = crate::tables::display_failed_derivations(failures.clone()) {
+                    tracing::warn!("Failed to render build failure summary table: {}", e);
+                }
+            }
+            if !output.status.success() {
+                return Err(eyre!(
+                    "nix build --keep-going finished with {} failed derivation(s)",
+                    failures.len().max(1)
+                ));
+            }
+            return if capture_stdout_for_path {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                parse_build_json_output(&stdout)
+            } else {
+                Ok(out_link
+                    .map(|p| p.to_path_buf())
+                    .unwrap_or_else(|| PathBuf::from("./result")))
+            };
+        }
+
         if capture_stdout_for_path {
             match build_cmd.run_capture()? {
-                Some(stdout) => {
-                    if let Some(path_str) = stdout.lines().find(|s| !s.trim().is_empty()) {
-                        Ok(PathBuf::from(path_str.trim()))
-                    } else {
-                        Err(eyre!(
-                            "nix build --print-out-paths produced no parsable output path"
-                        ))
-                    }
-                }
-                None => Err(eyre!("nix build --print-out-paths did not produce stdout")),
+                Some(stdout) => parse_build_json_output(&stdout),
+                None => Err(eyre!("nix build --json did not produce stdout")),
             }
         } else {
             build_cmd.run()?;
@@ -112,6 +167,81 @@ impl NixInterface {
         }
     }
 
+    /// Evaluates and builds `installable` on `build_host` over SSH instead of locally, returning
+    /// the resulting store path on that host. Callers are responsible for copying the result
+    /// wherever it needs to end up (see [`Self::copy_closure_from_host`] and
+    /// [`Self::copy_closure_between_hosts`]).
+    pub fn build_configuration_remote(
+        &self,
+        build_host: &str,
+        installable: &Installable,
+        extra_build_args: &[OsString],
+    ) -> Result<PathBuf> {
+        let mut remote_args = vec!["build".to_string()];
+        remote_args.extend(installable.to_args());
+        remote_args.push("--no-link".to_string());
+        remote_args.push("--json".to_string());
+        for arg in extra_build_args {
+            remote_args.push(arg.to_string_lossy().into_owned());
+        }
+        let remote_command = format!(
+            "nix {}",
+            remote_args
+                .iter()
+                .map(|arg| crate::util::shell_quote(arg))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+
+        let mut ssh_cmd = Command::new("ssh")
+            .arg(build_host)
+            .arg(&remote_command)
+            .add_verbosity_flags(self.verbose_count)
+            .dry(self.dry_run);
+
+        if let Some(log_path) = self.build_log_path() {
+            ssh_cmd = ssh_cmd.log_file(log_path);
+        }
+
+        if self.dry_run {
+            info!("[Dry Run] NixInterface: Would build on {}: {}", build_host, remote_command);
+            return Ok(PathBuf::from("/tmp/dry-run-remote-build-placeholder"));
+        }
+
+        let stdout = ssh_cmd
+            .run_capture()?
+            .ok_or_else(|| eyre!("Remote build on '{}' produced no stdout", build_host))?;
+        parse_build_json_output(&stdout)
+            .wrap_err_with(|| format!("Remote build on '{}'", build_host))
+    }
+
+    /// Copies a store path built on `build_host` back to this machine via `nix copy`.
+    pub fn copy_closure_from_host(&self, build_host: &str, path: &Path) -> Result<()> {
+        Command::new("nix")
+            .args(["copy", "--from"])
+            .arg(format!("ssh://{build_host}"))
+            .arg(path)
+            .add_verbosity_flags(self.verbose_count)
+            .dry(self.dry_run)
+            .run()
+    }
+
+    /// Copies a store path directly from `build_host` to `target_host`, without routing it
+    /// through this machine, by running `nix copy` on `build_host` itself.
+    pub fn copy_closure_between_hosts(&self, build_host: &str, target_host: &str, path: &Path) -> Result<()> {
+        let remote_command = format!(
+            "nix copy --to {} {}",
+            crate::util::shell_quote(&format!("ssh://{target_host}")),
+            crate::util::shell_quote(&path.display().to_string())
+        );
+        Command::new("ssh")
+            .arg(build_host)
+            .arg(&remote_command)
+            .add_verbosity_flags(self.verbose_count)
+            .dry(self.dry_run)
+            .run()
+    }
+
     pub fn run_gc(&self, dry_run_param: bool) -> Result<()> {
         let gc_cmd = Command::new("sudo")
             .args(["nix-store", "--gc"])
@@ -133,8 +263,468 @@ impl NixInterface {
             Ok(())
         }
     }
+
+    /// Pushes a built store path to a binary cache via `nix copy --to`.
+    pub fn push_to_binary_cache(&self, path: &Path, cache_uri: &str, extra_args: &[String]) -> Result<()> {
+        let mut push_cmd = Command::new("nix")
+            .args(["copy", "--to", cache_uri])
+            .arg(path)
+            .add_verbosity_flags(self.verbose_count)
+            .dry(self.dry_run);
+
+        for arg in extra_args {
+            push_cmd = push_cmd.arg(arg);
+        }
+
+        push_cmd
+            .message(format!("Pushing {} to {}", path.display(), cache_uri))
+            .run()
+    }
+
+    /// Checks that each configured remote builder is reachable over SSH by
+    /// running a benign `true` command on it. Returns the hostnames that
+    /// failed to respond so the caller can warn without aborting the build.
+    pub fn check_remote_builders(&self, builders: &[String]) -> Vec<String> {
+        let mut unreachable = Vec::new();
+        for spec in builders {
+            let host = match remote_builder_host(spec) {
+                Some(host) => host,
+                None => {
+                    warn_unparsable_builder(spec);
+                    continue;
+                }
+            };
+            let ping = Command::new("ssh")
+                .args(["-o", "BatchMode=yes", "-o", "ConnectTimeout=5"])
+                .arg(&host)
+                .arg("true")
+                .run_capture_output();
+            match ping {
+                Ok(output) if output.status.success() => {
+                    debug!("Remote builder '{}' is reachable", host);
+                }
+                _ => unreachable.push(host),
+            }
+        }
+        unreachable
+    }
+
+    /// Evaluates `<flake_rev>#<attribute>` with `nix eval --raw`, caching the
+    /// result for the lifetime of this `NixInterface` so a pre-flight eval
+    /// followed by a build of the same unchanged tree doesn't re-evaluate.
+    pub fn eval_cached(&self, flake_rev: &str, attribute: &str) -> Result<String> {
+        let key = (flake_rev.to_string(), attribute.to_string());
+        if let Some(cached) = self.eval_cache.lock().unwrap().get(&key) {
+            debug!("eval cache hit for {}#{}", flake_rev, attribute);
+            return Ok(cached.clone());
+        }
+
+        let output = Command::new("nix")
+            .args(["eval", "--raw"])
+            .arg(format!("{flake_rev}#{attribute}"))
+            .add_verbosity_flags(self.verbose_count)
+            .run_capture()?
+            .ok_or_else(|| eyre!("nix eval produced no output for {}#{}", flake_rev, attribute))?;
+
+        let result = output.trim().to_string();
+        self.eval_cache.lock().unwrap().insert(key, result.clone());
+        Ok(result)
+    }
+
+    /// Evaluates `installable` with `nix eval --json`, returning the raw JSON value. Unlike
+    /// [`Self::eval_cached`] this isn't cached, since its callers (pre-flight checks and
+    /// `ng check`) each evaluate a given attribute once.
+    pub fn eval_json(&self, installable: &Installable) -> Result<serde_json::Value> {
+        let target = installable
+            .to_args()
+            .into_iter()
+            .next()
+            .ok_or_else(|| eyre!("installable produced no `nix eval` target"))?;
+
+        let output = Command::new("nix")
+            .args(["eval", "--json"])
+            .arg(&target)
+            .add_verbosity_flags(self.verbose_count)
+            .run_capture()?
+            .ok_or_else(|| eyre!("nix eval produced no output for {}", target))?;
+
+        serde_json::from_str(&output)
+            .map_err(|e| eyre!("Failed to parse `nix eval --json` output for {}: {}", target, e))
+    }
+
+    /// Like [`Self::eval_json`], for attributes known to evaluate to a list of strings (e.g.
+    /// package names).
+    pub fn eval_json_list(&self, installable: &Installable) -> Result<Vec<String>> {
+        let value = self.eval_json(installable)?;
+        serde_json::from_value(value)
+            .map_err(|e| eyre!("Expected a JSON array of strings for {}: {}", installable, e))
+    }
+
+    /// Queries `nix flake metadata --json` for `flake_ref`.
+    pub fn flake_metadata(&self, flake_ref: &str) -> Result<crate::json::FlakeMetadata> {
+        let output = Command::new("nix")
+            .args(["flake", "metadata", "--json"])
+            .arg(flake_ref)
+            .add_verbosity_flags(self.verbose_count)
+            .run_capture()?
+            .ok_or_else(|| eyre!("nix flake metadata produced no output for {}", flake_ref))?;
+
+        serde_json::from_str(&output)
+            .map_err(|e| eyre!("Failed to parse `nix flake metadata --json` output for {}: {}", flake_ref, e))
+    }
+
+    /// Queries `nix path-info --json` for a single store path.
+    pub fn path_info(&self, path: &Path) -> Result<StorePathInfo> {
+        let output = Command::new("nix")
+            .args(["path-info", "--json"])
+            .arg(path)
+            .run_capture()?
+            .ok_or_else(|| eyre!("nix path-info produced no output for {}", path.display()))?;
+
+        let mut infos: Vec<StorePathInfo> = serde_json::from_str(&output)
+            .map_err(|e| eyre!("Failed to parse `nix path-info --json` output: {e}"))?;
+
+        infos.pop().ok_or_else(|| eyre!("nix path-info returned no entries for {}", path.display()))
+    }
+
+    /// Queries the closure size (in bytes) of a store path via
+    /// `nix path-info --json --closure-size`.
+    pub fn closure_size(&self, path: &Path) -> Result<u64> {
+        let output = Command::new("nix")
+            .args(["path-info", "--json", "--closure-size"])
+            .arg(path)
+            .run_capture()?
+            .ok_or_else(|| eyre!("nix path-info produced no output for {}", path.display()))?;
+
+        let mut infos: Vec<StorePathInfo> = serde_json::from_str(&output)
+            .map_err(|e| eyre!("Failed to parse `nix path-info --json` output: {e}"))?;
+
+        infos
+            .pop()
+            .and_then(|info| info.closure_size)
+            .ok_or_else(|| eyre!("nix path-info returned no closure size for {}", path.display()))
+    }
+
+    /// Like [`Self::closure_size`], caching the result for the lifetime of this `NixInterface`
+    /// keyed by store path. Store paths are content-addressed, so a hit is always correct — this
+    /// just avoids re-running `nix path-info` once per generation when listing a trend across
+    /// many generations that happen to share a path (e.g. the current one).
+    pub fn closure_size_cached(&self, path: &Path) -> Result<u64> {
+        let key = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if let Some(cached) = self.closure_size_cache.lock().unwrap().get(&key) {
+            debug!("closure size cache hit for {}", path.display());
+            return Ok(*cached);
+        }
+
+        let size = self.closure_size(path)?;
+        self.closure_size_cache.lock().unwrap().insert(key, size);
+        Ok(size)
+    }
+
+    /// Queries every store path in `path`'s closure, with each path's own (non-exclusive) NAR
+    /// size, via `nix path-info --json --recursive`. Used by `ng du` to rank what's taking up
+    /// space; note this is each path's own size, not its exclusive contribution to the closure
+    /// (a path shared by two packages will show up, in full, under both).
+    pub fn closure_path_sizes(&self, path: &Path) -> Result<Vec<StorePathInfo>> {
+        let output = Command::new("nix")
+            .args(["path-info", "--json", "--recursive"])
+            .arg(path)
+            .run_capture()?
+            .ok_or_else(|| eyre!("nix path-info produced no output for {}", path.display()))?;
+
+        serde_json::from_str(&output)
+            .map_err(|e| eyre!("Failed to parse `nix path-info --json` output: {e}"))
+    }
+
+    /// Queries `nix build --dry-run` to find out how many derivations will be built
+    /// locally/substituted vs fetched from a cache, before an actual build runs.
+    pub fn build_plan_summary(&self, installable: &Installable) -> Result<BuildPlanSummary> {
+        let output = Command::new("nix")
+            .arg("build")
+            .args(installable.to_args())
+            .arg("--dry-run")
+            .add_verbosity_flags(self.verbose_count)
+            .run_capture_output()?;
+
+        let combined = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(parse_build_plan_summary(&combined))
+    }
+
+    /// Computes package-level added/removed/changed counts between two store paths via `nix
+    /// store diff-closures`. Used for the `--json` operation summary (see
+    /// [`crate::json::OperationSummary`]) and for the `--ask` confirmation prompt.
+    pub fn diff_closures_summary(&self, before: &Path, after: &Path) -> Result<ClosureDiff> {
+        let output = Command::new("nix")
+            .args(["store", "diff-closures"])
+            .arg(before)
+            .arg(after)
+            .add_verbosity_flags(self.verbose_count)
+            .run_capture_output()?;
+
+        let combined = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(parse_closure_diff(&combined))
+    }
 } // **** THIS CLOSING BRACE IS FOR impl NixInterface ****
 
+/// Detected Nix (or Lix) version and enabled experimental features for the
+/// currently installed `nix` binary. Computed once per run and cached on
+/// [`crate::context::OperationContext`].
+#[derive(Debug, Clone)]
+pub struct NixEnvironmentInfo {
+    pub version: String,
+    pub is_lix: bool,
+    pub experimental_features: Vec<String>,
+}
+
+impl NixEnvironmentInfo {
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.experimental_features.iter().any(|f| f == feature)
+    }
+
+    /// Returns a clear error if flake support (`flakes` + `nix-command`) is
+    /// not enabled, instead of letting a flake-specific invocation fail with
+    /// a cryptic nix error.
+    pub fn ensure_flake_support(&self) -> Result<()> {
+        let missing: Vec<&str> = ["nix-command", "flakes"]
+            .into_iter()
+            .filter(|f| !self.has_feature(f))
+            .collect();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(eyre!(
+                "This command requires the experimental Nix feature(s) {} to be enabled.\n\
+                Add `experimental-features = nix-command flakes` to your nix.conf, \
+                or pass `--extra-experimental-features \"{}\"`.",
+                missing.join(", "),
+                missing.join(" ")
+            ))
+        }
+    }
+
+    /// Whether `ng`'s `nom` (nix-output-monitor) integration should be used for this build. `nom`
+    /// parses cpp-Nix's `--log-format internal-json` structured log lines; Lix's internal-json
+    /// output has diverged from upstream Nix in ways `nom` doesn't reliably parse, so ng falls
+    /// back to plain build output when Lix is detected rather than risk a garbled progress
+    /// display.
+    pub fn supports_nom(&self) -> bool {
+        !self.is_lix
+    }
+}
+
+impl NixInterface {
+    /// Detects the installed nix/Lix version and its enabled experimental
+    /// features by shelling out to `nix --version` and `nix config show`.
+    pub fn detect_environment(&self) -> Result<NixEnvironmentInfo> {
+        let version_output = Command::new("nix")
+            .arg("--version")
+            .run_capture()?
+            .ok_or_else(|| eyre!("`nix --version` produced no output"))?;
+        let version_line = version_output
+            .lines()
+            .next()
+            .ok_or_else(|| eyre!("`nix --version` produced empty output"))?;
+        let is_lix = version_line.to_lowercase().contains("lix");
+        let version = version_line
+            .split_whitespace()
+            .last()
+            .unwrap_or(version_line)
+            .to_string();
+
+        let features_output = Command::new("nix")
+            .args(["config", "show", "experimental-features"])
+            .run_capture()?
+            .unwrap_or_default();
+        let experimental_features: Vec<String> = features_output
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+
+        Ok(NixEnvironmentInfo {
+            version,
+            is_lix,
+            experimental_features,
+        })
+    }
+}
+
+/// Extracts the SSH host from a nix builder spec, e.g.
+/// `ssh://builder@example.com x86_64-linux /path/to/key 4 1` -> `builder@example.com`.
+fn remote_builder_host(spec: &str) -> Option<String> {
+    let first_field = spec.split_whitespace().next()?;
+    first_field.strip_prefix("ssh://").or(Some(first_field)).map(str::to_string)
+}
+
+fn warn_unparsable_builder(spec: &str) {
+    tracing::warn!("Could not parse remote builder spec '{}', skipping health check", spec);
+}
+
+/// Counts of derivations a pending build will build locally vs fetch from a cache.
+#[derive(Debug, Clone, Default)]
+pub struct BuildPlanSummary {
+    pub to_build: usize,
+    pub to_fetch: usize,
+    pub download_bytes: Option<u64>,
+    /// Unpacked (on-disk) size of everything that will be fetched, per nix's own "will be
+    /// fetched (D MiB download, U MiB unpacked)" reporting. Used to estimate the store space a
+    /// build needs, since that's what actually lands on disk rather than the download size.
+    pub unpacked_bytes: Option<u64>,
+}
+
+/// Parses `nix build --json` stdout into the primary output path of its first entry. Used instead
+/// of `--print-out-paths`/plain-text scraping so a build that produces multiple outputs (or that
+/// nix reports oddly) still resolves the intended `"out"` path authoritatively.
+fn parse_build_json_output(stdout: &str) -> Result<PathBuf> {
+    let results: Vec<crate::json::BuildResult> = serde_json::from_str(stdout.trim())
+        .map_err(|e| eyre!("Failed to parse `nix build --json` output: {e}"))?;
+
+    results
+        .first()
+        .and_then(crate::json::BuildResult::primary_output)
+        .cloned()
+        .ok_or_else(|| eyre!("nix build --json produced no output paths"))
+}
+
+/// Parses the human-readable summary nix prints for `nix build --dry-run`, e.g.
+/// "these 3 derivations will be built:" / "this path will be fetched (12.34 MiB download, 45.67 MiB unpacked):".
+fn parse_build_plan_summary(text: &str) -> BuildPlanSummary {
+    let mut summary = BuildPlanSummary::default();
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(count) = extract_leading_count(trimmed, "derivation", "will be built") {
+            summary.to_build = count.max(1);
+        } else if let Some(count) = extract_leading_count(trimmed, "path", "will be fetched") {
+            summary.to_fetch = count.max(1);
+            summary.download_bytes = extract_download_mib(trimmed).map(|mib| (mib * 1024.0 * 1024.0) as u64);
+            summary.unpacked_bytes = extract_unpacked_mib(trimmed).map(|mib| (mib * 1024.0 * 1024.0) as u64);
+        }
+    }
+
+    summary
+}
+
+/// Extracts a leading count from lines like "these 3 derivations will be built:" or
+/// "this path will be fetched (...):", falling back to 1 for the singular "this <noun>" phrasing.
+fn extract_leading_count(line: &str, noun: &str, suffix: &str) -> Option<usize> {
+    if !line.contains(noun) || !line.contains(suffix) {
+        return None;
+    }
+    if line.starts_with("this ") {
+        return Some(1);
+    }
+    line.split_whitespace().nth(1)?.parse::<usize>().ok()
+}
+
+/// Extracts the download size in MiB from a summary line's parenthesized `(X.YZ MiB download, ...)`.
+fn extract_download_mib(line: &str) -> Option<f64> {
+    let start = line.find('(')? + 1;
+    let rest = &line[start..];
+    let end = rest.find(" MiB download")?;
+    rest[..end].trim().parse::<f64>().ok()
+}
+
+/// Extracts the "U" from "(D MiB download, U MiB unpacked)".
+fn extract_unpacked_mib(line: &str) -> Option<f64> {
+    let start = line.find(" MiB download, ")? + " MiB download, ".len();
+    let rest = &line[start..];
+    let end = rest.find(" MiB unpacked")?;
+    rest[..end].trim().parse::<f64>().ok()
+}
+
+/// Counts of packages added, removed, or version-changed between two closures.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClosureDiff {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+/// Parses the human-readable output of `nix store diff-closures`, e.g. `pkg: 1.0 -> 1.1` for a
+/// version bump, `pkg: ∅ -> 1.0, +2.1 MiB` for an addition, or `pkg: 1.0 -> ∅, -2.1 MiB` for a
+/// removal.
+fn parse_closure_diff(text: &str) -> ClosureDiff {
+    let mut diff = ClosureDiff::default();
+
+    for line in text.lines() {
+        let Some((_, versions)) = line.trim().split_once(": ") else {
+            continue;
+        };
+        let versions = versions.split(',').next().unwrap_or(versions);
+        let Some((old, new)) = versions.split_once(" -> ") else {
+            continue;
+        };
+        let (old, new) = (old.trim(), new.trim());
+
+        match (old == "∅", new == "∅") {
+            (true, false) => diff.added += 1,
+            (false, true) => diff.removed += 1,
+            (false, false) if old != new => diff.changed += 1,
+            _ => {}
+        }
+    }
+
+    diff
+}
+
+/// A derivation that failed to build, as surfaced by `nix build --keep-going`.
+#[derive(Debug, Clone)]
+pub struct FailedDerivation {
+    pub drv_path: String,
+    pub error: String,
+}
+
+/// Summary statistics for a completed build, printed when `logging.build_stats` is enabled.
+#[derive(Debug, Clone)]
+pub struct BuildStats {
+    pub wall_time: std::time::Duration,
+    pub derivations_built: usize,
+    pub derivations_substituted: usize,
+    pub bytes_downloaded: Option<u64>,
+    pub closure_size: Option<u64>,
+}
+
+/// Scrapes `nix build --keep-going` stderr for "error: builder for '<drv>' failed" lines,
+/// pairing each with the summary text nix prints on the same line.
+pub(crate) fn parse_failed_derivations(stderr: &str) -> Vec<FailedDerivation> {
+    let mut failures = Vec::new();
+    for line in stderr.lines() {
+        let trimmed = line.trim();
+        let Some(rest) = trimmed
+            .strip_prefix("error: builder for ")
+            .or_else(|| trimmed.strip_prefix("error: build of "))
+        else {
+            continue;
+        };
+        let Some(start) = rest.find('\'') else {
+            continue;
+        };
+        let after_quote = &rest[start + 1..];
+        let Some(end) = after_quote.find('\'') else {
+            continue;
+        };
+        failures.push(FailedDerivation {
+            drv_path: after_quote[..end].to_string(),
+            error: after_quote[end + 1..].trim_start_matches(',').trim().to_string(),
+        });
+    }
+    failures
+}
+
+/// A single entry from `nix path-info --json`. Defined in [`crate::json`] alongside the rest of
+/// the typed nix JSON output models; re-exported here since every call site in this file already
+/// refers to it as `StorePathInfo`.
+pub use crate::json::StorePathInfo;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,10 +787,12 @@ mod tests {
     }
 
     #[test]
-    fn test_build_config_capture_print_out_paths() {
+    fn test_build_config_capture_json() {
         crate::commands::test_support::enable_test_mode();
         let expected_path_str = "/nix/store/somehash-package";
-        crate::commands::test_support::set_mock_capture_stdout(expected_path_str.to_string());
+        crate::commands::test_support::set_mock_capture_stdout(format!(
+            r#"[{{"drvPath":"/nix/store/somehash-package.drv","outputs":{{"out":"{expected_path_str}"}}}}]"#
+        ));
 
         let interface = NixInterface::new(1, false);
         let installable = get_test_installable();
@@ -220,7 +812,7 @@ mod tests {
         assert!(cmd_to_check.contains("nix build"));
         assert!(cmd_to_check.contains(&installable.to_args().join(" ")));
         assert!(cmd_to_check.contains("--no-link"));
-        assert!(cmd_to_check.contains("--print-out-paths"));
+        assert!(cmd_to_check.contains("--json"));
         assert!(cmd_to_check.contains("-v")); // from verbose_count = 1
 
         crate::commands::test_support::disable_test_mode();
@@ -244,7 +836,7 @@ mod tests {
         let recorded_commands = crate::commands::test_support::get_recorded_commands();
         assert_eq!(recorded_commands.len(), 1, "Expected exactly one command to be recorded in this test");
         let cmd_to_check = recorded_commands.first().expect("No command was recorded").clone();
-        assert!(cmd_to_check.contains("--print-out-paths")); // Ensure it tried the capture path
+        assert!(cmd_to_check.contains("--json")); // Ensure it tried the capture path
         crate::commands::test_support::disable_test_mode();
     }
 
@@ -264,11 +856,11 @@ mod tests {
         assert!(result
             .unwrap_err()
             .to_string()
-            .contains("produced no parsable output path"));
+            .contains("Failed to parse `nix build --json` output"));
         let recorded_commands = crate::commands::test_support::get_recorded_commands();
         assert_eq!(recorded_commands.len(), 1, "Expected exactly one command to be recorded in this test");
         let cmd_to_check = recorded_commands.first().expect("No command was recorded").clone();
-        assert!(cmd_to_check.contains("--print-out-paths")); // Ensure it tried the capture path
+        assert!(cmd_to_check.contains("--json")); // Ensure it tried the capture path
         crate::commands::test_support::disable_test_mode();
     }
 
@@ -292,7 +884,7 @@ mod tests {
         let recorded_commands = crate::commands::test_support::get_recorded_commands();
         assert_eq!(recorded_commands.len(), 1, "Expected exactly one command to be recorded in this test");
         let cmd_to_check = recorded_commands.first().expect("No command was recorded").clone();
-        assert!(!cmd_to_check.contains("--print-out-paths")); // Ensure it did NOT try the capture path
+        assert!(!cmd_to_check.contains("--json")); // Ensure it did NOT try the capture path
         crate::commands::test_support::disable_test_mode();
     }
 
@@ -330,4 +922,66 @@ mod tests {
         crate::commands::test_support::disable_test_mode();
     }
     // Removed duplicated test blocks that were present in the original code
+
+    #[test]
+    fn test_build_configuration_remote_escapes_flake_ref() {
+        crate::commands::test_support::enable_test_mode();
+        crate::commands::test_support::set_mock_capture_stdout(
+            r#"[{"drvPath":"/nix/store/somehash-package.drv","outputs":{"out":"/nix/store/somehash-package"}}]"#
+                .to_string(),
+        );
+
+        let interface = NixInterface::new(0, false); // Not in dry_run
+        let installable = Installable::Flake {
+            reference: "github:foo/bar?dir=hosts/foo&submodules=1".to_string(),
+            attribute: Vec::new(),
+        };
+
+        let result = interface.build_configuration_remote("builder.example.com", &installable, &[]);
+        assert!(result.is_ok(), "build_configuration_remote failed: {:?}", result.err());
+
+        let recorded_commands = crate::commands::test_support::get_recorded_commands();
+        assert_eq!(recorded_commands.len(), 1, "Expected exactly one command to be recorded in this test");
+        let cmd_to_check = recorded_commands.first().expect("No command was recorded").clone();
+        assert!(cmd_to_check.contains("builder.example.com"));
+        // The flake reference (joined with its, here empty, attribute path via `#`) must be
+        // quoted as a single shell word so its embedded `&` isn't interpreted by the remote
+        // shell as backgrounding the command.
+        assert!(cmd_to_check.contains("'github:foo/bar?dir=hosts/foo&submodules=1#'"));
+        crate::commands::test_support::disable_test_mode();
+    }
+
+    #[test]
+    fn test_copy_closure_between_hosts_escapes_path() {
+        crate::commands::test_support::enable_test_mode();
+        crate::commands::test_support::set_mock_run_result(Ok(()));
+
+        let interface = NixInterface::new(0, false); // Not in dry_run
+        let path = Path::new("/nix/store/somehash-with a space");
+
+        let result = interface.copy_closure_between_hosts("build.example.com", "target.example.com", path);
+        assert!(result.is_ok(), "copy_closure_between_hosts failed: {:?}", result.err());
+
+        let recorded_commands = crate::commands::test_support::get_recorded_commands();
+        assert_eq!(recorded_commands.len(), 1, "Expected exactly one command to be recorded in this test");
+        let cmd_to_check = recorded_commands.first().expect("No command was recorded").clone();
+        assert!(cmd_to_check.contains("build.example.com"));
+        assert!(cmd_to_check.contains("'ssh://target.example.com'"));
+        assert!(cmd_to_check.contains("'/nix/store/somehash-with a space'"));
+        crate::commands::test_support::disable_test_mode();
+    }
+
+    #[test]
+    fn test_parse_closure_diff() {
+        let output = "\
+pkgA: 1.0 -> 1.1
+pkgB: ∅ -> 2.0, +5.2 MiB
+pkgC: 1.0 -> ∅, -3.1 MiB
+pkgD: 1.0 -> 1.0
+";
+        let diff = parse_closure_diff(output);
+        assert_eq!(diff.added, 1);
+        assert_eq!(diff.removed, 1);
+        assert_eq!(diff.changed, 1);
+    }
 } // **** THIS IS THE FINAL CLOSING BRACE for mod tests ****
@@ -3,16 +3,15 @@ use std::path::{PathBuf};
 // use std::process::Command as StdCommand; // Unused
 use color_eyre::eyre::{eyre, Result}; // bail was unused
 // use nix; // Unused
-use walkdir::WalkDir;
 // use rayon::prelude::*; // Unused
-use tracing::{info, debug}; // warn was unused
+use tracing::{info, debug, warn};
 
 // Interface and core imports
-use crate::interface::{OsArgs, OsRebuildArgs, OsSubcommand, OsReplArgs, OsGenerationsArgs};
+use crate::interface::{OsArgs, OsRebuildArgs, OsSubcommand, OsReplArgs, OsGenerationsArgs, OsGenerationsSubcommand, OsGenerationsSwitchToArgs, OsGenerationsPruneArgs, OsSpecialisationsArgs};
 use crate::installable::Installable;
 use crate::commands::Command; // Build was unused
 use crate::util::get_hostname;
-// use crate::generations; // Unused
+use crate::generations;
 // use crate::update::update; // Unused
 
 // Workflow refactoring imports
@@ -40,12 +39,20 @@ impl OsArgs {
             strict_format: cli_args.common.common.strict_format, // Added
             medium_checks: cli_args.common.common.medium,
             full_checks: cli_args.common.common.full,
-            dry_run: cli_args.common.common.dry,
-            ask_confirmation: cli_args.common.common.ask,
+            dry_run: cli_args.common.common.dry || cli_args.common.common.plan,
+            confirm_stages: if cli_args.common.common.no_ask {
+                Vec::new()
+            } else {
+                cli_args.common.common.ask.clone()
+            },
             no_nom: cli_args.common.common.no_nom,
             out_link: cli_args.common.common.out_link.clone(),
             clean_after: cli_args.common.common.clean,
             extra_build_args: cli_args.extra_args.iter().map(|s| std::ffi::OsString::from(s.clone())).collect(),
+            keep_going: cli_args.common.common.keep_going,
+            json: cli_args.common.common.json,
+            plan: cli_args.common.common.plan,
+            no_group: cli_args.common.common.no_group,
         }
     }
 
@@ -53,8 +60,10 @@ impl OsArgs {
     fn execute_os_workflow(cli_args: &OsRebuildArgs, verbose_count: u8, activation_mode: ActivationMode) -> Result<()> {
         let core_common_args = Self::create_common_rebuild_args(cli_args);
         
-        let nix_interface = NixInterface::new(verbose_count, cli_args.common.common.dry); // Create this first
         let config = Arc::new(NgConfig::load()); // Load config
+        let nix_interface = NixInterface::new(verbose_count, core_common_args.dry_run)
+            .with_log_dir(config.logging.log_dir.as_ref().map(PathBuf::from))
+            .with_remote_builders(config.remote_builders.builders.clone().unwrap_or_default());
         let op_ctx = OperationContext::new(
             core_common_args, 
             &cli_args.update_args,
@@ -91,84 +100,54 @@ impl OsArgs {
                 Self::execute_os_workflow(&cli_args, verbose_count, ActivationMode::Build)
             }
             OsSubcommand::Repl(args) => args.run(verbose_count),
-            OsSubcommand::Info(args) => args.info(),
+            OsSubcommand::Info(args) => args.info(verbose_count),
+            OsSubcommand::Specialisations(args) => args.run(),
+            OsSubcommand::Generations(cmd) => match cmd.subcommand {
+                OsGenerationsSubcommand::SwitchTo(args) => args.run(verbose_count),
+                OsGenerationsSubcommand::Prune(args) => args.run(verbose_count),
+            },
         }
     }
 }
 
-// OsRebuildVariant enum removed (lines 93-98 of original)
-
-// impl OsRebuildArgs { fn rebuild(...) } block removed (lines 100-301 of original)
-
-/// Runs a parallel parse check on all .nix files
-pub fn run_parallel_parse_check(verbose_count: u8) -> Result<(), String> {
-    use rayon::prelude::*;
-    
-    info!("Running parallel syntax check on .nix files...");
-    
-    // Find .nix files
-    let nix_files: Vec<PathBuf> = WalkDir::new(".")
-        .follow_links(true)
-        .into_iter()
-        .filter_entry(|e| !is_hidden(e))
-        .filter_map(|entry| {
-            entry.ok().filter(|e| {
-                e.file_type().is_file() && 
-                e.path().extension().map_or(false, |ext| ext == "nix")
-            }).map(|e| e.path().to_owned())
-        })
-        .collect();
-        
-    if nix_files.is_empty() {
-        info!("No .nix files found to check.");
-        return Ok(());
-    }
-    
-    debug!("Found {} .nix files to check", nix_files.len());
-    
-    // Use rayon to run nix-instantiate in parallel
-    let parse_errors: Vec<(PathBuf, String)> = nix_files.par_iter()
-        .filter_map(|path| {
-            let mut cmd = std::process::Command::new("nix-instantiate");
-            cmd.args(["--parse", path.to_str().unwrap()]);
-            crate::util::add_verbosity_flags(&mut cmd, verbose_count);
-            
-            match cmd.output() {
-                Ok(output) => {
-                    if !output.status.success() {
-                        let error = String::from_utf8_lossy(&output.stderr).to_string();
-                        Some((path.clone(), error))
-                    } else {
-                        None
-                    }
-                },
-                Err(e) => Some((path.clone(), format!("Failed to run nix-instantiate: {}", e)))
-            }
-        })
-        .collect();
-        
-    if parse_errors.is_empty() {
-        Ok(())
-    } else {
-        let mut combined_error = format!("Found {} file(s) with syntax errors:\n", parse_errors.len());
-        for (path, error) in parse_errors {
-            combined_error.push_str(&format!("\nError in {}: \n{}\n", path.display(), error));
-        }
-        Err(combined_error)
+impl OsGenerationsSwitchToArgs {
+    pub fn run(self, verbose_count: u8) -> Result<()> {
+        let profile = PathBuf::from(
+            self.profile
+                .unwrap_or_else(|| "/nix/var/nix/profiles/system".to_string()),
+        );
+        let config = NgConfig::load();
+        generations::switch_to_generation(&profile, self.generation, verbose_count, &config)
     }
 }
 
-/// Checks if a directory entry is hidden
-fn is_hidden(entry: &walkdir::DirEntry) -> bool {
-    entry.file_name()
-        .to_str()
-        .map_or(false, |s| s.starts_with('.'))
-        || entry.path().components().any(|c| {
-            c.as_os_str().to_str().map_or(false, |s| s.starts_with('.'))
-        })
+impl OsGenerationsPruneArgs {
+    pub fn run(self, verbose_count: u8) -> Result<()> {
+        let profile = PathBuf::from(
+            self.profile
+                .unwrap_or_else(|| "/nix/var/nix/profiles/system".to_string()),
+        );
+        let config = NgConfig::load();
+        generations::prune_generations(
+            &profile,
+            self.older_than.into(),
+            self.dry,
+            self.yes,
+            verbose_count,
+            &config,
+        )
+    }
 }
 
+// OsRebuildVariant enum removed (lines 93-98 of original)
+
+// impl OsRebuildArgs { fn rebuild(...) } block removed (lines 100-301 of original)
+
 // perform_cleanup function removed (lines 358-366 of original)
+// run_parallel_parse_check / is_hidden removed: nixos.rs now goes through
+// execute_rebuild_workflow -> pre_flight::run_shared_pre_flight_checks, which already covers
+// syntax checking via NixParsePreFlightCheck (see pre_flight.rs). home.rs keeps its own copy
+// since it isn't migrated onto the shared workflow yet.
 
 /// Determines the toplevel installable for a NixOS configuration
 pub fn toplevel_for<T>(installable: Installable, hostname: &str, _args: &T) -> Result<Installable> {
@@ -199,23 +178,104 @@ impl OsReplArgs {
         };
         debug!("Using hostname: {}", hostname);
 
-        let _installable = self.installable.clone();
+        let (reference, mut attribute) = match self.installable {
+            Installable::Flake {
+                reference,
+                attribute,
+            } => (reference, attribute),
+            other => {
+                // Not a flake installable (e.g. --file/--expr); fall back to the old behavior of
+                // just repl-ing directly on it, since `builtins.getFlake` doesn't apply.
+                Command::new("nix").arg("repl").args(other.to_args()).run()?;
+                return Ok(());
+            }
+        };
+        if attribute.is_empty() {
+            attribute.push("nixosConfigurations".to_string());
+            attribute.push(hostname);
+        }
 
         Command::new("nix")
             .arg("repl")
-            .arg("--file")
-            .arg("<nixpkgs/nixos>")
+            .arg("--impure")
+            .arg("--expr")
+            .arg(crate::util::preloaded_repl_expr(&reference, &attribute))
             .run()?;
 
         Ok(())
     }
 }
 
+impl OsSpecialisationsArgs {
+    pub fn run(self) -> Result<()> {
+        let profile = PathBuf::from(
+            self.profile
+                .unwrap_or_else(|| "/nix/var/nix/profiles/system".to_string()),
+        );
+
+        let specialisations = crate::specialisation::list_specialisations(&profile);
+        if specialisations.is_empty() {
+            info!(
+                "No specialisations are built into the generation at {}",
+                profile.display()
+            );
+            return Ok(());
+        }
+
+        info!("Specialisations built into {}:", profile.display());
+        for name in specialisations {
+            println!("  - {}", name);
+        }
+
+        Ok(())
+    }
+}
+
 impl OsGenerationsArgs {
-    pub fn info(self) -> Result<()> {
-        // Assuming generations::list_generations takes a profile path and returns a Result
-        // This is a placeholder implementation
-        info!("Listing generations for profile: {:?}", self.profile);
+    pub fn info(self, verbose_count: u8) -> Result<()> {
+        let profile = PathBuf::from(
+            self.profile
+                .unwrap_or_else(|| "/nix/var/nix/profiles/system".to_string()),
+        );
+        debug!("Listing generations for profile: {}", profile.display());
+
+        let status = generations::system_status(&profile, &PathBuf::from("."));
+        crate::tables::display_system_status(&status);
+
+        let found = generations::list_generations(&profile);
+        let generations = generations::sort_and_filter(
+            found,
+            self.sort,
+            self.reverse,
+            self.filter.as_deref(),
+        );
+
+        if generations.is_empty() {
+            info!("No generations found for profile {}", profile.display());
+            return Ok(());
+        }
+
+        if self.sizes {
+            let nix_interface = crate::nix_interface::NixInterface::new(verbose_count, false);
+            let sizes: Vec<Option<u64>> = generations
+                .iter()
+                .map(|generation| {
+                    let number: u32 = generation.number.parse().ok()?;
+                    let link = generations::generation_link_path(&profile, number);
+                    match nix_interface.closure_size_cached(&link) {
+                        Ok(size) => Some(size),
+                        Err(e) => {
+                            warn!("Failed to compute closure size for generation {}: {}", generation.number, e);
+                            None
+                        }
+                    }
+                })
+                .collect();
+            crate::tables::display_generation_sizes(&generations, &sizes);
+            return Ok(());
+        }
+
+        crate::tables::display_generations_as(&generations, self.format, self.wide);
         Ok(())
     }
 }
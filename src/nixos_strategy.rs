@@ -6,12 +6,23 @@ use crate::util::{self, UtilCommandError};
 use crate::Result; // from color_eyre
 use std::path::{Path, PathBuf};
 use std::process::Command as StdCommand; // Alias to avoid conflict if Command struct exists
-use tracing::{info, debug}; // warn was unused
+use tracing::{info, debug, warn};
 use color_eyre::eyre::{bail, eyre}; // Context, Report were unused
 
 /// Strategy implementation for NixOS platform
 pub struct NixosPlatformStrategy;
 
+/// Resolves the system profile a generation should be registered under: the default
+/// `/nix/var/nix/profiles/system`, or `/nix/var/nix/profiles/system-profiles/<name>` when
+/// `--profile-name` is given, so an experimental configuration gets its own generation history
+/// and boot menu entry instead of overwriting the default one.
+fn resolve_profile_path(profile_name: Option<&str>) -> PathBuf {
+    match profile_name {
+        Some(name) => PathBuf::from(format!("/nix/var/nix/profiles/system-profiles/{name}")),
+        None => PathBuf::from("/nix/var/nix/profiles/system"),
+    }
+}
+
 impl PlatformRebuildStrategy for NixosPlatformStrategy {
     type PlatformArgs = OsRebuildArgs;
 
@@ -27,28 +38,53 @@ impl PlatformRebuildStrategy for NixosPlatformStrategy {
 
     fn get_toplevel_installable(&self, op_ctx: &OperationContext, platform_args: &Self::PlatformArgs) -> Result<Installable> {
         debug!("NixOSStrategy: get_toplevel_installable");
-        let hostname = platform_args.hostname.clone()
-            .or_else(|| util::get_hostname().ok()) // Try to get system hostname
-            .ok_or_else(|| eyre!("Hostname could not be determined and was not provided via --hostname. Needed for NixOS configuration attribute path."))?;
 
         let mut final_installable = op_ctx.common_args.installable.clone();
         match &mut final_installable {
             Installable::Flake { reference: _, attribute } => {
-                if attribute.is_empty() { 
+                let hostname = platform_args.hostname.clone()
+                    .or_else(|| util::get_hostname().ok()) // Try to get system hostname
+                    .ok_or_else(|| eyre!("Hostname could not be determined and was not provided via --hostname. Needed for NixOS configuration attribute path."))?;
+
+                if attribute.is_empty() {
                     attribute.push("nixosConfigurations".to_string());
-                    attribute.push(hostname.clone()); 
+                    attribute.push(hostname.clone());
                     attribute.extend(vec!["config".to_string(), "system".to_string(), "build".to_string(), "toplevel".to_string()]);
                 } else if attribute.len() == 2 && attribute[0] == "nixosConfigurations" {
                     // If attribute is like ["nixosConfigurations", "some-host"], then append suffix.
                     attribute.extend(vec!["config".to_string(), "system".to_string(), "build".to_string(), "toplevel".to_string()]);
                 }
-                // TODO: Handle platform_args.specialisation and platform_args.no_specialisation here
+                // Specialisation selection happens at activation time (see
+                // `crate::specialisation::resolve_specialisation`), against the built
+                // toplevel's `specialisation/` outputs rather than the eval attribute path.
             }
-            Installable::File { attribute: _, .. } | Installable::Expression { attribute: _, .. } => {
+            Installable::File { path, attribute } if attribute.is_empty() => {
+                // A bare `--file configuration.nix` with no attribute is the classic
+                // (non-flake) entry point: the same `<nixpkgs/nixos>` module evaluation
+                // `nixos-rebuild` itself uses, with `nixos-config` pointed at this file via
+                // `NIX_PATH` so `<nixos-config>` references inside the module tree resolve the
+                // same way they would on a stock channel-based install.
+                let nixos_config = path.clone();
+                let existing_nix_path = std::env::var("NIX_PATH").unwrap_or_default();
+                let nix_path = if existing_nix_path.is_empty() {
+                    format!("nixos-config={}", nixos_config.display())
+                } else {
+                    format!("nixos-config={}:{existing_nix_path}", nixos_config.display())
+                };
+                std::env::set_var("NIX_PATH", nix_path);
+
+                final_installable = Installable::Expression {
+                    expression: "import <nixpkgs/nixos>".to_string(),
+                    attribute: vec!["config".to_string(), "system".to_string(), "build".to_string(), "toplevel".to_string()],
+                };
+            }
+            Installable::File { .. } | Installable::Expression { .. } => {
+                // An explicit attribute was already given (e.g. `--expr '...' someAttr`);
+                // respect it as-is rather than assuming it points at a NixOS configuration.
             }
             Installable::Store { .. } => { /* Store path is already a toplevel */ }
         }
-        debug!("NixOS toplevel: {:?}", final_installable);
+        debug!("NixOS toplevel: {}", final_installable);
         Ok(final_installable)
     }
 
@@ -56,6 +92,42 @@ impl PlatformRebuildStrategy for NixosPlatformStrategy {
         Some(PathBuf::from("/run/current-system"))
     }
 
+    fn get_generation_profile_path(&self, _op_ctx: &OperationContext, platform_args: &Self::PlatformArgs) -> Option<PathBuf> {
+        Some(resolve_profile_path(platform_args.profile_name.as_deref()))
+    }
+
+    fn get_sops_secrets_installable(&self, op_ctx: &OperationContext, platform_args: &Self::PlatformArgs) -> Option<Installable> {
+        let hostname = platform_args.hostname.clone().or_else(|| util::get_hostname().ok())?;
+
+        let mut installable = op_ctx.common_args.installable.clone();
+        match &mut installable {
+            Installable::Flake { attribute, .. } => {
+                attribute.clear();
+                attribute.push("nixosConfigurations".to_string());
+                attribute.push(hostname);
+                attribute.extend(vec!["config".to_string(), "sops".to_string(), "secrets".to_string()]);
+                Some(installable)
+            }
+            _ => None,
+        }
+    }
+
+    fn get_agenix_secrets_installable(&self, op_ctx: &OperationContext, platform_args: &Self::PlatformArgs) -> Option<Installable> {
+        let hostname = platform_args.hostname.clone().or_else(|| util::get_hostname().ok())?;
+
+        let mut installable = op_ctx.common_args.installable.clone();
+        match &mut installable {
+            Installable::Flake { attribute, .. } => {
+                attribute.clear();
+                attribute.push("nixosConfigurations".to_string());
+                attribute.push(hostname);
+                attribute.extend(vec!["config".to_string(), "age".to_string()]);
+                Some(installable)
+            }
+            _ => None,
+        }
+    }
+
     fn activate_configuration(
         &self,
         op_ctx: &OperationContext,
@@ -77,9 +149,57 @@ impl PlatformRebuildStrategy for NixosPlatformStrategy {
             ActivationMode::Build => return Ok(()), // Should be caught by workflow_executor
         };
 
-        let switch_script_path = built_profile_path.join("bin/switch-to-configuration");
+        if let Some(target_host) = &platform_args.target_host {
+            return self.activate_remote(platform_args, built_profile_path, action_str, target_host);
+        }
+
+        let specialisation = crate::specialisation::resolve_specialisation(
+            built_profile_path,
+            platform_args.specialisation.as_deref(),
+            platform_args.no_specialisation,
+        )?;
+        let activation_dir = match &specialisation {
+            Some(name) => {
+                info!("Activating specialisation '{}'", name);
+                built_profile_path.join("specialisation").join(name)
+            }
+            None => built_profile_path.to_path_buf(),
+        };
+
+        let switch_script_path = activation_dir.join("bin/switch-to-configuration");
         if !switch_script_path.exists() {
-            bail!("Activation script 'bin/switch-to-configuration' not found in built profile: {}", built_profile_path.display());
+            bail!("Activation script 'bin/switch-to-configuration' not found in built profile: {}", activation_dir.display());
+        }
+
+        if let Some(name) = &platform_args.profile_name {
+            if matches!(activation_mode, ActivationMode::Switch | ActivationMode::Boot) {
+                if !op_ctx.config.elevation.may_elevate("profile_update") {
+                    bail!(
+                        "Registering profile 'system-profiles/{name}' requires sudo, but the \
+                         \"profile_update\" stage is not in elevation.allow_stages in ng.toml."
+                    );
+                }
+                let profile_path = resolve_profile_path(Some(name));
+                crate::commands::Command::new("nix-env")
+                    .arg("--profile")
+                    .arg(&profile_path)
+                    .arg("--set")
+                    .arg(built_profile_path)
+                    .elevate(true)
+                    .message(format!("Registering generation under {}", profile_path.display()))
+                    .add_verbosity_flags(op_ctx.verbose_count)
+                    .run()?;
+            }
+        }
+
+        let may_elevate = op_ctx.config.elevation.may_elevate("activation");
+        if !platform_args.bypass_root_check && !may_elevate {
+            bail!(
+                "Activation requires sudo, but the \"activation\" stage is not in \
+                 elevation.allow_stages in ng.toml. Either allow it there or run `ng os {action}` \
+                 as root with --bypass-root-check.",
+                action = action_str
+            );
         }
 
         let mut cmd = StdCommand::new(if platform_args.bypass_root_check {
@@ -117,13 +237,263 @@ impl PlatformRebuildStrategy for NixosPlatformStrategy {
         // Example: could print info about rebooting if kernel changed, etc.
         Ok(())
     }
+
+    fn get_build_host(&self, _op_ctx: &OperationContext, platform_args: &Self::PlatformArgs) -> Option<String> {
+        platform_args.build_host.clone()
+    }
+
+    fn get_target_host(&self, _op_ctx: &OperationContext, platform_args: &Self::PlatformArgs) -> Option<String> {
+        platform_args.target_host.clone()
+    }
+
+    fn get_diff_target_paths(
+        &self,
+        _op_ctx: &OperationContext,
+        platform_args: &Self::PlatformArgs,
+        current_profile: &Path,
+        built_profile_path: &Path,
+    ) -> (PathBuf, PathBuf) {
+        // Only adjust for an explicitly-named `--specialisation NAME`; an empty value means
+        // "prompt interactively", which happens later in `activate_configuration`, and
+        // `no_specialisation` means "diff/activate the base system", the default already.
+        let Some(name) = platform_args.specialisation.as_deref().filter(|n| !n.is_empty()) else {
+            return (current_profile.to_path_buf(), built_profile_path.to_path_buf());
+        };
+        if platform_args.no_specialisation {
+            return (current_profile.to_path_buf(), built_profile_path.to_path_buf());
+        }
+
+        let current_spec = current_profile.join("specialisation").join(name);
+        let new_spec = built_profile_path.join("specialisation").join(name);
+        if current_spec.exists() && new_spec.exists() {
+            (current_spec, new_spec)
+        } else {
+            (current_profile.to_path_buf(), built_profile_path.to_path_buf())
+        }
+    }
+}
+
+/// Builds the command string run over `ssh target_host <command>` to activate a build on a
+/// remote machine. Every argument is shell-quoted since the remote shell re-parses this whole
+/// string, and `switch_script_path` is derived from a specialisation name that ultimately comes
+/// from `--specialisation`.
+fn build_activation_remote_command(switch_script_path: &Path, action_str: &str) -> String {
+    format!(
+        "{} {}",
+        util::shell_quote(&switch_script_path.display().to_string()),
+        util::shell_quote(action_str)
+    )
+}
+
+/// Builds the remote shell script that snapshots the current system and schedules a background
+/// rollback to it after `timeout_secs`, writing its PID to `pidfile` for later cancellation.
+fn build_rollback_schedule_script(timeout_secs: u64, pidfile: &str) -> String {
+    format!(
+        "current=$(readlink -f /run/current-system); \
+         nohup sh -c 'sleep {timeout_secs}; \"$0\"/bin/switch-to-configuration switch' \"$current\" >/tmp/ng-confirm-rollback.log 2>&1 & \
+         echo $! > {}",
+        util::shell_quote(pidfile)
+    )
+}
+
+/// Builds the remote shell command that kills the scheduled rollback job and removes its
+/// pidfile, run once the user confirms the new configuration is working.
+fn build_rollback_cancel_command(pidfile: &str) -> String {
+    format!(
+        "kill $(cat {pidfile}) 2>/dev/null; rm -f {pidfile}",
+        pidfile = util::shell_quote(pidfile)
+    )
+}
+
+impl NixosPlatformStrategy {
+    /// Activates a built configuration on a remote machine instead of locally: copies the
+    /// closure over with `nix copy`, then runs `switch-to-configuration` over SSH. Specialisation
+    /// selection follows the same `--specialisation`/`--no-specialisation` flags as local
+    /// activation, but is resolved against the local build output since it's just a symlink name.
+    fn activate_remote(
+        &self,
+        platform_args: &OsRebuildArgs,
+        built_profile_path: &Path,
+        action_str: &str,
+        target_host: &str,
+    ) -> Result<()> {
+        info!("Copying closure to {} via `nix copy`", target_host);
+        let mut copy_cmd = StdCommand::new("nix");
+        copy_cmd
+            .arg("copy")
+            .arg("--to")
+            .arg(format!("ssh://{target_host}"))
+            .arg(built_profile_path);
+        util::run_cmd_inherit_stdio(&mut copy_cmd)
+            .map_err(|e| eyre!("Failed to copy closure to '{}': {}", target_host, e))?;
+
+        let specialisation = crate::specialisation::resolve_specialisation(
+            built_profile_path,
+            platform_args.specialisation.as_deref(),
+            platform_args.no_specialisation,
+        )?;
+        let activation_dir = match &specialisation {
+            Some(name) => {
+                info!("Activating specialisation '{}' on {}", name, target_host);
+                built_profile_path.join("specialisation").join(name)
+            }
+            None => built_profile_path.to_path_buf(),
+        };
+        let switch_script_path = activation_dir.join("bin/switch-to-configuration");
+
+        // Suffixed with our own PID so concurrent `--confirm-timeout` runs against the same
+        // `--target-host` (e.g. two overlapping deploys) don't stomp on each other's rollback jobs.
+        let rollback_pidfile = format!("/tmp/ng-confirm-rollback-{}.pid", std::process::id());
+        if let Some(timeout_secs) = platform_args.confirm_timeout {
+            self.schedule_confirm_timeout_rollback(target_host, timeout_secs, &rollback_pidfile)?;
+        }
+
+        let remote_command = build_activation_remote_command(&switch_script_path, action_str);
+        info!("Executing remote NixOS activation on {}: {}", target_host, remote_command);
+
+        let mut ssh_cmd = StdCommand::new("ssh");
+        ssh_cmd.arg(target_host).arg(remote_command);
+        util::run_cmd_inherit_stdio(&mut ssh_cmd)
+            .map_err(|e| {
+                let context_msg = format!("Remote NixOS activation on '{}' with action '{}' failed.", target_host, action_str);
+                match e {
+                    UtilCommandError::InheritedNonZeroStatus { command_str, status_code } => {
+                        eyre!("{}. Command: '{}', Status: {}", context_msg, command_str, status_code)
+                    }
+                    _ => eyre!("{}: {}", context_msg, e),
+                }
+            })?;
+
+        info!("NixOS configuration ({}) activated successfully on {}.", action_str, target_host);
+
+        if let Some(timeout_secs) = platform_args.confirm_timeout {
+            self.await_confirmation_or_rollback(target_host, timeout_secs, &rollback_pidfile)?;
+        }
+
+        Ok(())
+    }
+
+    /// Captures the currently-active system profile on `target_host` and schedules a background
+    /// job there that rolls back to it after `timeout_secs`, writing the job's PID to
+    /// `pidfile` so it can be cancelled by [`Self::await_confirmation_or_rollback`]. Must be
+    /// called *before* the new configuration is activated, since it snapshots
+    /// `/run/current-system` as the rollback target.
+    fn schedule_confirm_timeout_rollback(&self, target_host: &str, timeout_secs: u64, pidfile: &str) -> Result<()> {
+        info!(
+            "Scheduling automatic rollback on {} in {}s unless confirmed (magic rollback)",
+            target_host, timeout_secs
+        );
+
+        let remote_script = build_rollback_schedule_script(timeout_secs, pidfile);
+
+        let mut ssh_cmd = StdCommand::new("ssh");
+        ssh_cmd.arg(target_host).arg(remote_script);
+        util::run_cmd_inherit_stdio(&mut ssh_cmd)
+            .map_err(|e| eyre!("Failed to schedule rollback timer on '{}': {}", target_host, e))?;
+
+        Ok(())
+    }
+
+    /// Blocks for up to `timeout_secs` waiting for the user to confirm the new configuration on
+    /// `target_host`. If confirmed in time, cancels the rollback job scheduled by
+    /// [`Self::schedule_confirm_timeout_rollback`]; otherwise leaves it running so it fires and
+    /// rolls the host back on its own.
+    fn await_confirmation_or_rollback(&self, target_host: &str, timeout_secs: u64, pidfile: &str) -> Result<()> {
+        warn!(
+            "The new configuration on {target_host} will be automatically rolled back in {timeout_secs}s unless confirmed."
+        );
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let target_host_owned = target_host.to_string();
+        std::thread::spawn(move || {
+            let confirmed = dialoguer::Confirm::new()
+                .with_prompt(format!("Configuration on {target_host_owned} is working, keep it?"))
+                .default(true)
+                .interact()
+                .unwrap_or(false);
+            let _ = tx.send(confirmed);
+        });
+
+        let confirmed = rx
+            .recv_timeout(std::time::Duration::from_secs(timeout_secs))
+            .unwrap_or(false);
+
+        if confirmed {
+            info!("Confirmed. Cancelling scheduled rollback on {}.", target_host);
+            let mut cancel_cmd = StdCommand::new("ssh");
+            cancel_cmd
+                .arg(target_host)
+                .arg(build_rollback_cancel_command(pidfile));
+            if let Err(e) = util::run_cmd_inherit_stdio(&mut cancel_cmd) {
+                warn!("Failed to cancel scheduled rollback on {}: {}", target_host, e);
+            }
+        } else {
+            warn!(
+                "No confirmation received in time; {} will roll back to the previous generation automatically.",
+                target_host
+            );
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    // use super::*; // Removed as unused
-    
+    use super::*;
+
     // Test pre_rebuild_hook for root check logic
     // Test get_toplevel_installable with various Installable inputs, hostname, specialisation flags
     // Test activate_configuration by asserting the command string it would build for switch, boot, test
+
+    #[test]
+    fn test_build_activation_remote_command_quotes_arguments() {
+        let cmd = build_activation_remote_command(
+            Path::new("/nix/store/somehash-config/bin/switch-to-configuration"),
+            "switch",
+        );
+        assert_eq!(
+            cmd,
+            "'/nix/store/somehash-config/bin/switch-to-configuration' 'switch'"
+        );
+    }
+
+    #[test]
+    fn test_build_activation_remote_command_escapes_special_chars_in_path() {
+        // Specialisation names come from `--specialisation`, so an adversarial or careless
+        // value shouldn't be able to break out of the intended remote command: the whole path
+        // must stay inside one single-quoted shell word, semicolons and all.
+        let malicious_path = "/nix/store/somehash-config/specialisation/foo; rm -rf /$(bar)/bin/switch-to-configuration";
+        let cmd = build_activation_remote_command(Path::new(malicious_path), "switch");
+        assert_eq!(cmd, format!("'{malicious_path}' 'switch'"));
+    }
+
+    #[test]
+    fn test_build_rollback_schedule_script_quotes_pidfile() {
+        let script = build_rollback_schedule_script(30, "/tmp/ng-confirm-rollback.pid");
+        assert!(script.contains("sleep 30;"));
+        assert!(script.contains("echo $! > '/tmp/ng-confirm-rollback.pid'"));
+    }
+
+    #[test]
+    fn test_build_rollback_cancel_command_quotes_pidfile() {
+        let cmd = build_rollback_cancel_command("/tmp/ng-confirm-rollback.pid");
+        assert_eq!(
+            cmd,
+            "kill $(cat '/tmp/ng-confirm-rollback.pid') 2>/dev/null; rm -f '/tmp/ng-confirm-rollback.pid'"
+        );
+    }
+
+    #[test]
+    fn test_build_rollback_schedule_script_and_cancel_command_agree_on_pidfile() {
+        // The pidfile written by the schedule script must be the exact path the cancel
+        // command later reads back, or `--confirm-timeout` can't cancel its own rollback.
+        let pidfile = "/tmp/ng-confirm-rollback.pid";
+        let schedule = build_rollback_schedule_script(120, pidfile);
+        let cancel = build_rollback_cancel_command(pidfile);
+        let quoted_pidfile = util::shell_quote(pidfile);
+        assert!(schedule.ends_with(&format!("echo $! > {quoted_pidfile}")));
+        assert!(cancel.contains(&format!("cat {quoted_pidfile}")));
+        assert!(cancel.contains(&format!("rm -f {quoted_pidfile}")));
+    }
 }
\ No newline at end of file
@@ -25,11 +25,15 @@ mod tests {
             medium_checks: false,
             full_checks: false,
             dry_run: true,
-            ask_confirmation: false,
+            confirm_stages: Vec::new(),
             no_nom: true,
             out_link: None,
             clean_after: false,
             extra_build_args: Vec::<OsString>::new(),
+            keep_going: false,
+            json: false,
+            plan: false,
+            no_group: false,
         };
         let nix_interface = crate::nix_interface::NixInterface::new(verbose_count, common_args.dry_run);
         OperationContext::new(
@@ -51,10 +55,15 @@ mod tests {
             medium: false,
             full: false,
             dry: true,
-            ask: false,
+            ask: Vec::new(),
+            no_ask: true,
             no_nom: true,
             out_link: None,
             clean: false,
+            keep_going: false,
+            json: false,
+            plan: false,
+            no_group: false,
         };
 
         let interface_common_rebuild_args = InterfaceCommonRebuildArgs {
@@ -76,6 +85,10 @@ mod tests {
             no_specialisation: false,
             extra_args: Vec::<String>::new(),
             bypass_root_check,
+            target_host: None,
+            build_host: None,
+            confirm_timeout: None,
+            profile_name: None,
         }
     }
 
@@ -32,7 +32,7 @@ mod tests {
         if let NGCommand::Os(os_args) = &cli.command {
             if let OsSubcommand::Switch(switch_args) = &os_args.subcommand {
                 assert!(switch_args.common.common.dry, "Expected --dry flag to be parsed as true");
-                assert!(!switch_args.common.common.ask, "Expected --no-ask flag to result in ask=false");
+                assert!(!switch_args.common.common.asks_anything(), "Expected --no-ask flag to suppress all confirmation");
             } else {
                 panic!("Expected OsSubcommand::Switch for this test case.");
             }
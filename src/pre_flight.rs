@@ -1,17 +1,21 @@
 use std::collections::HashMap; // Added for the map
-use std::path::PathBuf; // ADDED
+use std::path::{Path, PathBuf}; // ADDED
 // use std::path::PathBuf; // Removed
 use std::sync::Arc;
 
 use color_eyre::eyre::bail;
+use ide::FileId;
+use indicatif::ProgressBar;
 use regex::Regex; // Added for Deadnix parsing
+use serde::Deserialize;
 use serde_json; // Added for Statix JSON parsing
 use tracing::{debug, info, warn};
 
 use crate::context::OperationContext;
 use crate::error_handler;
 use crate::external_linter_types; // Added for Statix types
-use crate::nix_analyzer::{NgDiagnostic, NgSeverity, NixAnalysisContext}; // Ensure NgDiagnostic is here
+use crate::installable::Installable;
+use crate::nix_analyzer::{NgDiagnostic, NgSeverity}; // Ensure NgDiagnostic is here
 use crate::progress;
 use crate::util; // For command_exists and find_nix_files_walkdir
 use crate::workflow_strategy::PlatformRebuildStrategy;
@@ -31,6 +35,7 @@ pub trait PreFlightCheck: std::fmt::Debug + Send + Sync {
     fn run<S: PlatformRebuildStrategy>(
         &self,
         op_ctx: &OperationContext,
+        pb: &ProgressBar,
         platform_strategy: &S,
         platform_args: &S::PlatformArgs,
     ) -> Result<CheckStatusReport>;
@@ -43,6 +48,18 @@ pub enum AnyPreFlightCheck {
     Semantic(SemanticPreFlightCheck),
     NixFormat(NixFormatPreFlightCheck),
     ExternalLinters(ExternalLintersPreFlightCheck), // ADDED
+    HomebrewDrift(HomebrewDriftPreFlightCheck),
+    SopsSecrets(SopsSecretsPreFlightCheck),
+    AgenixKeys(AgenixKeysPreFlightCheck),
+    FlakeInputAdvisories(FlakeInputAdvisoriesPreFlightCheck),
+    Eval(EvalPreFlightCheck),
+    Assertions(AssertionsPreFlightCheck),
+    DryRunBuild(DryRunBuildPreFlightCheck),
+    NixImplementation(NixImplementationPreFlightCheck),
+    NixConfigSanity(NixConfigSanityPreFlightCheck),
+    DiskSpace(DiskSpacePreFlightCheck),
+    MemoryAvailability(MemoryAvailabilityPreFlightCheck),
+    FlakeFetchability(FlakeFetchabilityPreFlightCheck),
 }
 
 impl AnyPreFlightCheck {
@@ -52,22 +69,57 @@ impl AnyPreFlightCheck {
             AnyPreFlightCheck::Semantic(c) => c.name(),
             AnyPreFlightCheck::NixFormat(c) => c.name(),
             AnyPreFlightCheck::ExternalLinters(c) => c.name(), // ADDED
+            AnyPreFlightCheck::HomebrewDrift(c) => c.name(),
+            AnyPreFlightCheck::SopsSecrets(c) => c.name(),
+            AnyPreFlightCheck::AgenixKeys(c) => c.name(),
+            AnyPreFlightCheck::FlakeInputAdvisories(c) => c.name(),
+            AnyPreFlightCheck::Eval(c) => c.name(),
+            AnyPreFlightCheck::Assertions(c) => c.name(),
+            AnyPreFlightCheck::DryRunBuild(c) => c.name(),
+            AnyPreFlightCheck::NixImplementation(c) => c.name(),
+            AnyPreFlightCheck::NixConfigSanity(c) => c.name(),
+            AnyPreFlightCheck::DiskSpace(c) => c.name(),
+            AnyPreFlightCheck::MemoryAvailability(c) => c.name(),
+            AnyPreFlightCheck::FlakeFetchability(c) => c.name(),
         }
     }
 
     pub fn run<S: PlatformRebuildStrategy>(
         &self,
         op_ctx: &OperationContext,
+        pb: &ProgressBar,
         platform_strategy: &S,
         platform_args: &S::PlatformArgs,
     ) -> Result<CheckStatusReport> {
         match self {
-            AnyPreFlightCheck::NixParse(c) => c.run(op_ctx, platform_strategy, platform_args),
-            AnyPreFlightCheck::Semantic(c) => c.run(op_ctx, platform_strategy, platform_args),
-            AnyPreFlightCheck::NixFormat(c) => c.run(op_ctx, platform_strategy, platform_args),
+            AnyPreFlightCheck::NixParse(c) => c.run(op_ctx, pb, platform_strategy, platform_args),
+            AnyPreFlightCheck::Semantic(c) => c.run(op_ctx, pb, platform_strategy, platform_args),
+            AnyPreFlightCheck::NixFormat(c) => c.run(op_ctx, pb, platform_strategy, platform_args),
             AnyPreFlightCheck::ExternalLinters(c) => {
-                c.run(op_ctx, platform_strategy, platform_args)
+                c.run(op_ctx, pb, platform_strategy, platform_args)
             } // ADDED
+            AnyPreFlightCheck::HomebrewDrift(c) => c.run(op_ctx, pb, platform_strategy, platform_args),
+            AnyPreFlightCheck::SopsSecrets(c) => c.run(op_ctx, pb, platform_strategy, platform_args),
+            AnyPreFlightCheck::AgenixKeys(c) => c.run(op_ctx, pb, platform_strategy, platform_args),
+            AnyPreFlightCheck::FlakeInputAdvisories(c) => {
+                c.run(op_ctx, pb, platform_strategy, platform_args)
+            }
+            AnyPreFlightCheck::Eval(c) => c.run(op_ctx, pb, platform_strategy, platform_args),
+            AnyPreFlightCheck::Assertions(c) => c.run(op_ctx, pb, platform_strategy, platform_args),
+            AnyPreFlightCheck::DryRunBuild(c) => c.run(op_ctx, pb, platform_strategy, platform_args),
+            AnyPreFlightCheck::NixImplementation(c) => {
+                c.run(op_ctx, pb, platform_strategy, platform_args)
+            }
+            AnyPreFlightCheck::NixConfigSanity(c) => {
+                c.run(op_ctx, pb, platform_strategy, platform_args)
+            }
+            AnyPreFlightCheck::DiskSpace(c) => c.run(op_ctx, pb, platform_strategy, platform_args),
+            AnyPreFlightCheck::MemoryAvailability(c) => {
+                c.run(op_ctx, pb, platform_strategy, platform_args)
+            }
+            AnyPreFlightCheck::FlakeFetchability(c) => {
+                c.run(op_ctx, pb, platform_strategy, platform_args)
+            }
         }
     }
 }
@@ -84,11 +136,12 @@ impl PreFlightCheck for NixParsePreFlightCheck {
     fn run<S: PlatformRebuildStrategy>(
         &self,
         op_ctx: &OperationContext,
+        _pb: &ProgressBar,
         _platform_strategy: &S,
         _platform_args: &S::PlatformArgs,
     ) -> Result<CheckStatusReport> {
         debug!("Running NixParsePreFlightCheck...");
-        let mut analyzer = NixAnalysisContext::new();
+        let mut analyzer = op_ctx.nix_analysis_context().borrow_mut();
         let nix_files = util::find_nix_files_walkdir(&op_ctx.get_effective_project_root())?;
         if nix_files.is_empty() {
             info!("No .nix files found for syntax check.");
@@ -116,7 +169,12 @@ impl PreFlightCheck for NixParsePreFlightCheck {
             }
         }
         if !all_diagnostics.is_empty() {
-            error_handler::report_ng_diagnostics("Syntax Check", &all_diagnostics, Some(&analyzer));
+            error_handler::report_ng_diagnostics(
+                "Syntax Check",
+                &all_diagnostics,
+                Some(&*analyzer),
+                !op_ctx.common_args.no_group,
+            );
             return Ok(CheckStatusReport::FailedCritical);
         }
         Ok(CheckStatusReport::Passed)
@@ -135,11 +193,12 @@ impl PreFlightCheck for SemanticPreFlightCheck {
     fn run<S: PlatformRebuildStrategy>(
         &self,
         op_ctx: &OperationContext,
+        _pb: &ProgressBar,
         _platform_strategy: &S,
         _platform_args: &S::PlatformArgs,
     ) -> Result<CheckStatusReport> {
         debug!("Running SemanticPreFlightCheck...");
-        let mut analyzer = NixAnalysisContext::new();
+        let mut analyzer = op_ctx.nix_analysis_context().borrow_mut();
         let nix_files = util::find_nix_files_walkdir(&op_ctx.get_effective_project_root())?;
         if nix_files.is_empty() {
             info!("No .nix files found for semantic check.");
@@ -147,6 +206,10 @@ impl PreFlightCheck for SemanticPreFlightCheck {
         }
         debug!("Found {} .nix files for semantic check", nix_files.len());
         let mut all_diagnostics = Vec::new();
+        // Parse every file first (sequential: registering a file with the analysis db and
+        // populating the parse cache needs `&mut self`), then run the actual semantic queries for
+        // the syntactically-valid files concurrently in one batch below.
+        let mut files_for_semantic_analysis = Vec::new();
         for path in nix_files {
             let content = match std::fs::read_to_string(&path) {
                 Ok(c) => Arc::new(c),
@@ -172,7 +235,16 @@ impl PreFlightCheck for SemanticPreFlightCheck {
                 }
                 continue;
             }
-            match analyzer.get_semantic_diagnostics(file_id) {
+            files_for_semantic_analysis.push((path, file_id));
+        }
+
+        let file_ids: Vec<FileId> = files_for_semantic_analysis
+            .iter()
+            .map(|(_, file_id)| *file_id)
+            .collect();
+        let semantic_results = analyzer.get_semantic_diagnostics_batch(&file_ids);
+        for ((path, file_id), result) in files_for_semantic_analysis.into_iter().zip(semantic_results) {
+            match result {
                 Ok(semantic_diagnostics_vec) => {
                     for diag in &semantic_diagnostics_vec {
                         all_diagnostics
@@ -190,7 +262,12 @@ impl PreFlightCheck for SemanticPreFlightCheck {
         let has_errors = all_diagnostics
             .iter()
             .any(|d| matches!(d.severity, NgSeverity::Error));
-        error_handler::report_ng_diagnostics("Semantic Check", &all_diagnostics, Some(&analyzer));
+        error_handler::report_ng_diagnostics(
+            "Semantic Check",
+            &all_diagnostics,
+            Some(&*analyzer),
+            !op_ctx.common_args.no_group,
+        );
         // Use strict_lint from config, falling back to CommonArgs, then false.
         let use_strict_lint = match op_ctx.common_args.strict_lint {
             Some(cli_value) => cli_value, // CLI override
@@ -222,6 +299,7 @@ impl PreFlightCheck for NixFormatPreFlightCheck {
     fn run<S: PlatformRebuildStrategy>(
         &self,
         op_ctx: &OperationContext,
+        pb: &ProgressBar,
         _platform_strategy: &S,
         _platform_args: &S::PlatformArgs,
     ) -> Result<CheckStatusReport> {
@@ -235,10 +313,16 @@ impl PreFlightCheck for NixFormatPreFlightCheck {
             .tool
             .as_deref()
             .unwrap_or("auto");
+
+        if formatter_tool_config == "treefmt" {
+            return run_treefmt_check(op_ctx);
+        }
+
         let formatter_bin = match formatter_tool_config {
-            "auto" => "nixfmt", // For now, auto just means nixfmt. Later: detect alejandra, etc.
-            other => other,
+            "auto" => detect_auto_formatter(op_ctx),
+            other => other.to_string(),
         };
+        let formatter_bin = formatter_bin.as_str();
 
         // Check if formatter exists by trying a benign command like --version.
         // This uses crate::commands::Command and is thus mockable.
@@ -277,7 +361,18 @@ impl PreFlightCheck for NixFormatPreFlightCheck {
         );
         let mut unformatted_files = Vec::new();
 
-        for path in &nix_files {
+        let total_files = nix_files.len();
+        for (index, path) in nix_files.iter().enumerate() {
+            progress::update_spinner_message(
+                pb,
+                &format!(
+                    "[Pre-flight] Running {} check ({}/{total_files}: {})",
+                    self.name(),
+                    index + 1,
+                    path.display()
+                ),
+            );
+
             let mut cmd = crate::commands::Command::new(formatter_bin);
             cmd = cmd.arg("--check").arg(path);
 
@@ -295,9 +390,21 @@ impl PreFlightCheck for NixFormatPreFlightCheck {
         }
 
         if !unformatted_files.is_empty() {
+            let show_diff = op_ctx.config.pre_flight.format.show_diff.unwrap_or(false);
+            let max_hunks = op_ctx.config.pre_flight.format.diff_hunks.unwrap_or(3);
+
             warn!("Found unformatted Nix files:");
             for file_path in &unformatted_files {
                 warn!("  - {}", file_path.display());
+                if show_diff {
+                    match util::unified_diff_after_format(file_path, formatter_bin) {
+                        Ok(diff) if !diff.is_empty() => {
+                            eprint!("{}", util::trim_diff_hunks(&diff, max_hunks));
+                        }
+                        Ok(_) => {}
+                        Err(e) => debug!("Failed to compute diff preview for {}: {}", file_path.display(), e),
+                    }
+                }
             }
             warn!(
                 "Please run '{} <file>' to format them or use 'ng fix format'.",
@@ -321,6 +428,131 @@ impl PreFlightCheck for NixFormatPreFlightCheck {
     }
 }
 
+/// Resolves the formatter to use for `tool = "auto"` (or when it's unset), mirroring what
+/// `nix fmt` would run in this repo: first the flake's own `formatter.<system>` output, then a
+/// PATH search through the common Nix formatters, then a hard-coded `nixfmt` guess if nothing
+/// else turned up (the existence check right after this call will warn and skip cleanly).
+fn detect_auto_formatter(op_ctx: &OperationContext) -> String {
+    if let Some(name) = flake_formatter_program(op_ctx) {
+        return name;
+    }
+
+    ["alejandra", "nixfmt", "nixpkgs-fmt"]
+        .into_iter()
+        .find(|cmd| util::command_exists(cmd))
+        .map(String::from)
+        .unwrap_or_else(|| "nixfmt".to_string())
+}
+
+/// Queries `<flake>#formatter.<system>.meta.mainProgram` to find the program the flake's own
+/// `nix fmt` would invoke. Returns `None` (rather than erroring) for anything other than a flake
+/// installable, or when the flake has no `formatter` output — both common cases, not failures.
+fn flake_formatter_program(op_ctx: &OperationContext) -> Option<String> {
+    let reference = match &op_ctx.common_args.installable {
+        Installable::Flake { reference, .. } => reference,
+        _ => return None,
+    };
+
+    let system = crate::commands::Command::new("nix")
+        .args(["eval", "--impure", "--raw", "--expr", "builtins.currentSystem"])
+        .add_verbosity_flags(op_ctx.verbose_count)
+        .run_capture()
+        .ok()??;
+    let system = system.trim();
+
+    crate::commands::Command::new("nix")
+        .arg("eval")
+        .arg("--raw")
+        .arg(format!("{reference}#formatter.{system}.meta.mainProgram"))
+        .add_verbosity_flags(op_ctx.verbose_count)
+        .run_capture()
+        .ok()?
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Runs `treefmt --fail-on-change` from the project root and maps whatever it reports back
+/// into diagnostics. Unlike the `nixfmt`/`alejandra` path above, file selection is delegated
+/// entirely to treefmt (via `treefmt.toml` at the project root) rather than a
+/// `find_nix_files_walkdir` scan, since a treefmt setup commonly covers non-Nix files
+/// (shell, YAML, ...) as well.
+fn run_treefmt_check(op_ctx: &OperationContext) -> Result<CheckStatusReport> {
+    if !util::command_exists("treefmt") {
+        warn!("Formatter 'treefmt' not found in PATH. Skipping format check. Please install it or configure via ng.toml.");
+        return Ok(CheckStatusReport::PassedWithWarnings);
+    }
+
+    let effective_root = op_ctx.get_effective_project_root();
+    let output = crate::commands::Command::new("treefmt")
+        .arg("--fail-on-change")
+        .arg("--no-cache")
+        .current_dir(&effective_root)
+        .run_capture_output()?;
+
+    if output.status.success() {
+        return Ok(CheckStatusReport::Passed);
+    }
+
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let diagnostics = parse_treefmt_diagnostics(&combined, &effective_root);
+
+    error_handler::report_ng_diagnostics(
+        "Nix Code Format (treefmt)",
+        &diagnostics,
+        None,
+        !op_ctx.common_args.no_group,
+    );
+
+    let use_strict_format = match op_ctx.common_args.strict_format {
+        Some(cli_value) => cli_value,
+        None => op_ctx.config.pre_flight.strict_format.unwrap_or(false),
+    };
+    if use_strict_format {
+        return Ok(CheckStatusReport::FailedCritical);
+    }
+    Ok(CheckStatusReport::PassedWithWarnings)
+}
+
+/// Best-effort parse of `treefmt --fail-on-change` output into per-file diagnostics. treefmt
+/// only reports which files it (would have) reformatted, not per-line detail, since the
+/// underlying formatters vary too much to normalize further; a bare relative path per line is
+/// the closest thing to a stable contract across treefmt versions. Falls back to a single
+/// diagnostic carrying the raw output when no path-shaped lines are found, so nothing is
+/// silently swallowed.
+fn parse_treefmt_diagnostics(output: &str, project_root: &PathBuf) -> Vec<NgDiagnostic> {
+    let path_line = Regex::new(r"^\s*([\w./-]+\.\w+)\s*$").unwrap();
+
+    let mut diagnostics: Vec<NgDiagnostic> = output
+        .lines()
+        .filter_map(|line| path_line.captures(line))
+        .map(|caps| NgDiagnostic {
+            tool_name: Some("treefmt".to_string()),
+            file_path: project_root.join(caps.get(1).unwrap().as_str()),
+            message: "would be reformatted by treefmt".to_string(),
+            line: None,
+            column: None,
+            severity: NgSeverity::Warning,
+        })
+        .collect();
+
+    if diagnostics.is_empty() {
+        diagnostics.push(NgDiagnostic {
+            tool_name: Some("treefmt".to_string()),
+            file_path: project_root.clone(),
+            message: format!("treefmt reported formatting issues:\n{}", output.trim()),
+            line: None,
+            column: None,
+            severity: NgSeverity::Warning,
+        });
+    }
+
+    diagnostics
+}
+
 // ADDED PreFlightCheck impl for ExternalLintersPreFlightCheck
 impl PreFlightCheck for ExternalLintersPreFlightCheck {
     fn name(&self) -> &str {
@@ -330,6 +562,7 @@ impl PreFlightCheck for ExternalLintersPreFlightCheck {
     fn run<S: PlatformRebuildStrategy>(
         &self,
         op_ctx: &OperationContext,
+        pb: &ProgressBar,
         _platform_strategy: &S,
         _platform_args: &S::PlatformArgs,
     ) -> Result<CheckStatusReport, color_eyre::Report> {
@@ -367,6 +600,7 @@ impl PreFlightCheck for ExternalLintersPreFlightCheck {
             .any(|name| name.eq_ignore_ascii_case("statix"))
         {
             ran_any_linter = true;
+            progress::update_spinner_message(pb, "[Pre-flight] Running External Linters check (statix)");
             info!("Checking for Statix...");
             let statix_cmd_name = op_ctx.config.pre_flight.external_linters.statix_path.as_deref().unwrap_or("statix");
             if util::command_exists(statix_cmd_name) {
@@ -496,6 +730,7 @@ impl PreFlightCheck for ExternalLintersPreFlightCheck {
             .any(|name| name.eq_ignore_ascii_case("deadnix"))
         {
             ran_any_linter = true;
+            progress::update_spinner_message(pb, "[Pre-flight] Running External Linters check (deadnix)");
             info!("Checking for Deadnix...");
             let deadnix_cmd_name = op_ctx.config.pre_flight.external_linters.deadnix_path.as_deref().unwrap_or("deadnix");
             if util::command_exists(deadnix_cmd_name) {
@@ -592,6 +827,7 @@ impl PreFlightCheck for ExternalLintersPreFlightCheck {
                 "External Linters",
                 &diagnostics,
                 None,
+                !op_ctx.common_args.no_group,
             );
         }
 
@@ -612,6 +848,1053 @@ impl PreFlightCheck for ExternalLintersPreFlightCheck {
     }
 }
 
+/// Pre-flight check comparing the `homebrew.brews`/`homebrew.casks` declared in a nix-darwin
+/// configuration against what `brew` actually has installed, since Homebrew state lives outside
+/// the Nix store and can silently drift from the declaration (manual `brew uninstall`, a brew
+/// bundle step that failed partway, etc).
+///
+/// Not applicable on platforms without a Homebrew integration; see
+/// [`PlatformRebuildStrategy::get_homebrew_options_installable`].
+#[derive(Debug, Clone, Copy)]
+pub struct HomebrewDriftPreFlightCheck;
+
+impl PreFlightCheck for HomebrewDriftPreFlightCheck {
+    fn name(&self) -> &str {
+        "Homebrew Drift"
+    }
+
+    fn run<S: PlatformRebuildStrategy>(
+        &self,
+        op_ctx: &OperationContext,
+        _pb: &ProgressBar,
+        platform_strategy: &S,
+        platform_args: &S::PlatformArgs,
+    ) -> Result<CheckStatusReport> {
+        debug!("Running HomebrewDriftPreFlightCheck...");
+
+        let Some(homebrew_installable) =
+            platform_strategy.get_homebrew_options_installable(op_ctx, platform_args)
+        else {
+            debug!(
+                "{} has no Homebrew integration; skipping Homebrew drift check.",
+                platform_strategy.name()
+            );
+            return Ok(CheckStatusReport::Passed);
+        };
+
+        let brew_bin = op_ctx
+            .config
+            .pre_flight
+            .homebrew
+            .brew_path
+            .as_deref()
+            .unwrap_or("brew");
+        if !util::command_exists(brew_bin) {
+            warn!(
+                "'{}' not found; skipping Homebrew drift check.",
+                brew_bin
+            );
+            return Ok(CheckStatusReport::PassedWithWarnings);
+        }
+
+        let declared_brews = declared_homebrew_list(op_ctx, &homebrew_installable, "brews");
+        let declared_casks = declared_homebrew_list(op_ctx, &homebrew_installable, "casks");
+
+        if declared_brews.is_empty() && declared_casks.is_empty() {
+            info!("No `homebrew.brews`/`homebrew.casks` declared; skipping Homebrew drift check.");
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        let installed_formulae = brew_list(brew_bin, &[])?;
+        let installed_casks = brew_list(brew_bin, &["--cask"])?;
+
+        let missing_brews: Vec<&String> = declared_brews
+            .iter()
+            .filter(|b| !installed_formulae.contains(*b))
+            .collect();
+        let missing_casks: Vec<&String> = declared_casks
+            .iter()
+            .filter(|c| !installed_casks.contains(*c))
+            .collect();
+
+        if missing_brews.is_empty() && missing_casks.is_empty() {
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        warn!("Homebrew state has drifted from the declared configuration:");
+        for name in &missing_brews {
+            warn!("  - brew '{}' is declared but not installed", name);
+        }
+        for name in &missing_casks {
+            warn!("  - cask '{}' is declared but not installed", name);
+        }
+        warn!("Run `brew bundle` or check for a failed activation to reconcile.");
+
+        Ok(CheckStatusReport::PassedWithWarnings)
+    }
+}
+
+/// Evaluates `homebrew.<field>` against the built configuration, warning (rather than failing
+/// the whole check) if the eval itself doesn't work out — e.g. the option isn't set at all.
+fn declared_homebrew_list(
+    op_ctx: &OperationContext,
+    homebrew_installable: &Installable,
+    field: &str,
+) -> Vec<String> {
+    let mut installable = homebrew_installable.clone();
+    if let Installable::Flake { attribute, .. } = &mut installable {
+        attribute.push(field.to_string());
+    }
+
+    match op_ctx.nix_interface.eval_json_list(&installable) {
+        Ok(names) => names,
+        Err(e) => {
+            debug!("Could not evaluate `homebrew.{}`: {}", field, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Runs `brew list [extra_args]` and returns the installed names.
+fn brew_list(brew_bin: &str, extra_args: &[&str]) -> Result<Vec<String>> {
+    let mut cmd = crate::commands::Command::new(brew_bin).arg("list");
+    for arg in extra_args {
+        cmd = cmd.arg(*arg);
+    }
+
+    let output = cmd
+        .run_capture()?
+        .unwrap_or_default();
+    Ok(output.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_string).collect())
+}
+
+/// Pre-flight check validating sops-nix secrets: when `sops.secrets` is declared, verifies every
+/// referenced sops file exists, parses, and is decryptable with the available age/gpg keys,
+/// failing before a build that would otherwise only surface the problem at activation time.
+///
+/// Not applicable on platforms without a sops-nix integration wired up here; see
+/// [`PlatformRebuildStrategy::get_sops_secrets_installable`].
+#[derive(Debug, Clone, Copy)]
+pub struct SopsSecretsPreFlightCheck;
+
+impl PreFlightCheck for SopsSecretsPreFlightCheck {
+    fn name(&self) -> &str {
+        "Sops Secrets"
+    }
+
+    fn run<S: PlatformRebuildStrategy>(
+        &self,
+        op_ctx: &OperationContext,
+        _pb: &ProgressBar,
+        platform_strategy: &S,
+        platform_args: &S::PlatformArgs,
+    ) -> Result<CheckStatusReport> {
+        debug!("Running SopsSecretsPreFlightCheck...");
+
+        let Some(secrets_installable) =
+            platform_strategy.get_sops_secrets_installable(op_ctx, platform_args)
+        else {
+            debug!(
+                "{} has no sops-nix integration; skipping sops secrets check.",
+                platform_strategy.name()
+            );
+            return Ok(CheckStatusReport::Passed);
+        };
+
+        let secrets = match op_ctx.nix_interface.eval_json(&secrets_installable) {
+            Ok(v) => v,
+            Err(e) => {
+                debug!("Could not evaluate `sops.secrets` (sops-nix likely not in use): {}", e);
+                return Ok(CheckStatusReport::Passed);
+            }
+        };
+
+        let Some(secrets_map) = secrets.as_object() else {
+            return Ok(CheckStatusReport::Passed);
+        };
+        if secrets_map.is_empty() {
+            info!("No `sops.secrets` declared; skipping sops-nix secrets check.");
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        let sops_bin = "sops";
+        let sops_available = util::command_exists(sops_bin);
+        if !sops_available {
+            warn!("'sops' not found; skipping decryption check for declared secrets.");
+        }
+
+        let mut problems = Vec::new();
+        let mut decrypted_files = std::collections::HashSet::new();
+
+        for (name, secret) in secrets_map {
+            let Some(sops_file) = secret.get("sopsFile").and_then(|v| v.as_str()) else {
+                problems.push(format!(
+                    "secret '{}' has no resolvable `sopsFile`",
+                    name
+                ));
+                continue;
+            };
+            let sops_file_path = PathBuf::from(sops_file);
+            if !sops_file_path.exists() {
+                problems.push(format!(
+                    "secret '{}' references sops file '{}', which does not exist",
+                    name, sops_file
+                ));
+                continue;
+            }
+
+            if sops_available && decrypted_files.insert(sops_file_path.clone()) {
+                let cmd = crate::commands::Command::new(sops_bin)
+                    .arg("--decrypt")
+                    .arg(&sops_file_path);
+                match cmd.run_capture_output() {
+                    Ok(output) if output.status.success() => {}
+                    Ok(output) => problems.push(format!(
+                        "sops file '{}' (used by secret '{}') failed to decrypt with the available age/gpg keys: {}",
+                        sops_file,
+                        name,
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    )),
+                    Err(e) => problems.push(format!(
+                        "failed to run '{}' to decrypt '{}': {}",
+                        sops_bin, sops_file, e
+                    )),
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        warn!("sops-nix secrets validation found problems:");
+        for problem in &problems {
+            warn!("  - {}", problem);
+        }
+        Ok(CheckStatusReport::FailedCritical)
+    }
+}
+
+/// Pre-flight check validating agenix secrets: when `age.secrets` is declared, confirms that at
+/// least one of the configured identity (host key) files exists on this machine and that every
+/// referenced `.age` file exists and decrypts with the available identities, reporting problems
+/// as [`NgDiagnostic`]s.
+///
+/// Not applicable on platforms without an agenix integration wired up here; see
+/// [`PlatformRebuildStrategy::get_agenix_secrets_installable`].
+#[derive(Debug, Clone, Copy)]
+pub struct AgenixKeysPreFlightCheck;
+
+impl PreFlightCheck for AgenixKeysPreFlightCheck {
+    fn name(&self) -> &str {
+        "Agenix Keys"
+    }
+
+    fn run<S: PlatformRebuildStrategy>(
+        &self,
+        op_ctx: &OperationContext,
+        _pb: &ProgressBar,
+        platform_strategy: &S,
+        platform_args: &S::PlatformArgs,
+    ) -> Result<CheckStatusReport> {
+        debug!("Running AgenixKeysPreFlightCheck...");
+
+        let Some(age_installable) =
+            platform_strategy.get_agenix_secrets_installable(op_ctx, platform_args)
+        else {
+            debug!(
+                "{} has no agenix integration; skipping agenix keys check.",
+                platform_strategy.name()
+            );
+            return Ok(CheckStatusReport::Passed);
+        };
+
+        let age_config = match op_ctx.nix_interface.eval_json(&age_installable) {
+            Ok(v) => v,
+            Err(e) => {
+                debug!("Could not evaluate `age` option (agenix likely not in use): {}", e);
+                return Ok(CheckStatusReport::Passed);
+            }
+        };
+
+        let Some(age_obj) = age_config.as_object() else {
+            return Ok(CheckStatusReport::Passed);
+        };
+        let Some(secrets) = age_obj.get("secrets").and_then(|v| v.as_object()) else {
+            return Ok(CheckStatusReport::Passed);
+        };
+        if secrets.is_empty() {
+            info!("No `age.secrets` declared; skipping agenix keys check.");
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        let identity_paths: Vec<PathBuf> = age_obj
+            .get("identityPaths")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(PathBuf::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let available_identities: Vec<&PathBuf> =
+            identity_paths.iter().filter(|p| p.exists()).collect();
+
+        let mut diagnostics: Vec<NgDiagnostic> = Vec::new();
+
+        if available_identities.is_empty() {
+            diagnostics.push(NgDiagnostic {
+                tool_name: Some("agenix".to_string()),
+                file_path: PathBuf::from("age.identityPaths"),
+                message: format!(
+                    "None of the configured identity files exist on this machine ({:?}); secrets cannot be decrypted here.",
+                    identity_paths
+                ),
+                line: None,
+                column: None,
+                severity: NgSeverity::Error,
+            });
+        }
+
+        let age_bin = "age";
+        let age_available = util::command_exists(age_bin);
+        if !age_available {
+            warn!("'age' not found; skipping decryption check for declared secrets.");
+        }
+
+        for (name, secret) in secrets {
+            let Some(file) = secret.get("file").and_then(|v| v.as_str()) else {
+                diagnostics.push(NgDiagnostic {
+                    tool_name: Some("agenix".to_string()),
+                    file_path: PathBuf::from(name),
+                    message: format!("secret '{}' has no resolvable `file`", name),
+                    line: None,
+                    column: None,
+                    severity: NgSeverity::Error,
+                });
+                continue;
+            };
+            let file_path = PathBuf::from(file);
+            if !file_path.exists() {
+                diagnostics.push(NgDiagnostic {
+                    tool_name: Some("agenix".to_string()),
+                    file_path: file_path.clone(),
+                    message: format!("secret '{}' references '.age' file '{}', which does not exist", name, file),
+                    line: None,
+                    column: None,
+                    severity: NgSeverity::Error,
+                });
+                continue;
+            }
+
+            if age_available && !available_identities.is_empty() {
+                let mut cmd = crate::commands::Command::new(age_bin).arg("--decrypt");
+                for identity in &available_identities {
+                    cmd = cmd.arg("--identity").arg(identity.as_path());
+                }
+                cmd = cmd.arg(&file_path);
+
+                match cmd.run_capture_output() {
+                    Ok(output) if output.status.success() => {}
+                    Ok(output) => diagnostics.push(NgDiagnostic {
+                        tool_name: Some("agenix".to_string()),
+                        file_path: file_path.clone(),
+                        message: format!(
+                            "secret '{}' failed to decrypt with the available identities (missing recipient?): {}",
+                            name,
+                            String::from_utf8_lossy(&output.stderr).trim()
+                        ),
+                        line: None,
+                        column: None,
+                        severity: NgSeverity::Error,
+                    }),
+                    Err(e) => diagnostics.push(NgDiagnostic {
+                        tool_name: Some("agenix".to_string()),
+                        file_path: file_path.clone(),
+                        message: format!("failed to run '{}' to decrypt '{}': {}", age_bin, file, e),
+                        line: None,
+                        column: None,
+                        severity: NgSeverity::Error,
+                    }),
+                }
+            }
+        }
+
+        if diagnostics.is_empty() {
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        error_handler::report_ng_diagnostics(
+            "Agenix Keys",
+            &diagnostics,
+            None,
+            !op_ctx.common_args.no_group,
+        );
+        Ok(CheckStatusReport::FailedCritical)
+    }
+}
+
+/// Advisories bundled with `ng` itself, on top of whatever the user adds via
+/// `pre_flight.security_advisories.extra_advisories` in `ng.toml`. Each entry is
+/// `(input name, locked rev, reason)`. Empty for now — populated as advisories are reported
+/// upstream, rather than guessed at.
+pub const BUNDLED_ADVISORIES: &[(&str, &str, &str)] = &[];
+
+/// Opt-in pre-flight check comparing `flake.lock`'s locked input revisions against a list of
+/// known-bad revisions/advisories (bundled + user-extendable via `ng.toml`), warning when e.g. a
+/// compromised or yanked input revision is pinned. This only reads `flake.lock` directly, so it
+/// applies to the whole flake rather than a specific platform configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct FlakeInputAdvisoriesPreFlightCheck;
+
+impl PreFlightCheck for FlakeInputAdvisoriesPreFlightCheck {
+    fn name(&self) -> &str {
+        "Flake Input Advisories"
+    }
+
+    fn run<S: PlatformRebuildStrategy>(
+        &self,
+        op_ctx: &OperationContext,
+        _pb: &ProgressBar,
+        _platform_strategy: &S,
+        _platform_args: &S::PlatformArgs,
+    ) -> Result<CheckStatusReport> {
+        debug!("Running FlakeInputAdvisoriesPreFlightCheck...");
+
+        let lock_path = op_ctx.get_effective_project_root().join("flake.lock");
+        if !lock_path.exists() {
+            debug!("No flake.lock at {}; skipping flake input advisory check.", lock_path.display());
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        let contents = std::fs::read_to_string(&lock_path)
+            .map_err(|e| color_eyre::eyre::eyre!("Failed to read {}: {}", lock_path.display(), e))?;
+        let lock: serde_json::Value = serde_json::from_str(&contents)
+            .map_err(|e| color_eyre::eyre::eyre!("Failed to parse {}: {}", lock_path.display(), e))?;
+
+        let Some(nodes) = lock.get("nodes").and_then(|v| v.as_object()) else {
+            return Ok(CheckStatusReport::Passed);
+        };
+
+        let extra_advisories = &op_ctx.config.pre_flight.security_advisories.extra_advisories;
+        let mut advisories: Vec<(&str, &str, &str)> = BUNDLED_ADVISORIES.to_vec();
+        advisories.extend(
+            extra_advisories
+                .iter()
+                .map(|a| (a.input.as_str(), a.rev.as_str(), a.reason.as_str())),
+        );
+
+        if advisories.is_empty() {
+            debug!("No flake input advisories configured; skipping check.");
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        let mut hits = Vec::new();
+        for (name, node) in nodes {
+            if name == "root" {
+                continue;
+            }
+            let Some(rev) = node
+                .get("locked")
+                .and_then(|l| l.get("rev"))
+                .and_then(|r| r.as_str())
+            else {
+                continue;
+            };
+
+            for (adv_input, adv_rev, reason) in &advisories {
+                if *adv_input == name.as_str() && *adv_rev == rev {
+                    hits.push(format!(
+                        "input '{}' is pinned to advisory revision {} — {}",
+                        name, rev, reason
+                    ));
+                }
+            }
+        }
+
+        if hits.is_empty() {
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        warn!("Flake input security advisories matched:");
+        for hit in &hits {
+            warn!("  - {}", hit);
+        }
+        Ok(CheckStatusReport::PassedWithWarnings)
+    }
+}
+
+/// Pre-flight check that evaluates the platform's toplevel derivation (without building it) to
+/// catch eval errors before a full build is attempted. Included by default whenever `--medium`
+/// or `--full` is passed; can also be selected explicitly via `ng.toml`'s `pre_flight.checks`.
+#[derive(Debug)]
+pub struct EvalPreFlightCheck;
+
+impl PreFlightCheck for EvalPreFlightCheck {
+    fn name(&self) -> &str {
+        "Eval Check"
+    }
+
+    fn run<S: PlatformRebuildStrategy>(
+        &self,
+        op_ctx: &OperationContext,
+        _pb: &ProgressBar,
+        platform_strategy: &S,
+        platform_args: &S::PlatformArgs,
+    ) -> Result<CheckStatusReport> {
+        debug!("Running EvalPreFlightCheck...");
+
+        let mut installable = platform_strategy.get_toplevel_installable(op_ctx, platform_args)?;
+        match &mut installable {
+            Installable::Flake { attribute, .. }
+            | Installable::File { attribute, .. }
+            | Installable::Expression { attribute, .. } => {
+                attribute.push("drvPath".to_string());
+            }
+            Installable::Store { .. } => return Ok(CheckStatusReport::Passed),
+        }
+
+        let output = crate::commands::Command::new("nix")
+            .arg("eval")
+            .arg("--raw")
+            .arg(installable.to_args().join(" "))
+            .add_verbosity_flags(op_ctx.verbose_count)
+            .run_capture_output()?;
+
+        if output.status.success() {
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        if let Some((message, file, line, column)) = error_handler::parse_nix_eval_error(&stderr) {
+            error_handler::report_failure(
+                self.name(),
+                &format!("{message} at {file}:{line}:{column}"),
+                Some(stderr),
+                vec!["Fix the evaluation error reported above before rebuilding.".to_string()],
+            );
+        } else {
+            error_handler::report_failure(self.name(), "`nix eval` failed", Some(stderr), vec![]);
+        }
+        Ok(CheckStatusReport::FailedCritical)
+    }
+}
+
+/// A single entry of NixOS's `config.assertions`.
+#[derive(Debug, Deserialize)]
+struct ModuleAssertion {
+    assertion: bool,
+    message: String,
+}
+
+/// Pre-flight check that evaluates the target configuration's `config.assertions` and
+/// `config.warnings` via `nix eval --json` and reports failed assertions through
+/// `error_handler`, so a broken module surfaces here instead of exploding mid-build with a raw
+/// `nix build` trace. Included by default whenever `--medium` or `--full` is passed; can also be
+/// selected explicitly via `ng.toml`'s `pre_flight.checks`.
+#[derive(Debug)]
+pub struct AssertionsPreFlightCheck;
+
+impl AssertionsPreFlightCheck {
+    /// Evaluates `config.<option>` for `installable` as JSON, returning `None` if the option
+    /// can't be evaluated (e.g. a module tree that doesn't define `assertions`/`warnings` at
+    /// all) rather than treating that as a hard error — `EvalPreFlightCheck` already catches
+    /// real evaluation failures.
+    fn eval_config_option(
+        op_ctx: &OperationContext,
+        installable: &Installable,
+        option: &str,
+    ) -> Option<String> {
+        let mut option_installable = installable.clone();
+        match &mut option_installable {
+            Installable::Flake { attribute, .. }
+            | Installable::File { attribute, .. }
+            | Installable::Expression { attribute, .. } => attribute.push(option.to_string()),
+            Installable::Store { .. } => return None,
+        }
+
+        let output = crate::commands::Command::new("nix")
+            .arg("eval")
+            .arg("--json")
+            .arg(option_installable.to_args().join(" "))
+            .add_verbosity_flags(op_ctx.verbose_count)
+            .run_capture_output()
+            .ok()?;
+
+        if !output.status.success() {
+            debug!(
+                "Could not evaluate config.{}: {}",
+                option,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return None;
+        }
+
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+impl PreFlightCheck for AssertionsPreFlightCheck {
+    fn name(&self) -> &str {
+        "Module Assertions"
+    }
+
+    fn run<S: PlatformRebuildStrategy>(
+        &self,
+        op_ctx: &OperationContext,
+        _pb: &ProgressBar,
+        platform_strategy: &S,
+        platform_args: &S::PlatformArgs,
+    ) -> Result<CheckStatusReport> {
+        debug!("Running AssertionsPreFlightCheck...");
+
+        let mut config_installable = platform_strategy.get_toplevel_installable(op_ctx, platform_args)?;
+        match &mut config_installable {
+            // Swap the `system.build.toplevel` tail for nothing, leaving `...#config`, so we can
+            // append `assertions`/`warnings` below instead of the derivation path.
+            Installable::Flake { attribute, .. }
+            | Installable::File { attribute, .. }
+            | Installable::Expression { attribute, .. } => {
+                attribute.truncate(attribute.len().saturating_sub(3));
+            }
+            Installable::Store { .. } => return Ok(CheckStatusReport::Passed),
+        }
+
+        let mut status = CheckStatusReport::Passed;
+
+        if let Some(json) = Self::eval_config_option(op_ctx, &config_installable, "assertions") {
+            let assertions: Vec<ModuleAssertion> = match serde_json::from_str(&json) {
+                Ok(a) => a,
+                Err(e) => {
+                    debug!("Failed to parse config.assertions as JSON: {}", e);
+                    Vec::new()
+                }
+            };
+            let failed: Vec<String> = assertions
+                .into_iter()
+                .filter(|a| !a.assertion)
+                .map(|a| a.message)
+                .collect();
+            if !failed.is_empty() {
+                error_handler::report_failure(
+                    self.name(),
+                    "One or more module assertions failed",
+                    Some(failed.join("\n")),
+                    vec!["Fix the assertion(s) above before rebuilding.".to_string()],
+                );
+                status = CheckStatusReport::FailedCritical;
+            }
+        }
+
+        if let Some(json) = Self::eval_config_option(op_ctx, &config_installable, "warnings") {
+            let warnings: Vec<String> = match serde_json::from_str(&json) {
+                Ok(w) => w,
+                Err(e) => {
+                    debug!("Failed to parse config.warnings as JSON: {}", e);
+                    Vec::new()
+                }
+            };
+            if !warnings.is_empty() {
+                warn!("Module warnings:");
+                for message in &warnings {
+                    warn!("  - {}", message);
+                }
+                if status == CheckStatusReport::Passed {
+                    status = CheckStatusReport::PassedWithWarnings;
+                }
+            }
+        }
+
+        Ok(status)
+    }
+}
+
+/// Pre-flight check that runs `nix build --dry-run` against the platform's toplevel derivation.
+/// Catches build-plan errors (e.g. a derivation that fails to instantiate) before committing to
+/// the real build. Included by default whenever `--full` is passed; can also be selected
+/// explicitly via `ng.toml`'s `pre_flight.checks`.
+#[derive(Debug)]
+pub struct DryRunBuildPreFlightCheck;
+
+impl PreFlightCheck for DryRunBuildPreFlightCheck {
+    fn name(&self) -> &str {
+        "Dry-Run Build"
+    }
+
+    fn run<S: PlatformRebuildStrategy>(
+        &self,
+        op_ctx: &OperationContext,
+        _pb: &ProgressBar,
+        platform_strategy: &S,
+        platform_args: &S::PlatformArgs,
+    ) -> Result<CheckStatusReport> {
+        debug!("Running DryRunBuildPreFlightCheck...");
+
+        let installable = platform_strategy.get_toplevel_installable(op_ctx, platform_args)?;
+
+        let output = crate::commands::Command::new("nix")
+            .arg("build")
+            .args(installable.to_args())
+            .arg("--dry-run")
+            .add_verbosity_flags(op_ctx.verbose_count)
+            .run_capture_output()?;
+
+        if output.status.success() {
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        if let Some((message, file, line, column)) = error_handler::parse_nix_eval_error(&stderr) {
+            error_handler::report_failure(
+                self.name(),
+                &format!("{message} at {file}:{line}:{column}"),
+                Some(stderr),
+                vec!["Fix the error reported above before rebuilding.".to_string()],
+            );
+        } else {
+            error_handler::report_failure(self.name(), "`nix build --dry-run` failed", Some(stderr), vec![]);
+        }
+        Ok(CheckStatusReport::FailedCritical)
+    }
+}
+
+/// Pre-flight check that detects the installed Nix implementation (cpp-Nix vs Lix) and warns if
+/// an experimental feature `ng` needs (`nix-command`, `flakes`) isn't enabled, instead of letting
+/// a later flake-specific invocation fail with a cryptic nix error.
+#[derive(Debug)]
+pub struct NixImplementationPreFlightCheck;
+
+impl PreFlightCheck for NixImplementationPreFlightCheck {
+    fn name(&self) -> &str {
+        "Nix Implementation"
+    }
+
+    fn run<S: PlatformRebuildStrategy>(
+        &self,
+        op_ctx: &OperationContext,
+        _pb: &ProgressBar,
+        _platform_strategy: &S,
+        _platform_args: &S::PlatformArgs,
+    ) -> Result<CheckStatusReport> {
+        debug!("Running NixImplementationPreFlightCheck...");
+
+        let env = op_ctx.nix_environment()?;
+        debug!(
+            "Detected {} {}",
+            if env.is_lix { "Lix" } else { "Nix" },
+            env.version
+        );
+
+        if let Err(e) = env.ensure_flake_support() {
+            warn!("{}", e);
+            return Ok(CheckStatusReport::PassedWithWarnings);
+        }
+
+        Ok(CheckStatusReport::Passed)
+    }
+}
+
+/// Pre-flight check that validates the local Nix configuration is sane enough for `ng` to work
+/// reliably: `nix-command`/`flakes` are enabled, the invoking user is trusted by the Nix daemon
+/// (or already covered by `trusted-users`), and every configured substituter has a matching entry
+/// in `trusted-public-keys`. Fails early with precise remediation when `nix-command`/`flakes`
+/// are missing; an untrusted user or a substituter without a matching key is only reported as a
+/// warning and does not fail the check.
+#[derive(Debug)]
+pub struct NixConfigSanityPreFlightCheck;
+
+impl PreFlightCheck for NixConfigSanityPreFlightCheck {
+    fn name(&self) -> &str {
+        "Nix Configuration Sanity"
+    }
+
+    fn run<S: PlatformRebuildStrategy>(
+        &self,
+        op_ctx: &OperationContext,
+        _pb: &ProgressBar,
+        _platform_strategy: &S,
+        _platform_args: &S::PlatformArgs,
+    ) -> Result<CheckStatusReport> {
+        debug!("Running NixConfigSanityPreFlightCheck...");
+
+        let env = op_ctx.nix_environment()?;
+        if let Err(e) = env.ensure_flake_support() {
+            error_handler::report_failure(
+                self.name(),
+                "Required experimental Nix feature(s) are not enabled",
+                Some(e.to_string()),
+                vec![
+                    "Add `experimental-features = nix-command flakes` to your nix.conf."
+                        .to_string(),
+                ],
+            );
+            return Ok(CheckStatusReport::FailedCritical);
+        }
+
+        let mut warnings = Vec::new();
+
+        match crate::commands::Command::new("nix")
+            .args(["store", "ping", "--json"])
+            .run_capture()
+        {
+            Ok(Some(output)) => {
+                let trusted = serde_json::from_str::<serde_json::Value>(&output)
+                    .ok()
+                    .and_then(|v| v.get("trusted").and_then(|t| t.as_bool()))
+                    .unwrap_or(false);
+                if !trusted {
+                    warnings.push(
+                        "The current user is not a trusted Nix user, so custom substituters and \
+                        `--option` overrides in flakes may be silently ignored. Add yourself to \
+                        `trusted-users` in nix.conf if you rely on those."
+                            .to_string(),
+                    );
+                }
+            }
+            Ok(None) => {}
+            Err(e) => debug!("`nix store ping --json` failed, skipping trust check: {}", e),
+        }
+
+        let substituters = crate::commands::Command::new("nix")
+            .args(["config", "show", "substituters"])
+            .run_capture()
+            .unwrap_or_default()
+            .unwrap_or_default();
+        let trusted_keys = crate::commands::Command::new("nix")
+            .args(["config", "show", "trusted-public-keys"])
+            .run_capture()
+            .unwrap_or_default()
+            .unwrap_or_default();
+
+        for substituter in substituters.split_whitespace() {
+            let Some(host) = substituter
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .filter(|h| !h.is_empty())
+            else {
+                continue;
+            };
+            if host.contains("cache.nixos.org") {
+                continue;
+            }
+            let has_key = trusted_keys
+                .split_whitespace()
+                .any(|key| key.split(':').next().is_some_and(|name| name == host));
+            if !has_key {
+                warnings.push(format!(
+                    "Substituter '{substituter}' has no matching entry in `trusted-public-keys`; \
+                    nix will refuse to fetch from it. Add its signing key to nix.conf."
+                ));
+            }
+        }
+
+        if warnings.is_empty() {
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        warn!("Nix configuration sanity check found potential issues:");
+        for w in &warnings {
+            warn!("  - {}", w);
+        }
+        Ok(CheckStatusReport::PassedWithWarnings)
+    }
+}
+
+/// Pre-flight check that estimates how much disk space a pending build needs (from `nix build
+/// --dry-run`'s download/unpacked size reporting) and compares it against free space on the Nix
+/// store filesystem, so a build doesn't die partway through with a full disk — those are painful
+/// to recover from. Configurable via `pre_flight.disk_space` in `ng.toml`.
+#[derive(Debug)]
+pub struct DiskSpacePreFlightCheck;
+
+impl PreFlightCheck for DiskSpacePreFlightCheck {
+    fn name(&self) -> &str {
+        "Disk Space"
+    }
+
+    fn run<S: PlatformRebuildStrategy>(
+        &self,
+        op_ctx: &OperationContext,
+        _pb: &ProgressBar,
+        platform_strategy: &S,
+        platform_args: &S::PlatformArgs,
+    ) -> Result<CheckStatusReport> {
+        debug!("Running DiskSpacePreFlightCheck...");
+
+        let installable = platform_strategy.get_toplevel_installable(op_ctx, platform_args)?;
+        let plan = op_ctx.nix_interface.build_plan_summary(&installable)?;
+        let needed_bytes = plan.unpacked_bytes.or(plan.download_bytes).unwrap_or(0);
+
+        let disk_space_config = &op_ctx.config.pre_flight.disk_space;
+        let headroom_bytes = disk_space_config.min_headroom_bytes.unwrap_or(1024 * 1024 * 1024);
+        let required_bytes = needed_bytes + headroom_bytes;
+
+        let store_dir = Path::new("/nix/store");
+        let free_bytes = match nix::sys::statvfs::statvfs(store_dir) {
+            Ok(stat) => stat.blocks_available() * stat.fragment_size(),
+            Err(e) => {
+                debug!("Failed to statvfs {}: {e}, skipping disk space check", store_dir.display());
+                return Ok(CheckStatusReport::Passed);
+            }
+        };
+
+        if free_bytes >= required_bytes {
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        let message = format!(
+            "Only {} free on {}, but this build needs an estimated {} ({} headroom)",
+            crate::tables::format_bytes(free_bytes),
+            store_dir.display(),
+            crate::tables::format_bytes(required_bytes),
+            crate::tables::format_bytes(headroom_bytes),
+        );
+
+        if disk_space_config.strict {
+            error_handler::report_failure(
+                self.name(),
+                &message,
+                None,
+                vec![
+                    "Free up space (e.g. `ng clean all`) before retrying, or relax \
+                    `pre_flight.disk_space.strict`/`min_headroom_bytes` in ng.toml."
+                        .to_string(),
+                ],
+            );
+            return Ok(CheckStatusReport::FailedCritical);
+        }
+
+        warn!("{message}");
+        Ok(CheckStatusReport::PassedWithWarnings)
+    }
+}
+
+/// Pre-flight check that warns when available RAM+swap is below a configurable threshold, since
+/// evaluating large configurations can OOM small VPSes. Linux-only (reads `/proc/meminfo`); a
+/// no-op elsewhere. Not part of the default check selection — opt in via `pre_flight.checks` in
+/// ng.toml.
+#[derive(Debug)]
+pub struct MemoryAvailabilityPreFlightCheck;
+
+impl PreFlightCheck for MemoryAvailabilityPreFlightCheck {
+    fn name(&self) -> &str {
+        "Memory Availability"
+    }
+
+    fn run<S: PlatformRebuildStrategy>(
+        &self,
+        op_ctx: &OperationContext,
+        _pb: &ProgressBar,
+        _platform_strategy: &S,
+        _platform_args: &S::PlatformArgs,
+    ) -> Result<CheckStatusReport> {
+        debug!("Running MemoryAvailabilityPreFlightCheck...");
+
+        let Some(available_bytes) = available_memory_bytes() else {
+            debug!("Could not determine available memory on this platform, skipping check");
+            return Ok(CheckStatusReport::Passed);
+        };
+
+        let threshold_bytes = op_ctx
+            .config
+            .pre_flight
+            .memory
+            .min_available_bytes
+            .unwrap_or(2 * 1024 * 1024 * 1024);
+
+        if available_bytes >= threshold_bytes {
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        warn!(
+            "Only {} of RAM+swap available, below the configured {} threshold; evaluating a \
+            large configuration may run out of memory. Consider `--max-jobs 1` or offloading to \
+            a remote builder.",
+            crate::tables::format_bytes(available_bytes),
+            crate::tables::format_bytes(threshold_bytes),
+        );
+        Ok(CheckStatusReport::PassedWithWarnings)
+    }
+}
+
+/// Combined available RAM + free swap, in bytes, from `/proc/meminfo`. `MemAvailable` already
+/// accounts for reclaimable caches, so it's a better OOM predictor than raw `MemFree`.
+#[cfg(target_os = "linux")]
+fn available_memory_bytes() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let mut mem_available_kb = None;
+    let mut swap_free_kb = None;
+    for line in meminfo.lines() {
+        if let Some(rest) = line.strip_prefix("MemAvailable:") {
+            mem_available_kb = rest.trim().trim_end_matches(" kB").trim().parse::<u64>().ok();
+        } else if let Some(rest) = line.strip_prefix("SwapFree:") {
+            swap_free_kb = rest.trim().trim_end_matches(" kB").trim().parse::<u64>().ok();
+        }
+    }
+    Some((mem_available_kb? + swap_free_kb.unwrap_or(0)) * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn available_memory_bytes() -> Option<u64> {
+    None
+}
+
+/// Opt-in pre-flight check that runs `nix flake archive --dry-run` against the project's flake to
+/// confirm every input is fetchable with the current credentials/network, catching private-repo
+/// auth failures or network outages before the (much slower) main evaluation gets to them.
+#[derive(Debug)]
+pub struct FlakeFetchabilityPreFlightCheck;
+
+impl PreFlightCheck for FlakeFetchabilityPreFlightCheck {
+    fn name(&self) -> &str {
+        "Flake Input Fetchability"
+    }
+
+    fn run<S: PlatformRebuildStrategy>(
+        &self,
+        op_ctx: &OperationContext,
+        _pb: &ProgressBar,
+        _platform_strategy: &S,
+        _platform_args: &S::PlatformArgs,
+    ) -> Result<CheckStatusReport> {
+        debug!("Running FlakeFetchabilityPreFlightCheck...");
+
+        let flake_dir = op_ctx.get_effective_project_root();
+        if !flake_dir.join("flake.nix").exists() {
+            debug!(
+                "No flake.nix at {}; skipping flake input fetchability check.",
+                flake_dir.display()
+            );
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        let output = crate::commands::Command::new("nix")
+            .args(["flake", "archive", "--dry-run"])
+            .arg(&flake_dir)
+            .add_verbosity_flags(op_ctx.verbose_count)
+            .run_capture_output()?;
+
+        if output.status.success() {
+            return Ok(CheckStatusReport::Passed);
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        error_handler::report_failure(
+            self.name(),
+            "One or more flake inputs could not be fetched",
+            Some(stderr),
+            vec![
+                "Check network connectivity and credentials for private inputs (e.g. an SSH \
+                key or an `access-tokens` entry in nix.conf), then retry."
+                    .to_string(),
+            ],
+        );
+        Ok(CheckStatusReport::FailedCritical)
+    }
+}
+
 // Helper to build a map of all available checks by their canonical names
 fn get_available_checks_map() -> HashMap<String, AnyPreFlightCheck> {
     let mut checks_map = HashMap::new();
@@ -640,9 +1923,107 @@ fn get_available_checks_map() -> HashMap<String, AnyPreFlightCheck> {
         AnyPreFlightCheck::ExternalLinters(external_linters_check),
     ); // ADDED
 
+    let homebrew_drift_check = HomebrewDriftPreFlightCheck;
+    checks_map.insert(
+        homebrew_drift_check.name().to_string(),
+        AnyPreFlightCheck::HomebrewDrift(homebrew_drift_check),
+    );
+
+    let sops_secrets_check = SopsSecretsPreFlightCheck;
+    checks_map.insert(
+        sops_secrets_check.name().to_string(),
+        AnyPreFlightCheck::SopsSecrets(sops_secrets_check),
+    );
+
+    let agenix_keys_check = AgenixKeysPreFlightCheck;
+    checks_map.insert(
+        agenix_keys_check.name().to_string(),
+        AnyPreFlightCheck::AgenixKeys(agenix_keys_check),
+    );
+
+    let flake_input_advisories_check = FlakeInputAdvisoriesPreFlightCheck;
+    checks_map.insert(
+        flake_input_advisories_check.name().to_string(),
+        AnyPreFlightCheck::FlakeInputAdvisories(flake_input_advisories_check),
+    );
+
+    let eval_check = EvalPreFlightCheck;
+    checks_map.insert(eval_check.name().to_string(), AnyPreFlightCheck::Eval(eval_check));
+
+    let assertions_check = AssertionsPreFlightCheck;
+    checks_map.insert(
+        assertions_check.name().to_string(),
+        AnyPreFlightCheck::Assertions(assertions_check),
+    );
+
+    let dry_run_build_check = DryRunBuildPreFlightCheck;
+    checks_map.insert(
+        dry_run_build_check.name().to_string(),
+        AnyPreFlightCheck::DryRunBuild(dry_run_build_check),
+    );
+
+    let nix_implementation_check = NixImplementationPreFlightCheck;
+    checks_map.insert(
+        nix_implementation_check.name().to_string(),
+        AnyPreFlightCheck::NixImplementation(nix_implementation_check),
+    );
+
+    let nix_config_sanity_check = NixConfigSanityPreFlightCheck;
+    checks_map.insert(
+        nix_config_sanity_check.name().to_string(),
+        AnyPreFlightCheck::NixConfigSanity(nix_config_sanity_check),
+    );
+
+    let disk_space_check = DiskSpacePreFlightCheck;
+    checks_map.insert(
+        disk_space_check.name().to_string(),
+        AnyPreFlightCheck::DiskSpace(disk_space_check),
+    );
+
+    let memory_availability_check = MemoryAvailabilityPreFlightCheck;
+    checks_map.insert(
+        memory_availability_check.name().to_string(),
+        AnyPreFlightCheck::MemoryAvailability(memory_availability_check),
+    );
+
+    let flake_fetchability_check = FlakeFetchabilityPreFlightCheck;
+    checks_map.insert(
+        flake_fetchability_check.name().to_string(),
+        AnyPreFlightCheck::FlakeFetchability(flake_fetchability_check),
+    );
+
     checks_map
 }
 
+/// Names of the checks that `ng.toml`'s `pre_flight.checks` selects, falling back to the
+/// built-in default selection when unset. Used both to drive
+/// [`run_shared_pre_flight_checks`] and to report `checks_run` in the `--json` operation
+/// summary (see [`crate::json::OperationSummary`]).
+pub fn configured_check_names(op_ctx: &OperationContext) -> Vec<String> {
+    let mut default_checks = vec![
+        NixConfigSanityPreFlightCheck.name().to_string(),
+        NixImplementationPreFlightCheck.name().to_string(),
+        NixParsePreFlightCheck.name().to_string(),
+        SemanticPreFlightCheck.name().to_string(),
+        NixFormatPreFlightCheck.name().to_string(),
+    ];
+    if op_ctx.common_args.medium_checks || op_ctx.common_args.full_checks {
+        default_checks.push(EvalPreFlightCheck.name().to_string());
+        default_checks.push(AssertionsPreFlightCheck.name().to_string());
+    }
+    if op_ctx.common_args.full_checks {
+        default_checks.push(DryRunBuildPreFlightCheck.name().to_string());
+        default_checks.push(DiskSpacePreFlightCheck.name().to_string());
+    }
+
+    op_ctx
+        .config
+        .pre_flight
+        .checks
+        .clone()
+        .unwrap_or(default_checks)
+}
+
 /// Runs the shared pre-flight checks based on ng.toml configuration.
 pub fn run_shared_pre_flight_checks<S: PlatformRebuildStrategy>(
     op_ctx: &OperationContext,
@@ -656,18 +2037,7 @@ pub fn run_shared_pre_flight_checks<S: PlatformRebuildStrategy>(
 
     let available_checks_map = get_available_checks_map();
 
-    let default_checks = vec![
-        NixParsePreFlightCheck.name().to_string(),
-        SemanticPreFlightCheck.name().to_string(),
-        NixFormatPreFlightCheck.name().to_string(),
-    ];
-
-    let checks_to_run_names: Vec<String> = op_ctx
-        .config
-        .pre_flight
-        .checks
-        .clone()
-        .unwrap_or(default_checks);
+    let checks_to_run_names: Vec<String> = configured_check_names(op_ctx);
 
     if checks_to_run_names.is_empty() {
         info!("[ℹ️ Pre-flight] No checks configured to run.");
@@ -687,7 +2057,7 @@ pub fn run_shared_pre_flight_checks<S: PlatformRebuildStrategy>(
             let pb =
                 progress::start_spinner(&format!("[Pre-flight] Running {} check", check.name()));
 
-            match check.run(op_ctx, platform_strategy, platform_args) {
+            match check.run(op_ctx, &pb, platform_strategy, platform_args) {
                 Ok(CheckStatusReport::Passed) => {
                     progress::finish_spinner_success(
                         &pb,
@@ -0,0 +1,76 @@
+//! After `nix flake update`, prefetches every changed input's source concurrently, so the
+//! subsequent eval/build doesn't stall sequentially on slow fetches.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::Value;
+use tracing::debug;
+
+use crate::commands::Command;
+use crate::progress::MultiSpinner;
+use crate::release_notes::locked_nodes;
+
+/// Constructs a flake reference nix can directly prefetch (e.g. `github:owner/repo/rev`) from a
+/// flake.lock `locked` object, for the source types `nix flake prefetch` understands.
+fn flake_ref_for_locked(locked: &Value) -> Option<String> {
+    let rev = locked["rev"].as_str()?;
+    match locked["type"].as_str()? {
+        "github" => Some(format!(
+            "github:{}/{}/{rev}",
+            locked["owner"].as_str()?,
+            locked["repo"].as_str()?
+        )),
+        "gitlab" => Some(format!(
+            "gitlab:{}/{}/{rev}",
+            locked["owner"].as_str()?,
+            locked["repo"].as_str()?
+        )),
+        "git" => Some(format!("git+{}?rev={rev}", locked["url"].as_str()?)),
+        _ => None,
+    }
+}
+
+/// Prefetches the source of every input whose locked revision changed between `revs_before` and
+/// the current `flake.lock` under `project_root`, each on its own progress line. Best-effort: a
+/// failed prefetch is logged at debug level and otherwise ignored, since the eval/build step that
+/// follows will surface the same fetch failure anyway.
+pub fn prefetch_updated_inputs(project_root: &Path, revs_before: &HashMap<String, String>) {
+    let locked_after = locked_nodes(&project_root.join("flake.lock"));
+    if locked_after.is_empty() {
+        return;
+    }
+
+    let refs: Vec<(String, String)> = locked_after
+        .iter()
+        .filter_map(|(name, locked)| {
+            let new_rev = locked["rev"].as_str()?;
+            if revs_before.get(name).map(String::as_str) == Some(new_rev) {
+                return None;
+            }
+            flake_ref_for_locked(locked).map(|flake_ref| (name.clone(), flake_ref))
+        })
+        .collect();
+
+    if refs.is_empty() {
+        return;
+    }
+
+    let multi = MultiSpinner::new();
+    std::thread::scope(|scope| {
+        for (name, flake_ref) in &refs {
+            let spinner = multi.add_spinner(&format!("Prefetching {name}"));
+            scope.spawn(move || {
+                match Command::new("nix").args(["flake", "prefetch", flake_ref]).run() {
+                    Ok(()) => {
+                        crate::progress::finish_spinner_success(&spinner, &format!("Prefetched {name}"));
+                    }
+                    Err(e) => {
+                        debug!("Failed to prefetch {}: {}", name, e);
+                        crate::progress::finish_spinner_fail(&spinner);
+                    }
+                }
+            });
+        }
+    });
+}
@@ -1,11 +1,58 @@
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use once_cell::sync::OnceCell;
 use std::time::Duration;
-use crate::util::is_stdout_tty;
+use crate::interface::ProgressMode;
+use crate::util::is_stderr_tty;
 use crate::ui_style::{Colors, Symbols, spinner_message, success_message};
 
+static PLAIN_MODE: OnceCell<bool> = OnceCell::new();
+static QUIET_MODE: OnceCell<bool> = OnceCell::new();
+
+/// Sets whether `-q`/`--quiet` was passed. Called once from `main` before any output happens.
+pub fn init_quiet_mode(quiet: bool) {
+    let _ = QUIET_MODE.set(quiet);
+}
+
+/// Whether `-q`/`--quiet` is in effect. Defaults to `false` if `init_quiet_mode` was never
+/// called, e.g. in unit tests.
+pub fn is_quiet() -> bool {
+    *QUIET_MODE.get_or_init(|| false)
+}
+
+/// Resolves and caches whether progress output should degrade to plain, timestamped log
+/// lines instead of interactive indicatif spinners. Called once from `main` with the
+/// `--progress` flag; `ProgressMode::Auto` auto-enables plain mode under `CI=true` or when
+/// stderr isn't a TTY (indicatif spinners render to stderr, and garble CI log viewers like
+/// GitHub Actions/Jenkins, or any run with stderr piped/redirected to a file).
+pub fn init_progress_mode(mode: ProgressMode) {
+    let plain = match mode {
+        ProgressMode::Plain => true,
+        ProgressMode::Fancy => false,
+        ProgressMode::Auto => std::env::var("CI").map(|v| v == "true").unwrap_or(false) || !is_stderr_tty(),
+    };
+    let _ = PLAIN_MODE.set(plain);
+}
+
+/// Whether progress output is in plain (non-interactive) mode. Defaults to auto-detection
+/// (CI env / non-TTY stderr) if `init_progress_mode` was never called, e.g. in unit tests.
+fn is_plain_mode() -> bool {
+    *PLAIN_MODE.get_or_init(|| std::env::var("CI").map(|v| v == "true").unwrap_or(false) || !is_stderr_tty())
+}
+
+fn plain_log(message: &str) {
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+    println!("[{timestamp}] {message}");
+}
+
+/// Whether an interactive (ticking, redrawing) progress bar should be rendered at all.
+fn interactive() -> bool {
+    is_stderr_tty() && !is_plain_mode()
+}
+
 /// Creates and returns a new spinner with the given message.
 ///
-/// If stdout is not a TTY, the spinner will be hidden but the message will still be printed.
+/// If interactive display is unavailable (non-TTY, or plain/CI mode), the spinner is
+/// hidden and the message is printed as a single line instead.
 ///
 /// # Arguments
 ///
@@ -15,7 +62,13 @@ use crate::ui_style::{Colors, Symbols, spinner_message, success_message};
 ///
 /// * `ProgressBar` - The created spinner.
 pub fn start_spinner(message: &str) -> ProgressBar {
-    let pb = if is_stdout_tty() {
+    if is_quiet() {
+        let pb = ProgressBar::hidden();
+        pb.set_message(message.to_string());
+        return pb;
+    }
+
+    let pb = if interactive() {
         let pb = ProgressBar::new_spinner();
         pb.set_style(
             ProgressStyle::default_spinner()
@@ -26,12 +79,15 @@ pub fn start_spinner(message: &str) -> ProgressBar {
         pb.enable_steady_tick(Duration::from_millis(80));
         pb
     } else {
-        // Create a hidden spinner if not in a TTY
         let pb = ProgressBar::hidden();
-        tracing::info!("{}", message);
+        if is_plain_mode() {
+            plain_log(message);
+        } else {
+            tracing::info!("{}", message);
+        }
         pb
     };
-    
+
     pb.set_message(message.to_string());
     pb
 }
@@ -43,8 +99,17 @@ pub fn start_spinner(message: &str) -> ProgressBar {
 /// * `spinner` - The spinner to update.
 /// * `message` - The new message to display.
 pub fn update_spinner_message(spinner: &ProgressBar, message: &str) {
-    if !is_stdout_tty() {
-        tracing::info!("{}", message);
+    if is_quiet() {
+        spinner.set_message(message.to_string());
+        return;
+    }
+
+    if !interactive() {
+        if is_plain_mode() {
+            plain_log(message);
+        } else {
+            tracing::info!("{}", message);
+        }
     }
     spinner.set_message(message.to_string());
 }
@@ -56,10 +121,18 @@ pub fn update_spinner_message(spinner: &ProgressBar, message: &str) {
 /// * `spinner` - The spinner to finish.
 /// * `message` - The success message to display.
 pub fn finish_spinner_success(spinner: &ProgressBar, message: &str) {
-    if is_stdout_tty() {
-        spinner.finish_with_message(format!("{} {}", Colors::success(Symbols::success()), message));
+    if is_quiet() {
+        spinner.finish_and_clear();
+        return;
+    }
+
+    let line = format!("{} {}", Colors::success(Symbols::success()), message);
+    if interactive() {
+        spinner.finish_with_message(line);
+    } else if is_plain_mode() {
+        plain_log(&line);
     } else {
-        tracing::info!("{} {}", Colors::success(Symbols::success()), message);
+        tracing::info!("{}", line);
     }
 }
 
@@ -69,10 +142,13 @@ pub fn finish_spinner_success(spinner: &ProgressBar, message: &str) {
 ///
 /// * `spinner` - The spinner to finish.
 pub fn finish_spinner_fail(spinner: &ProgressBar) {
-    if is_stdout_tty() {
-        spinner.finish_with_message(format!("{} Operation failed", Colors::error("✗")));
+    let line = format!("{} Operation failed", Colors::error("✗"));
+    if interactive() {
+        spinner.finish_with_message(line);
+    } else if is_plain_mode() {
+        plain_log(&line);
     } else {
-        tracing::error!("{} Operation failed", Colors::error("✗"));
+        tracing::error!("{}", line);
     }
 }
 
@@ -99,4 +175,75 @@ pub fn start_stage_spinner(stage: &str, action: &str) -> ProgressBar {
 /// * `message` - The success message.
 pub fn finish_stage_spinner_success(spinner: &ProgressBar, stage: &str, message: &str) {
     finish_spinner_success(spinner, &success_message(stage, message))
-}
\ No newline at end of file
+}
+
+/// A group of spinners that render on their own line, for tasks that run concurrently
+/// (parallel pre-flight checks, multi-host deploys, piped build/nom output).
+///
+/// In plain/non-TTY mode, member spinners are hidden and each update is logged as its own
+/// line instead, mirroring the fallback behaviour of [`start_spinner`].
+pub struct MultiSpinner {
+    multi: MultiProgress,
+}
+
+impl MultiSpinner {
+    /// Creates a new, empty multi-spinner group.
+    pub fn new() -> Self {
+        Self {
+            multi: MultiProgress::new(),
+        }
+    }
+
+    /// Adds a new spinner line to the group with the given message.
+    pub fn add_spinner(&self, message: &str) -> ProgressBar {
+        let pb = if interactive() {
+            let pb = self.multi.add(ProgressBar::new_spinner());
+            pb.set_style(
+                ProgressStyle::default_spinner()
+                    .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏")
+                    .template("{spinner:.cyan} {msg}")
+                    .unwrap(),
+            );
+            pb.enable_steady_tick(Duration::from_millis(80));
+            pb
+        } else {
+            if is_plain_mode() {
+                plain_log(message);
+            } else {
+                tracing::info!("{}", message);
+            }
+            ProgressBar::hidden()
+        };
+
+        pb.set_message(message.to_string());
+        pb
+    }
+}
+
+impl Default for MultiSpinner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Starts a determinate progress bar for a build, seeded with the number of derivations
+/// expected to be built/fetched (from [`crate::nix_interface::BuildPlanSummary`]).
+///
+/// Falls back to an indeterminate/hidden spinner when the total is unknown (e.g. the
+/// dry-run plan query failed) or interactive display is unavailable.
+pub fn start_build_progress(total: u64) -> ProgressBar {
+    if total == 0 || !interactive() {
+        return start_spinner(&spinner_message("Build", "Building configuration..."));
+    }
+
+    let pb = ProgressBar::new(total);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.cyan} [{bar:30.cyan/blue}] {pos}/{len} derivations ({eta}) {msg}")
+            .unwrap()
+            .progress_chars("=>-"),
+    );
+    pb.enable_steady_tick(Duration::from_millis(200));
+    pb.set_message(spinner_message("Build", "Building configuration..."));
+    pb
+}
@@ -0,0 +1,297 @@
+//! After a flake update, surfaces the parts of the nixpkgs/home-manager changelog that are
+//! actually relevant to this configuration, so a bumped input doesn't silently carry a breaking
+//! change for an option this configuration uses.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use regex::Regex;
+use serde_json::Value;
+use tracing::{debug, warn};
+
+/// Known flake inputs we know how to fetch release notes for, mapped to their GitHub repo.
+const KNOWN_INPUTS: &[(&str, &str)] = &[
+    ("nixpkgs", "NixOS/nixpkgs"),
+    ("home-manager", "nix-community/home-manager"),
+];
+
+/// Reads `<flake>/flake.lock` and returns each input's locked revision, keyed by input name.
+fn locked_revs(flake_lock: &Path) -> HashMap<String, String> {
+    let contents = match std::fs::read_to_string(flake_lock) {
+        Ok(c) => c,
+        Err(e) => {
+            debug!("Could not read {}: {}", flake_lock.display(), e);
+            return HashMap::new();
+        }
+    };
+
+    let parsed: Value = match serde_json::from_str(&contents) {
+        Ok(v) => v,
+        Err(e) => {
+            warn!("Failed to parse {}: {}", flake_lock.display(), e);
+            return HashMap::new();
+        }
+    };
+
+    let Some(nodes) = parsed["nodes"].as_object() else {
+        return HashMap::new();
+    };
+
+    nodes
+        .iter()
+        .filter_map(|(name, node)| {
+            let rev = node["locked"]["rev"].as_str()?;
+            Some((name.clone(), rev.to_string()))
+        })
+        .collect()
+}
+
+/// Scans every `*.nix` file under `project_root` for option paths under well-known top-level
+/// namespaces (e.g. `services.nginx.enable`), returning the second segment of each match (e.g.
+/// `nginx`) as a rough proxy for "modules this configuration actually uses".
+fn used_module_names(project_root: &Path) -> HashSet<String> {
+    let option_regex = Regex::new(
+        r"\b(?:services|programs|boot|networking|hardware|virtualisation|systemd)\.([a-zA-Z0-9_-]+)",
+    )
+    .expect("static regex is valid");
+
+    let mut names = HashSet::new();
+    for entry in walkdir::WalkDir::new(project_root)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "nix"))
+    {
+        let Ok(contents) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        for cap in option_regex.captures_iter(&contents) {
+            names.insert(cap[1].to_string());
+        }
+    }
+    names
+}
+
+/// Fetches commit messages between `old_rev` and `new_rev` for `repo` (e.g. `"NixOS/nixpkgs"`)
+/// from the GitHub compare API.
+fn compare_commit_messages(repo: &str, old_rev: &str, new_rev: &str) -> crate::Result<Vec<String>> {
+    let url = format!("https://api.github.com/repos/{repo}/compare/{old_rev}...{new_rev}");
+
+    let response = reqwest::blocking::Client::new()
+        .get(&url)
+        .header("User-Agent", "ng-release-notes")
+        .send()
+        .map_err(|e| color_eyre::eyre::eyre!("Failed to reach GitHub compare API: {e}"))?
+        .error_for_status()
+        .map_err(|e| color_eyre::eyre::eyre!("GitHub compare API returned an error: {e}"))?;
+
+    let body: Value = response
+        .json()
+        .map_err(|e| color_eyre::eyre::eyre!("Failed to parse GitHub compare response: {e}"))?;
+
+    let commits = body["commits"].as_array().cloned().unwrap_or_default();
+    Ok(commits
+        .iter()
+        .filter_map(|c| c["commit"]["message"].as_str())
+        .map(|msg| msg.lines().next().unwrap_or(msg).to_string())
+        .collect())
+}
+
+/// Compares `flake.lock` under `project_root` before and after an update, and for any known
+/// input (nixpkgs, home-manager) whose revision changed, prints the subset of its commit log
+/// that mentions a module this configuration actually uses. Best-effort: network or parsing
+/// failures are logged as warnings rather than failing the update.
+pub fn show_relevant_release_notes(project_root: &Path, revs_before: &HashMap<String, String>) {
+    let flake_lock = project_root.join("flake.lock");
+    let revs_after = locked_revs(&flake_lock);
+    if revs_after.is_empty() {
+        return;
+    }
+
+    let used = used_module_names(project_root);
+
+    for (input, repo) in KNOWN_INPUTS {
+        let (Some(old_rev), Some(new_rev)) = (revs_before.get(*input), revs_after.get(*input))
+        else {
+            continue;
+        };
+        if old_rev == new_rev {
+            continue;
+        }
+
+        debug!("{} changed: {} -> {}", input, old_rev, new_rev);
+        let messages = match compare_commit_messages(repo, old_rev, new_rev) {
+            Ok(messages) => messages,
+            Err(e) => {
+                warn!("Could not fetch release notes for {}: {}", input, e);
+                continue;
+            }
+        };
+
+        let relevant: Vec<&String> = messages
+            .iter()
+            .filter(|msg| {
+                let lower = msg.to_lowercase();
+                used.iter().any(|name| lower.contains(&name.to_lowercase()))
+            })
+            .collect();
+
+        if relevant.is_empty() {
+            continue;
+        }
+
+        println!(
+            "\n{}",
+            crate::ui_style::Colors::info(format!("Relevant {} changes ({} -> {}):", input, &old_rev[..7.min(old_rev.len())], &new_rev[..7.min(new_rev.len())]))
+        );
+        for msg in relevant {
+            println!("  - {}", msg);
+        }
+    }
+}
+
+/// Captures every input's locked revision (not only the known ones) before running an update,
+/// to be passed to [`show_relevant_release_notes`] and [`show_compare_links`] afterwards.
+pub fn capture_revs_before(project_root: &Path) -> HashMap<String, String> {
+    locked_revs(&project_root.join("flake.lock"))
+}
+
+/// Reads `<flake>/flake.lock` and returns each input's `locked` object, keyed by input name.
+pub(crate) fn locked_nodes(flake_lock: &Path) -> HashMap<String, Value> {
+    let contents = match std::fs::read_to_string(flake_lock) {
+        Ok(c) => c,
+        Err(e) => {
+            debug!("Could not read {}: {}", flake_lock.display(), e);
+            return HashMap::new();
+        }
+    };
+
+    let parsed: Value = match serde_json::from_str(&contents) {
+        Ok(v) => v,
+        Err(e) => {
+            warn!("Failed to parse {}: {}", flake_lock.display(), e);
+            return HashMap::new();
+        }
+    };
+
+    let Some(nodes) = parsed["nodes"].as_object() else {
+        return HashMap::new();
+    };
+
+    nodes
+        .iter()
+        .filter_map(|(name, node)| Some((name.clone(), node["locked"].clone())))
+        .collect()
+}
+
+/// A flake input pinned to a GitHub- or GitLab-hosted repo, as identified by its `locked.type`.
+enum HostedRepo {
+    GitHub { host: String, owner: String, repo: String },
+    GitLab { host: String, owner: String, repo: String },
+}
+
+impl HostedRepo {
+    fn from_locked(locked: &Value) -> Option<Self> {
+        let owner = locked["owner"].as_str()?.to_string();
+        let repo = locked["repo"].as_str()?.to_string();
+        match locked["type"].as_str()? {
+            "github" => Some(Self::GitHub {
+                host: locked["host"].as_str().unwrap_or("github.com").to_string(),
+                owner,
+                repo,
+            }),
+            "gitlab" => Some(Self::GitLab {
+                host: locked["host"].as_str().unwrap_or("gitlab.com").to_string(),
+                owner,
+                repo,
+            }),
+            _ => None,
+        }
+    }
+
+    fn compare_url(&self, old_rev: &str, new_rev: &str) -> String {
+        match self {
+            Self::GitHub { host, owner, repo } => {
+                format!("https://{host}/{owner}/{repo}/compare/{old_rev}...{new_rev}")
+            }
+            Self::GitLab { host, owner, repo } => {
+                format!("https://{host}/{owner}/{repo}/-/compare/{old_rev}...{new_rev}")
+            }
+        }
+    }
+
+    /// Best-effort commit count between `old_rev` and `new_rev`, via each host's compare API.
+    fn commit_count(&self, old_rev: &str, new_rev: &str) -> crate::Result<usize> {
+        let url = match self {
+            Self::GitHub { host, owner, repo } => {
+                let api_host = if host == "github.com" {
+                    "api.github.com".to_string()
+                } else {
+                    format!("{host}/api/v3")
+                };
+                format!("https://{api_host}/repos/{owner}/{repo}/compare/{old_rev}...{new_rev}")
+            }
+            Self::GitLab { host, owner, repo } => {
+                // GitLab's API takes the project path percent-encoded as a single path segment.
+                format!(
+                    "https://{host}/api/v4/projects/{owner}%2F{repo}/repository/compare?from={old_rev}&to={new_rev}"
+                )
+            }
+        };
+
+        let body: Value = reqwest::blocking::Client::new()
+            .get(&url)
+            .header("User-Agent", "ng-release-notes")
+            .send()
+            .map_err(|e| color_eyre::eyre::eyre!("Failed to reach compare API: {e}"))?
+            .error_for_status()
+            .map_err(|e| color_eyre::eyre::eyre!("Compare API returned an error: {e}"))?
+            .json()
+            .map_err(|e| color_eyre::eyre::eyre!("Failed to parse compare response: {e}"))?;
+
+        Ok(body["commits"].as_array().map(Vec::len).unwrap_or(0))
+    }
+}
+
+/// Compares `flake.lock` under `project_root` before and after an update, and for every
+/// GitHub/GitLab-hosted input whose revision changed, prints a compare URL and the number of
+/// commits between the old and new revisions, so a reviewer can inspect what actually changed.
+/// Best-effort: network failures are logged as debug output rather than failing the update.
+pub fn show_compare_links(project_root: &Path, revs_before: &HashMap<String, String>) {
+    let locked_after = locked_nodes(&project_root.join("flake.lock"));
+    if locked_after.is_empty() {
+        return;
+    }
+
+    for (name, locked) in &locked_after {
+        let Some(new_rev) = locked["rev"].as_str() else {
+            continue;
+        };
+        let Some(old_rev) = revs_before.get(name) else {
+            continue;
+        };
+        if old_rev == new_rev {
+            continue;
+        }
+        let Some(hosted) = HostedRepo::from_locked(locked) else {
+            continue;
+        };
+
+        let compare_url = hosted.compare_url(old_rev, new_rev);
+        match hosted.commit_count(old_rev, new_rev) {
+            Ok(count) => println!(
+                "\n{}",
+                crate::ui_style::Colors::info(format!(
+                    "{name}: {count} commit{} — {compare_url}",
+                    if count == 1 { "" } else { "s" }
+                ))
+            ),
+            Err(e) => {
+                debug!("Could not fetch commit count for {}: {}", name, e);
+                println!(
+                    "\n{}",
+                    crate::ui_style::Colors::info(format!("{name}: {compare_url}"))
+                );
+            }
+        }
+    }
+}
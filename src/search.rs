@@ -35,6 +35,7 @@ struct SearchResult {
     package_system: String,
     package_homepage: Vec<String>,
     package_position: Option<String>,
+    package_broken: Option<bool>,
 }
 
 macro_rules! print_hyperlink {
@@ -68,11 +69,34 @@ impl SearchArgs {
                 .output()
         });
 
-        let query_s = self.query.join(" ");
+        if self.program.is_some() && !self.query.is_empty() {
+            bail!("--program cannot be combined with a search query");
+        }
+
+        let query_s = self.program.clone().unwrap_or_else(|| self.query.join(" "));
         debug!(?query_s);
 
+        let mut bool_query = Query::bool().filter(Query::term("type", "package"));
+        if let Some(program) = &self.program {
+            bool_query = bool_query.filter(
+                Query::wildcard("package_programs", format!("*{program}*")).case_insensitive(true),
+            );
+        }
+        if let Some(license) = &self.license {
+            bool_query = bool_query.filter(
+                Query::wildcard("package_license_set", format!("*{license}*"))
+                    .case_insensitive(true),
+            );
+        }
+        if let Some(platform) = &self.platform {
+            bool_query = bool_query.filter(Query::term("package_platforms", platform.as_str()));
+        }
+        if let Some(broken) = self.broken {
+            bool_query = bool_query.filter(Query::term("package_broken", broken));
+        }
+
         let query = Search::new().from(0).size(self.limit).query(
-            Query::bool().filter(Query::term("type", "package")).must(
+            bool_query.must(
                 Query::dis_max()
                     .tie_breaker(0.7)
                     .query(
@@ -149,10 +173,15 @@ impl SearchArgs {
             .context("parsing response into the elasticsearch format")?;
         trace!(?parsed_response);
 
-        let documents = parsed_response
+        let mut documents = parsed_response
             .documents::<SearchResult>()
             .context("parsing search document")?;
 
+        // Elasticsearch's relevance score already favors exact matches, but not reliably enough
+        // to always put e.g. `hello` ahead of `hello-unfree` — bump exact attr/pname/program matches to
+        // the front so they're the first thing a user sees (results are otherwise most-relevant-last).
+        documents.sort_by_key(|doc| !is_exact_match(doc, &query_s));
+
         if self.json {
             // Output as JSON
             let json_output = JSONOutput {
@@ -211,6 +240,14 @@ impl SearchArgs {
                 println!("  Platforms: {}", elem.package_platforms.join(", "));
             }
 
+            if self.program.is_some() && !elem.package_programs.is_empty() {
+                println!("  Provides: {}", elem.package_programs.join(", "));
+            }
+
+            if let Some(snippet) = self.snippet {
+                println!("  Snippet: {}", format_snippet(snippet, &elem.package_attr_name));
+            }
+
             if let Some(position) = &elem.package_position {
                 let position = position.split(':').next().unwrap();
                 print!("  Defined at: ");
@@ -234,6 +271,33 @@ impl SearchArgs {
     }
 }
 
+/// Whether `doc`'s attribute name (ignoring any `pkgs.`-style prefix), package name, or one of
+/// its provided programs matches `query` exactly, case-insensitively.
+fn is_exact_match(doc: &SearchResult, query: &str) -> bool {
+    let last_component = doc
+        .package_attr_name
+        .rsplit('.')
+        .next()
+        .unwrap_or(&doc.package_attr_name);
+    last_component.eq_ignore_ascii_case(query)
+        || doc.package_pname.eq_ignore_ascii_case(query)
+        || doc
+            .package_programs
+            .iter()
+            .any(|program| program.eq_ignore_ascii_case(query))
+}
+
+/// Renders the line a user would paste into their config to install `attr_name`.
+fn format_snippet(kind: interface::SearchSnippet, attr_name: &str) -> String {
+    match kind {
+        interface::SearchSnippet::Nixos => {
+            format!("environment.systemPackages = [ pkgs.{attr_name} ];")
+        }
+        interface::SearchSnippet::Home => format!("home.packages = [ pkgs.{attr_name} ];"),
+        interface::SearchSnippet::Shell => format!("nix shell nixpkgs#{attr_name}"),
+    }
+}
+
 fn supported_branch<S: AsRef<str>>(branch: S) -> bool {
     let branch = branch.as_ref();
 
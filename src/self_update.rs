@@ -0,0 +1,112 @@
+//! `ng self-update`: detects how the running `ng` binary was installed and either reports or
+//! performs the appropriate update.
+
+use color_eyre::eyre::eyre;
+use tracing::{info, warn};
+
+use crate::commands::Command;
+use crate::interface::SelfUpdateArgs;
+use crate::Result;
+
+const REPO: &str = "viperML/ng";
+
+#[derive(Debug, PartialEq, Eq)]
+enum InstallMethod {
+    /// Linked into a mutable `nix profile` (e.g. `~/.nix-profile` or a system profile)
+    NixProfile,
+    /// Running out of the Nix store without a mutable profile link, e.g. via a flake input
+    /// pinned elsewhere, or `nix run`
+    Flake,
+    /// A local `cargo build`/`cargo run`
+    Cargo,
+    Unknown,
+}
+
+fn detect_install_method() -> InstallMethod {
+    let exe = match std::env::current_exe() {
+        Ok(path) => path,
+        Err(e) => {
+            warn!("Failed to determine the current executable's path: {}", e);
+            return InstallMethod::Unknown;
+        }
+    };
+    let exe = exe.to_string_lossy();
+
+    if exe.contains("/target/debug/") || exe.contains("/target/release/") {
+        InstallMethod::Cargo
+    } else if exe.contains("/.nix-profile/") || exe.contains("/nix/var/nix/profiles/") {
+        InstallMethod::NixProfile
+    } else if exe.contains("/nix/store/") {
+        InstallMethod::Flake
+    } else {
+        InstallMethod::Unknown
+    }
+}
+
+/// Queries the GitHub releases API for the latest tagged `ng` release.
+fn latest_release_version() -> Result<String> {
+    let url = format!("https://api.github.com/repos/{REPO}/releases/latest");
+
+    let response = reqwest::blocking::Client::new()
+        .get(&url)
+        .header("User-Agent", "ng-self-update")
+        .send()
+        .map_err(|e| eyre!("Failed to reach the GitHub releases API: {}", e))?
+        .error_for_status()
+        .map_err(|e| eyre!("GitHub releases API returned an error: {}", e))?;
+
+    let body: serde_json::Value = response
+        .json()
+        .map_err(|e| eyre!("Failed to parse the GitHub releases response: {}", e))?;
+
+    body["tag_name"]
+        .as_str()
+        .map(|tag| tag.trim_start_matches('v').to_string())
+        .ok_or_else(|| eyre!("GitHub releases response had no 'tag_name' field"))
+}
+
+impl SelfUpdateArgs {
+    pub fn run(self, _verbose_count: u8) -> Result<()> {
+        let current = crate::NG_VERSION;
+        let latest = latest_release_version()?;
+
+        if latest == current {
+            info!("ng {} is already up to date.", current);
+            return Ok(());
+        }
+
+        info!("A newer ng release is available: {} -> {}", current, latest);
+
+        if self.check {
+            return Ok(());
+        }
+
+        match detect_install_method() {
+            InstallMethod::NixProfile => {
+                info!("Detected a `nix profile` install, running `nix profile upgrade ng`...");
+                Command::new("nix").args(["profile", "upgrade", "ng"]).run()?;
+            }
+            InstallMethod::Flake => {
+                warn!(
+                    "ng is running from the Nix store without a mutable profile link (likely a \
+                     flake input or `nix run`); self-update can't modify it in place. Bump the \
+                     `ng` input instead: `nix flake lock --update-input ng`."
+                );
+            }
+            InstallMethod::Cargo => {
+                warn!(
+                    "ng appears to be a local cargo build; self-update won't touch it. Pull the \
+                     latest source and run `cargo install --path . --force`."
+                );
+            }
+            InstallMethod::Unknown => {
+                warn!(
+                    "Could not detect how ng was installed; update it manually (latest: {}).",
+                    latest
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+//! Specialisation discovery and selection for NixOS activation.
+//!
+//! A built NixOS toplevel exposes each `specialisation.<name>` as
+//! `<toplevel>/specialisation/<name>`, itself a full system closure. This module lists what's
+//! available in a given build and resolves `--specialisation` (including interactive selection)
+//! against it, so activation never blindly joins a path that doesn't exist.
+
+use std::fs;
+use std::path::Path;
+
+use color_eyre::eyre::bail;
+use tracing::warn;
+
+use crate::Result;
+
+/// Lists the specialisation names available in a built toplevel (i.e. the entries under
+/// `<built_profile_path>/specialisation/`).
+pub fn list_specialisations(built_profile_path: &Path) -> Vec<String> {
+    let dir = built_profile_path.join("specialisation");
+    match fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Resolves which specialisation (if any) to activate.
+///
+/// * `no_specialisation` always wins, returning the base (unspecialised) system.
+/// * `requested = Some("")` (i.e. `--specialisation` passed with no value) prompts the user to
+///   interactively pick from the ones available in `built_profile_path`.
+/// * `requested = Some(name)` is validated against the built configuration; an unknown name is
+///   an error rather than a silently-broken activation path.
+/// * `requested = None` activates the base system, matching the pre-existing default.
+pub fn resolve_specialisation(
+    built_profile_path: &Path,
+    requested: Option<&str>,
+    no_specialisation: bool,
+) -> Result<Option<String>> {
+    if no_specialisation {
+        return Ok(None);
+    }
+
+    let Some(requested) = requested else {
+        return Ok(None);
+    };
+
+    let available = list_specialisations(built_profile_path);
+
+    if requested.is_empty() {
+        if available.is_empty() {
+            warn!("No specialisations available in this configuration; using the base system.");
+            return Ok(None);
+        }
+        let selection = dialoguer::Select::new()
+            .with_prompt("Select a specialisation")
+            .items(&available)
+            .default(0)
+            .interact()?;
+        return Ok(Some(available[selection].clone()));
+    }
+
+    if available.iter().any(|s| s == requested) {
+        Ok(Some(requested.to_string()))
+    } else if available.is_empty() {
+        bail!(
+            "Specialisation '{}' was requested, but this configuration has no specialisations.",
+            requested
+        );
+    } else {
+        bail!(
+            "Specialisation '{}' not found in the built configuration. Available: {}",
+            requested,
+            available.join(", ")
+        );
+    }
+}
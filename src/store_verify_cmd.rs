@@ -0,0 +1,86 @@
+//! `ng store verify`: wraps `nix store verify`, reporting corrupted/untrusted paths in a table
+//! and routing the failure through [`error_handler::report_failure`] with a `nix store repair`
+//! recommendation per bad path, instead of leaving raw verify output to parse by hand.
+
+use color_eyre::eyre::bail;
+use regex::Regex;
+use tracing::warn;
+
+use crate::commands::Command;
+use crate::error_handler;
+use crate::interface::StoreVerifyArgs;
+use crate::Result;
+
+/// A single store path flagged by `nix store verify`, with a short human description of why.
+#[derive(Debug, Clone)]
+pub struct VerifyIssue {
+    pub path: String,
+    pub problem: String,
+}
+
+impl StoreVerifyArgs {
+    pub fn run(&self, verbose_count: u8) -> Result<()> {
+        let mut cmd = Command::new("nix").args(["store", "verify"]);
+        cmd = if self.paths.is_empty() {
+            cmd.arg("--all")
+        } else {
+            cmd.args(self.paths.iter().map(|p| p.to_string_lossy().into_owned()))
+        };
+
+        let pb = crate::progress::start_spinner("Verifying nix store paths...");
+        let output = cmd.add_verbosity_flags(verbose_count).run_capture_output()?;
+
+        if output.status.success() {
+            crate::progress::finish_spinner_success(&pb, "All paths verified successfully.");
+            return Ok(());
+        }
+        crate::progress::finish_spinner_fail(&pb);
+
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        let issues = parse_verify_issues(&stderr);
+
+        if !issues.is_empty() {
+            if let Err(e) = crate::tables::display_store_verify_issues(&issues) {
+                warn!("Failed to render store verify results table: {}", e);
+            }
+
+            let recommendations = issues
+                .iter()
+                .map(|issue| format!("nix store repair {}", issue.path))
+                .collect();
+            error_handler::report_failure(
+                "Store Verify",
+                &format!("{} store path(s) failed verification", issues.len()),
+                None,
+                recommendations,
+            );
+        }
+
+        bail!("`nix store verify` reported problems with the store");
+    }
+}
+
+/// Best-effort parse of `nix store verify`'s per-path error lines, e.g. `path '/nix/store/...'
+/// was modified!` (corrupted) or `path '/nix/store/...' is untrusted`. Falls back to reporting
+/// no specific issues (just the overall failure) if nix's wording has changed and nothing matches.
+fn parse_verify_issues(stderr: &str) -> Vec<VerifyIssue> {
+    let modified_re = Regex::new(r"path '([^']+)' was modified").unwrap();
+    let untrusted_re =
+        Regex::new(r"path '([^']+)'.*(?:untrusted|not signed by a trusted key)").unwrap();
+
+    let mut issues = Vec::new();
+    for line in stderr.lines() {
+        if let Some(caps) = modified_re.captures(line) {
+            issues.push(VerifyIssue {
+                path: caps[1].to_string(),
+                problem: "corrupted (hash mismatch)".to_string(),
+            });
+        } else if let Some(caps) = untrusted_re.captures(line) {
+            issues.push(VerifyIssue {
+                path: caps[1].to_string(),
+                problem: "untrusted signature".to_string(),
+            });
+        }
+    }
+    issues
+}
@@ -1,8 +1,165 @@
 //! Module for displaying structured data in tables
 
+use clap::ValueEnum;
 use cli_table::{print_stdout, Cell, Table, Style};
 use cli_table::Color;
 
+/// Output format shared by any `--format`-taking command that lists tabular data.
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Csv,
+    Json,
+}
+
+/// A generic tabular result: column headers plus plain-string cell rows, renderable as a
+/// colored terminal table, CSV, or JSON depending on [`OutputFormat`]. Domain-specific
+/// `display_*` functions below build one of these instead of hand-rolling CSV/JSON export
+/// per command.
+pub struct ReportTable {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+    wide: bool,
+}
+
+impl ReportTable {
+    pub fn new(headers: Vec<&'static str>, rows: Vec<Vec<String>>) -> Self {
+        Self { headers, rows, wide: false }
+    }
+
+    /// Skip width-aware truncation and print every column at its natural width, e.g. when the
+    /// user passes `--wide`.
+    pub fn wide(mut self, wide: bool) -> Self {
+        self.wide = wide;
+        self
+    }
+
+    pub fn render(&self, format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+        match format {
+            OutputFormat::Table => self.render_table(),
+            OutputFormat::Csv => self.render_csv(),
+            OutputFormat::Json => self.render_json(),
+        }
+    }
+
+    fn render_table(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let rows: Vec<Vec<String>> = if self.wide {
+            self.rows.clone()
+        } else {
+            fit_to_terminal_width(&self.headers, &self.rows)
+        };
+
+        let table: Vec<Vec<_>> = rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.as_str().cell()).collect())
+            .collect();
+
+        let table = table
+            .table()
+            .title(self.headers.iter().map(|h| h.cell().bold(true)).collect::<Vec<_>>())
+            .bold(true);
+
+        print_stdout(table)?;
+        Ok(())
+    }
+
+    /// Minimal RFC 4180 quoting: a field is quoted if it contains a comma, quote, or newline,
+    /// with embedded quotes doubled.
+    fn render_csv(&self) -> Result<(), Box<dyn std::error::Error>> {
+        fn csv_field(field: &str) -> String {
+            if field.contains([',', '"', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        }
+
+        println!("{}", self.headers.iter().copied().map(csv_field).collect::<Vec<_>>().join(","));
+        for row in &self.rows {
+            println!("{}", row.iter().map(|cell| csv_field(cell)).collect::<Vec<_>>().join(","));
+        }
+        Ok(())
+    }
+
+    fn render_json(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let objects: Vec<serde_json::Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let mut obj = serde_json::Map::new();
+                for (header, cell) in self.headers.iter().zip(row.iter()) {
+                    obj.insert((*header).to_string(), serde_json::Value::String(cell.clone()));
+                }
+                serde_json::Value::Object(obj)
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&objects)?);
+        Ok(())
+    }
+}
+
+/// Shrinks the widest column(s) of a table so it fits the terminal width, leaving narrow
+/// columns (numbers, dates, short flags) untouched. Falls back to the natural widths if the
+/// table already fits, or if the terminal width can't be determined (e.g. output is piped).
+fn fit_to_terminal_width(headers: &[&str], rows: &[Vec<String>]) -> Vec<Vec<String>> {
+    // `termwidth()` reports 80 columns rather than 0 when stdout isn't a terminal.
+    if !crate::util::is_stdout_tty() {
+        return rows.to_vec();
+    }
+    let available = textwrap::termwidth();
+    if available == 0 {
+        return rows.to_vec();
+    }
+
+    let n = headers.len();
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (w, cell) in widths.iter_mut().zip(row.iter()) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    // cli_table draws a border around the table plus a " x " padding between columns, roughly
+    // 3 extra characters per column plus one for the outer border.
+    let overhead = n * 3 + 1;
+    let natural_total = widths.iter().sum::<usize>() + overhead;
+    if natural_total <= available {
+        return rows.to_vec();
+    }
+
+    const MIN_COLUMN_WIDTH: usize = 8;
+    let mut remaining = natural_total - available;
+    let mut widest_first: Vec<usize> = (0..n).collect();
+    widest_first.sort_by_key(|&i| std::cmp::Reverse(widths[i]));
+
+    for i in widest_first {
+        if remaining == 0 {
+            break;
+        }
+        let shrinkable = widths[i].saturating_sub(MIN_COLUMN_WIDTH);
+        let take = shrinkable.min(remaining);
+        widths[i] -= take;
+        remaining -= take;
+    }
+
+    rows.iter()
+        .map(|row| row.iter().zip(widths.iter()).map(|(cell, &w)| truncate_cell(cell, w)).collect())
+        .collect()
+}
+
+fn truncate_cell(cell: &str, max_width: usize) -> String {
+    if cell.chars().count() <= max_width {
+        return cell.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+    let mut truncated: String = cell.chars().take(max_width.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
 /// Display a table with lint results
 pub fn display_lint_results(
     results: Vec<(String, String, String)>, // (Linter, Status, Details)
@@ -97,6 +254,292 @@ pub fn display_package_diff(
     Ok(())
 }
 
+/// Display a summary table of derivations that failed to build under `--keep-going`
+pub fn display_failed_derivations(
+    failures: Vec<crate::nix_interface::FailedDerivation>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut table = vec![];
+
+    for failure in failures {
+        table.push(vec![
+            failure.drv_path.cell().foreground_color(Some(Color::Red)),
+            failure.error.cell(),
+        ]);
+    }
+
+    let table = table.table()
+        .title(vec![
+            "Failed Derivation".cell().bold(true),
+            "Error".cell().bold(true),
+        ])
+        .bold(true);
+
+    print_stdout(table)?;
+    Ok(())
+}
+
+/// Display a pass/fail table for `ng check`, one row per `checks.<system>` attribute.
+pub fn display_check_results(
+    results: Vec<(String, bool)>, // (attribute, passed)
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut table = vec![];
+
+    for (name, passed) in results {
+        let status_cell = if passed {
+            "Passed".cell().foreground_color(Some(Color::Green))
+        } else {
+            "Failed".cell().foreground_color(Some(Color::Red))
+        };
+        table.push(vec![name.cell(), status_cell]);
+    }
+
+    let table = table.table()
+        .title(vec![
+            "Check".cell().bold(true),
+            "Status".cell().bold(true),
+        ])
+        .bold(true);
+
+    print_stdout(table)?;
+    Ok(())
+}
+
+/// Display a summary of a completed build: wall time, derivations built vs substituted,
+/// bytes downloaded, and resulting closure size.
+pub fn display_build_stats(stats: &crate::nix_interface::BuildStats) {
+    let mut table = vec![
+        vec!["Wall time".cell(), format!("{:.1}s", stats.wall_time.as_secs_f64()).cell()],
+        vec!["Derivations built".cell(), stats.derivations_built.to_string().cell()],
+        vec!["Derivations substituted".cell(), stats.derivations_substituted.to_string().cell()],
+    ];
+
+    if let Some(bytes) = stats.bytes_downloaded {
+        table.push(vec!["Downloaded".cell(), format_bytes(bytes).cell()]);
+    }
+    if let Some(bytes) = stats.closure_size {
+        table.push(vec!["Closure size".cell(), format_bytes(bytes).cell()]);
+    }
+
+    let table = table.table()
+        .title(vec![
+            "Build Summary".cell().bold(true),
+            "".cell().bold(true),
+        ])
+        .bold(true);
+
+    if let Err(e) = print_stdout(table) {
+        tracing::warn!("Failed to render build statistics summary: {}", e);
+    }
+}
+
+/// Display a rich system status summary: current generation, versions, active specialisation,
+/// the running system's configuration revision vs the repo's HEAD, and uptime.
+pub fn display_system_status(status: &crate::generations::SystemStatus) {
+    let revision_matches = status.running_revision == status.repo_head_revision
+        && !status.running_revision.is_empty();
+    let revision_cell = if status.running_revision.is_empty() {
+        "Unknown".cell()
+    } else if revision_matches {
+        status.running_revision.clone().cell().foreground_color(Some(Color::Green))
+    } else {
+        format!("{} (repo HEAD: {})", status.running_revision, status.repo_head_revision)
+            .cell()
+            .foreground_color(Some(Color::Yellow))
+    };
+
+    let table = vec![
+        vec!["Generation".cell(), status.generation_number.clone().cell()],
+        vec!["NixOS Version".cell(), status.nixos_version.clone().cell()],
+        vec!["Kernel".cell(), status.kernel_version.clone().cell()],
+        vec!["Active Specialisation".cell(), status.active_specialisation.clone().cell()],
+        vec!["Configuration Revision".cell(), revision_cell],
+        vec!["Uptime".cell(), status.uptime.clone().cell()],
+    ];
+
+    let table = table.table()
+        .title(vec![
+            "System Status".cell().bold(true),
+            "".cell().bold(true),
+        ])
+        .bold(true);
+
+    if let Err(e) = print_stdout(table) {
+        tracing::warn!("Failed to render system status table: {}", e);
+    }
+}
+
+/// Display a table of NixOS generations, most recent first as ordered by the caller (see
+/// `generations::sort_and_filter`), in the given `--format` (table/csv/json).
+pub fn display_generations_as(generations: &[crate::generations::GenerationInfo], format: OutputFormat, wide: bool) {
+    let rows = generations
+        .iter()
+        .map(|generation| {
+            let number = if generation.current {
+                format!("{} (current)", generation.number)
+            } else {
+                generation.number.clone()
+            };
+            vec![
+                number,
+                generation.date.clone(),
+                generation.nixos_version.clone(),
+                generation.kernel_version.clone(),
+                generation.configuration_revision.clone(),
+                generation.specialisations.join(", "),
+            ]
+        })
+        .collect();
+
+    let table = ReportTable::new(
+        vec![
+            "Generation",
+            "Build Date",
+            "NixOS Version",
+            "Kernel",
+            "Configuration Revision",
+            "Specialisations",
+        ],
+        rows,
+    )
+    .wide(wide);
+
+    if let Err(e) = table.render(format) {
+        tracing::warn!("Failed to render generations table: {}", e);
+    }
+}
+
+/// Display a table of NixOS generations alongside each one's closure size and the delta from
+/// the previous row, so a growing system stands out at a glance. `sizes` is aligned 1:1 with
+/// `generations`; a `None` entry (size lookup failed) is rendered as "?" with no delta.
+pub fn display_generation_sizes(
+    generations: &[crate::generations::GenerationInfo],
+    sizes: &[Option<u64>],
+) {
+    let mut table = vec![];
+    let mut previous: Option<u64> = None;
+
+    for (generation, size) in generations.iter().zip(sizes) {
+        let number_cell = if generation.current {
+            format!("{} (current)", generation.number)
+                .cell()
+                .foreground_color(Some(Color::Green))
+        } else {
+            generation.number.clone().cell()
+        };
+
+        let size_cell = match size {
+            Some(bytes) => format_bytes(*bytes).cell(),
+            None => "?".cell(),
+        };
+
+        let delta_cell = match (size, previous) {
+            (Some(bytes), Some(prev)) if *bytes >= prev => {
+                format!("+{}", format_bytes(bytes - prev)).cell().foreground_color(Some(Color::Yellow))
+            }
+            (Some(bytes), Some(prev)) => {
+                format!("-{}", format_bytes(prev - bytes)).cell().foreground_color(Some(Color::Green))
+            }
+            _ => "".cell(),
+        };
+
+        table.push(vec![number_cell, generation.date.clone().cell(), size_cell, delta_cell]);
+
+        if let Some(bytes) = size {
+            previous = Some(*bytes);
+        }
+    }
+
+    let table = table.table()
+        .title(vec![
+            "Generation".cell().bold(true),
+            "Build Date".cell().bold(true),
+            "Closure Size".cell().bold(true),
+            "Delta".cell().bold(true),
+        ])
+        .bold(true);
+
+    if let Err(e) = print_stdout(table) {
+        tracing::warn!("Failed to render generation sizes table: {}", e);
+    }
+}
+
+/// Display a table of store paths flagged by `nix store verify`, alongside why each one failed.
+pub fn display_store_verify_issues(
+    issues: &[crate::store_verify_cmd::VerifyIssue],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let table: Vec<Vec<_>> = issues
+        .iter()
+        .map(|issue| vec![issue.path.clone().cell(), issue.problem.clone().cell()])
+        .collect();
+
+    let table = table.table()
+        .title(vec![
+            "Store Path".cell().bold(true),
+            "Problem".cell().bold(true),
+        ])
+        .bold(true);
+
+    print_stdout(table)?;
+    Ok(())
+}
+
+/// Display a sorted disk-usage breakdown of a closure: each store path with its own NAR size,
+/// largest first, plus a totals row. `top` limits how many paths are printed, with the rest
+/// folded into an "and N more" summary line so the table stays readable on large closures.
+pub fn display_disk_usage(paths: &[crate::nix_interface::StorePathInfo], top: usize) {
+    let total: u64 = paths.iter().map(|p| p.nar_size).sum();
+
+    let mut sorted: Vec<&crate::nix_interface::StorePathInfo> = paths.iter().collect();
+    sorted.sort_unstable_by(|a, b| b.nar_size.cmp(&a.nar_size));
+
+    let shown = sorted.iter().take(top);
+    let mut table: Vec<Vec<_>> = shown
+        .map(|info| {
+            let share = if total > 0 { (info.nar_size as f64 / total as f64) * 100.0 } else { 0.0 };
+            vec![
+                info.path.display().to_string().cell(),
+                format_bytes(info.nar_size).cell(),
+                format!("{share:.1}%").cell(),
+            ]
+        })
+        .collect();
+
+    let remaining = sorted.len().saturating_sub(top);
+    if remaining > 0 {
+        let remaining_size: u64 = sorted[top..].iter().map(|p| p.nar_size).sum();
+        table.push(vec![
+            format!("... and {remaining} more").cell(),
+            format_bytes(remaining_size).cell(),
+            "".cell(),
+        ]);
+    }
+
+    let table = table.table()
+        .title(vec![
+            "Store Path".cell().bold(true),
+            "Size".cell().bold(true),
+            "Share".cell().bold(true),
+        ])
+        .bold(true);
+
+    if let Err(e) = print_stdout(table) {
+        tracing::warn!("Failed to render disk usage table: {}", e);
+    }
+
+    println!("\nTotal closure size: {} across {} paths", format_bytes(total), sorted.len());
+}
+
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit_index = 0;
+    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit_index += 1;
+    }
+    format!("{:.2} {}", size, UNITS[unit_index])
+}
+
 /// Format package diff as a table string
 pub fn format_package_diff_table(
     added: &[String],
@@ -240,4 +683,4 @@ pub fn display_git_status(
     }
 
     Ok(())
-}
\ No newline at end of file
+}
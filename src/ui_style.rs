@@ -5,46 +5,193 @@
 //! semantic color palettes, standardized output prefixes/symbols, and
 //! helper functions for printing styled messages.
 
-use owo_colors::OwoColorize;
+use once_cell::sync::OnceCell;
+use owo_colors::{AnsiColors, OwoColorize};
 use std::fmt::Display;
 
+/// Semantic role -> color mapping backing [`Colors`], resolved once from `[ui.theme]` in
+/// `ng.toml` via [`init_theme`]. Defaults to [`Theme::dark`] if `init_theme` is never called
+/// (e.g. in unit tests).
+static THEME: OnceCell<Theme> = OnceCell::new();
+
+/// A set of colors for each semantic role used throughout the CLI's output.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub success: AnsiColors,
+    pub error: AnsiColors,
+    pub warning: AnsiColors,
+    pub info: AnsiColors,
+    pub prompt: AnsiColors,
+    pub code: AnsiColors,
+    pub emphasis: AnsiColors,
+}
+
+impl Theme {
+    /// The original palette, tuned for dark terminal backgrounds.
+    pub fn dark() -> Self {
+        Self {
+            success: AnsiColors::Green,
+            error: AnsiColors::Red,
+            warning: AnsiColors::Yellow,
+            info: AnsiColors::Cyan,
+            prompt: AnsiColors::Magenta,
+            code: AnsiColors::BrightBlack,
+            emphasis: AnsiColors::White,
+        }
+    }
+
+    /// Built-in palette for light/white terminal backgrounds, where `dark`'s bright-black
+    /// code color and pale yellow warnings are unreadable.
+    pub fn light() -> Self {
+        Self {
+            success: AnsiColors::Green,
+            error: AnsiColors::Red,
+            warning: AnsiColors::Magenta,
+            info: AnsiColors::Blue,
+            prompt: AnsiColors::Blue,
+            code: AnsiColors::Black,
+            emphasis: AnsiColors::Black,
+        }
+    }
+
+    fn preset(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// Parses a color name from `ng.toml` (e.g. `"red"`, `"bright_black"`) into an [`AnsiColors`].
+fn parse_color(name: &str) -> Option<AnsiColors> {
+    Some(match name.to_lowercase().replace(['_', '-'], "").as_str() {
+        "black" => AnsiColors::Black,
+        "red" => AnsiColors::Red,
+        "green" => AnsiColors::Green,
+        "yellow" => AnsiColors::Yellow,
+        "blue" => AnsiColors::Blue,
+        "magenta" => AnsiColors::Magenta,
+        "cyan" => AnsiColors::Cyan,
+        "white" => AnsiColors::White,
+        "brightblack" => AnsiColors::BrightBlack,
+        "brightred" => AnsiColors::BrightRed,
+        "brightgreen" => AnsiColors::BrightGreen,
+        "brightyellow" => AnsiColors::BrightYellow,
+        "brightblue" => AnsiColors::BrightBlue,
+        "brightmagenta" => AnsiColors::BrightMagenta,
+        "brightcyan" => AnsiColors::BrightCyan,
+        "brightwhite" => AnsiColors::BrightWhite,
+        _ => return None,
+    })
+}
+
+/// Resolves the active [`Theme`] from `[ui.theme]` config: starts from `preset` (default
+/// `"dark"`), then applies any per-role overrides. Called once from `main`; unknown preset or
+/// color names are warned about and ignored rather than treated as a hard error, since a typo
+/// in a theme name shouldn't block a rebuild.
+pub fn init_theme(config: &crate::config::ThemeConfig) {
+    let mut theme = config
+        .preset
+        .as_deref()
+        .map(|name| {
+            Theme::preset(name).unwrap_or_else(|| {
+                eprintln!("Warning: unknown ui.theme.preset '{name}', using 'dark'");
+                Theme::dark()
+            })
+        })
+        .unwrap_or_default();
+
+    macro_rules! apply_override {
+        ($field:ident) => {
+            if let Some(name) = &config.$field {
+                match parse_color(name) {
+                    Some(color) => theme.$field = color,
+                    None => eprintln!(
+                        "Warning: unknown color '{}' for ui.theme.{}, ignoring",
+                        name,
+                        stringify!($field)
+                    ),
+                }
+            }
+        };
+    }
+    apply_override!(success);
+    apply_override!(error);
+    apply_override!(warning);
+    apply_override!(info);
+    apply_override!(prompt);
+    apply_override!(code);
+    apply_override!(emphasis);
+
+    let _ = THEME.set(theme);
+}
+
+fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::dark)
+}
+
+/// Applies `--color`/`NO_COLOR` to `owo_colors`' global override, so every `Colors::*` call
+/// (and any other `owo_colors` styling in the crate) respects it without threading a flag
+/// through every print site. `auto` disables color when `NO_COLOR` is set or stdout isn't a
+/// terminal; `always`/`never` force the corresponding behavior regardless of environment.
+pub fn init_color(mode: crate::interface::ColorMode) {
+    use crate::interface::ColorMode;
+
+    match mode {
+        ColorMode::Always => owo_colors::set_override(true),
+        ColorMode::Never => owo_colors::set_override(false),
+        ColorMode::Auto => {
+            if std::env::var_os("NO_COLOR").is_some() || !crate::util::is_stdout_tty() {
+                owo_colors::set_override(false);
+            }
+        }
+    }
+}
+
 /// Semantic color palette for consistent UI styling
 pub struct Colors;
 
 impl Colors {
-    /// Success color (green)
+    /// Success color
     pub fn success<D: Display>(text: D) -> String {
-        format!("{}", text.green())
+        format!("{}", text.color(theme().success))
     }
 
-    /// Error/Failure color (red)
+    /// Error/Failure color
     pub fn error<D: Display>(text: D) -> String {
-        format!("{}", text.red())
+        format!("{}", text.color(theme().error))
     }
 
-    /// Warning color (yellow)
+    /// Warning color
     pub fn warning<D: Display>(text: D) -> String {
-        format!("{}", text.yellow())
+        format!("{}", text.color(theme().warning))
     }
 
-    /// Informational/Progress color (cyan)
+    /// Informational/Progress color
     pub fn info<D: Display>(text: D) -> String {
-        format!("{}", text.cyan())
+        format!("{}", text.color(theme().info))
     }
 
-    /// User Input/Prompts color (magenta)
+    /// User Input/Prompts color
     pub fn prompt<D: Display>(text: D) -> String {
-        format!("{}", text.magenta())
+        format!("{}", text.color(theme().prompt))
     }
 
-    /// Code/Paths/Commands color (bright black)
+    /// Code/Paths/Commands color
     pub fn code<D: Display>(text: D) -> String {
-        format!("{}", text.bright_black())
+        format!("{}", text.color(theme().code))
     }
 
-    /// Emphasis (bold)
+    /// Emphasis (bold, themed color)
     pub fn emphasis<D: Display>(text: D) -> String {
-        format!("{}", text.bold())
+        format!("{}", text.color(theme().emphasis).bold())
     }
 }
 
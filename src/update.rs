@@ -1,10 +1,14 @@
+use std::path::Path;
+
 use tracing::warn;
 
 use crate::commands::Command;
 use crate::installable::Installable;
+use crate::prefetch;
+use crate::release_notes;
 use crate::Result;
 
-pub fn update(installable: &Installable, input: Option<String>) -> Result<()> {
+pub fn update(installable: &Installable, input: Option<String>, project_root: &Path) -> Result<()> {
     match installable {
         Installable::Flake { reference, .. } => {
             let mut cmd = Command::new("nix").args(["flake", "update"]);
@@ -15,7 +19,11 @@ pub fn update(installable: &Installable, input: Option<String>) -> Result<()> {
                 cmd = cmd.message("Updating all flake inputs");
             }
 
+            let revs_before = release_notes::capture_revs_before(project_root);
             cmd.arg("--flake").arg(reference).run()?;
+            prefetch::prefetch_updated_inputs(project_root, &revs_before);
+            release_notes::show_relevant_release_notes(project_root, &revs_before);
+            release_notes::show_compare_links(project_root, &revs_before);
         }
         _ => {
             warn!(
@@ -191,6 +191,13 @@ pub fn is_stdout_tty() -> bool {
     atty::is(atty::Stream::Stdout)
 }
 
+/// Checks if stderr is connected to a terminal. Progress spinners render to stderr (see
+/// `progress.rs`), so this is what determines whether they can be drawn interactively, as
+/// opposed to `is_stdout_tty`, which governs redirected command *output*.
+pub fn is_stderr_tty() -> bool {
+    atty::is(atty::Stream::Stderr)
+}
+
 /// Manages the output path for Nix builds.
 pub fn manage_out_path(out_link_opt: Option<&PathBuf>) -> Result<Box<dyn MaybeTempPath>> {
     use color_eyre::eyre::WrapErr;
@@ -257,6 +264,216 @@ pub fn run_piped_commands(
     Ok(output2)
 }
 
+/// Runs a command with inherited stdin, duplicating its stdout/stderr to both
+/// the terminal and a log file so a run's full output survives after the
+/// terminal scrollback is gone.
+pub fn run_cmd_tee_stdio(
+    command: &mut std::process::Command,
+    log_path: &Path,
+) -> Result<std::process::ExitStatus, UtilCommandError> {
+    use std::io::{Read, Write};
+    use std::process::Stdio;
+
+    let command_str = format!("{:?}", command);
+    debug!("Executing command with tee'd stdio to {:?}: {:?}", log_path, command_str);
+
+    if let Some(parent) = log_path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let log_file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path)
+        .map_err(|e| UtilCommandError::SpawnFailed {
+            command_str: command_str.clone(),
+            io_error: e,
+        })?;
+
+    let mut child = command
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| UtilCommandError::SpawnFailed {
+            command_str: command_str.clone(),
+            io_error: e,
+        })?;
+
+    let child_stdout = child.stdout.take().expect("stdout was piped");
+    let child_stderr = child.stderr.take().expect("stderr was piped");
+    let stdout_log = log_file.try_clone().map_err(|e| UtilCommandError::SpawnFailed {
+        command_str: command_str.clone(),
+        io_error: e,
+    })?;
+
+    fn tee<R: Read + Send + 'static, W: Write + Send + 'static>(
+        mut src: R,
+        mut dst: W,
+        mut log: std::fs::File,
+    ) -> std::thread::JoinHandle<()> {
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 8192];
+            loop {
+                match src.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        let _ = dst.write_all(&buf[..n]);
+                        let _ = log.write_all(&buf[..n]);
+                    }
+                }
+            }
+        })
+    }
+
+    let stdout_handle = tee(child_stdout, std::io::stdout(), stdout_log);
+    let stderr_handle = tee(child_stderr, std::io::stderr(), log_file);
+
+    let status = child.wait().map_err(|e| UtilCommandError::SpawnFailed {
+        command_str: command_str.clone(),
+        io_error: e,
+    })?;
+
+    let _ = stdout_handle.join();
+    let _ = stderr_handle.join();
+
+    if !status.success() {
+        warn!(
+            "Command with tee'd stdio failed: {} - Exit Code: {:?}",
+            command_str,
+            status.code()
+        );
+        return Err(UtilCommandError::InheritedNonZeroStatus {
+            command_str,
+            status_code: status.code().map_or_else(|| "unknown".to_string(), |c| c.to_string()),
+        });
+    }
+    Ok(status)
+}
+
+/// Runs `sudo -v` once up front so a privileged stage later in the workflow (the profile
+/// update and activation) doesn't stop mid-run to prompt for a password, then refreshes sudo's
+/// timestamp every 4 minutes (just under its default 5 minute timeout) for the rest of the
+/// process's life, so a slow build can't let it expire before activation runs. Enabled via
+/// `elevation.preauth` in `ng.toml` (see [`crate::config::ElevationConfig`]).
+pub fn preauthenticate_sudo() -> Result<(), UtilCommandError> {
+    let mut cmd = std::process::Command::new("sudo");
+    cmd.arg("-v");
+    run_cmd_inherit_stdio(&mut cmd)?;
+
+    std::thread::spawn(|| loop {
+        std::thread::sleep(std::time::Duration::from_secs(4 * 60));
+        // `-n`: never prompt from the background thread; if the timestamp already lapsed,
+        // the next real `sudo` invocation will just prompt again like it would have anyway.
+        let _ = std::process::Command::new("sudo").args(["-v", "-n"]).output();
+    });
+
+    Ok(())
+}
+
+/// Runs a command with a pseudo-terminal attached to stdin/stdout/stderr instead of the
+/// process's own stdio, so interactive prompts (most importantly `sudo` asking for a
+/// password) still see a real terminal even when our own stdout is being piped or captured
+/// by the caller (e.g. `nom`, or `Command::run_capture_output`). A background thread forwards
+/// our stdin to the pty so the user's keystrokes still reach the prompt.
+///
+/// Because a pty has a single stream, the child's stdout and stderr are merged the way they
+/// would be on a real terminal; callers that need them split should not go through this path.
+pub fn run_cmd_pty(command: &mut std::process::Command) -> Result<std::process::Output, UtilCommandError> {
+    use std::io::{Read, Write};
+    use std::process::Stdio;
+
+    let command_str = format!("{:?}", command);
+    debug!("Executing command with pty-attached stdio: {:?}", command_str);
+
+    let pty = nix::pty::openpty(None, None).map_err(|e| UtilCommandError::SpawnFailed {
+        command_str: command_str.clone(),
+        io_error: std::io::Error::from_raw_os_error(e as i32),
+    })?;
+
+    let make_slave_stdio = |name: &str| -> Result<Stdio, UtilCommandError> {
+        pty.slave
+            .try_clone()
+            .map(Stdio::from)
+            .map_err(|e| UtilCommandError::SpawnFailed {
+                command_str: format!("{} ({})", command_str, name),
+                io_error: e,
+            })
+    };
+
+    let mut child = command
+        .stdin(make_slave_stdio("stdin")?)
+        .stdout(make_slave_stdio("stdout")?)
+        .stderr(make_slave_stdio("stderr")?)
+        .spawn()
+        .map_err(|e| UtilCommandError::SpawnFailed {
+            command_str: command_str.clone(),
+            io_error: e,
+        })?;
+    // The child holds its own clones of the slave now; drop ours so we see EOF on the master
+    // once the child exits instead of waiting on ourselves.
+    drop(pty.slave);
+
+    let mut master = std::fs::File::from(pty.master);
+    if let Ok(mut stdin_writer) = master.try_clone() {
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            let mut stdin = std::io::stdin();
+            loop {
+                match stdin.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        if stdin_writer.write_all(&buf[..n]).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    let mut output = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        match master.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => {
+                let _ = std::io::stdout().write_all(&buf[..n]);
+                let _ = std::io::stdout().flush();
+                output.extend_from_slice(&buf[..n]);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            // The kernel reports EIO once the child has exited and closed the slave side;
+            // that's the normal end-of-session signal for a pty, not a real failure.
+            Err(_) => break,
+        }
+    }
+
+    let status = child.wait().map_err(|e| UtilCommandError::SpawnFailed {
+        command_str: command_str.clone(),
+        io_error: e,
+    })?;
+
+    if !status.success() {
+        warn!(
+            "Command with pty-attached stdio failed: {} - Exit Code: {:?}",
+            command_str,
+            status.code()
+        );
+        return Err(UtilCommandError::NonZeroStatus {
+            command_str,
+            status_code: status.code().map_or_else(|| "unknown".to_string(), |c| c.to_string()),
+            stdout: String::from_utf8_lossy(&output).into_owned(),
+            stderr: String::new(),
+        });
+    }
+
+    Ok(std::process::Output {
+        status,
+        stdout: output,
+        stderr: Vec::new(),
+    })
+}
+
 /// Checks if a command exists in the PATH.
 pub fn command_exists(cmd_name: &str) -> bool {
     let cmd_version = crate::commands::Command::new(cmd_name).arg("--version");
@@ -269,6 +486,80 @@ pub fn command_exists(cmd_name: &str) -> bool {
     }
 }
 
+/// Formats a copy of `path` (via `formatter_bin`, assumed to format in place when given a bare
+/// file argument) in a temp directory, then returns a `diff -u` between the original and the
+/// formatted copy.
+pub fn unified_diff_after_format(path: &Path, formatter_bin: &str) -> Result<String> {
+    let original = std::fs::read(path)?;
+
+    let tmp_dir = tempfile::Builder::new().prefix("ng-fmt").tempdir()?;
+    let tmp_file = tmp_dir.path().join(
+        path.file_name()
+            .ok_or_else(|| eyre::eyre!("{} has no file name", path.display()))?,
+    );
+    std::fs::write(&tmp_file, &original)?;
+
+    crate::commands::Command::new(formatter_bin).arg(&tmp_file).run().ok();
+
+    let diff = crate::commands::Command::new("diff")
+        .arg("-u")
+        .arg(path)
+        .arg(&tmp_file)
+        .run_capture_output()?;
+
+    Ok(String::from_utf8_lossy(&diff.stdout)
+        .replace(&tmp_file.display().to_string(), &format!("{} (formatted)", path.display())))
+}
+
+/// Trims a unified diff down to its first `max_hunks` `@@ ... @@` hunks (plus the leading
+/// `---`/`+++` file header lines), so a preview doesn't dump an entire rewritten file. Returns
+/// the diff unchanged if it has `max_hunks` hunks or fewer.
+pub fn trim_diff_hunks(diff: &str, max_hunks: usize) -> String {
+    let mut result = String::new();
+    let mut hunks_seen = 0;
+
+    for line in diff.lines() {
+        if line.starts_with("@@") {
+            hunks_seen += 1;
+            if hunks_seen > max_hunks {
+                result.push_str(&format!("... ({} more hunk(s) omitted)\n", diff.lines().filter(|l| l.starts_with("@@")).count() - max_hunks));
+                break;
+            }
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+
+    result
+}
+
+/// Builds a `nix repl --expr` argument that evaluates `<flake_reference>#<attribute>` and
+/// re-exposes its `config`, `options`, `pkgs`, and `lib` as top-level repl bindings (`nix repl
+/// --expr` merges an attrset result's attributes into the repl scope), instead of leaving the
+/// user to dig through nested flake outputs by hand.
+///
+/// Assumes the target evaluates to something shaped like the result of `nixosSystem`/
+/// `darwinSystem`/`homeManagerConfiguration` (i.e. exposing `config`, `options`, and `pkgs`).
+pub fn preloaded_repl_expr(flake_reference: &str, attribute: &[String]) -> String {
+    let attr_path = if attribute.is_empty() {
+        String::new()
+    } else {
+        format!(".{}", crate::installable::join_attribute(attribute))
+    };
+
+    format!(
+        r#"let cfg = (builtins.getFlake "{flake_reference}"){attr_path}; in {{ inherit (cfg) config options; pkgs = cfg.pkgs; lib = cfg.pkgs.lib; }}"#
+    )
+}
+
+/// Single-quotes `arg` for safe inclusion in a shell command line built by `format!`/`.join(" ")`
+/// (e.g. a command string handed to `ssh host <command>`, which the remote shell re-parses).
+/// Wraps in `'...'`, escaping any embedded `'` as `'\''`, so the argument survives as one word
+/// regardless of spaces, `&`, `;`, `` ` ``, or other shell metacharacters it may contain.
+pub fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r#"'\''"#))
+}
+
 /// Checks if a path is hidden (starts with a dot).
 pub fn is_hidden_path(path: &Path) -> bool {
     path.file_name()
@@ -0,0 +1,30 @@
+//! `ng why`: wraps `nix why-depends` with the same flexible target resolution `ng diff` uses, so
+//! `package`/`dependency` can be a generation reference, an existing path, or a flake installable.
+
+use color_eyre::eyre::WrapErr;
+
+use crate::commands::Command;
+use crate::diff_cmd::resolve_diff_target;
+use crate::interface::WhyArgs;
+use crate::nix_interface::NixInterface;
+use crate::Result;
+
+impl WhyArgs {
+    pub fn run(&self, verbose_count: u8) -> Result<()> {
+        let nix_interface = NixInterface::new(verbose_count, false);
+
+        let package = resolve_diff_target(&self.package, &nix_interface)
+            .wrap_err_with(|| format!("Failed to resolve '{}'", self.package))?;
+        let dependency = resolve_diff_target(&self.dependency, &nix_interface)
+            .wrap_err_with(|| format!("Failed to resolve '{}'", self.dependency))?;
+
+        let mut cmd = Command::new("nix")
+            .args(["why-depends"])
+            .arg(&package)
+            .arg(&dependency);
+        if self.all {
+            cmd = cmd.arg("--all");
+        }
+        cmd.add_verbosity_flags(verbose_count).run()
+    }
+}
@@ -0,0 +1,135 @@
+//! `ng why-rebuild`: explains why a derivation would rebuild by diffing it against the
+//! derivation behind the currently-active generation, using `nix-diff` when available.
+
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, WrapErr};
+use tracing::info;
+
+use crate::commands::Command;
+use crate::installable::Installable;
+use crate::interface::WhyRebuildArgs;
+use crate::util;
+use crate::Result;
+
+impl WhyRebuildArgs {
+    pub fn run(&self, verbose_count: u8) -> Result<()> {
+        let new_drv = drv_path_for_installable(&self.installable, verbose_count)
+            .wrap_err("Failed to resolve the derivation to explain")?;
+
+        let against = match &self.against {
+            Some(path) => path.clone(),
+            None => default_comparison_profile()
+                .ok_or_else(|| eyre!("Could not find a default profile to compare against; pass --against explicitly"))?,
+        };
+        let old_drv = deriver_of(&against)
+            .wrap_err_with(|| format!("Failed to find the derivation behind {}", against.display()))?;
+
+        if old_drv == new_drv {
+            info!("{} is already up to date with {}; nothing would rebuild.", against.display(), new_drv.display());
+            return Ok(());
+        }
+
+        if util::command_exists("nix-diff") {
+            info!("Comparing {} -> {} with nix-diff...", old_drv.display(), new_drv.display());
+            Command::new("nix-diff")
+                .arg(&old_drv)
+                .arg(&new_drv)
+                .add_verbosity_flags(verbose_count)
+                .run()
+        } else {
+            info!("`nix-diff` not found on PATH; falling back to a plain derivation diff.");
+            plain_derivation_diff(&old_drv, &new_drv, verbose_count)
+        }
+    }
+}
+
+/// Resolves `installable` to its `.drv` path without building it, by evaluating
+/// `<installable>.drvPath` instead of the installable itself.
+fn drv_path_for_installable(installable: &Installable, verbose_count: u8) -> Result<PathBuf> {
+    let mut installable = installable.clone();
+    match &mut installable {
+        Installable::Flake { attribute, .. }
+        | Installable::File { attribute, .. }
+        | Installable::Expression { attribute, .. } => {
+            attribute.push("drvPath".to_string());
+        }
+        Installable::Store { path } => return Ok(path.clone()),
+    }
+
+    let raw = Command::new("nix")
+        .arg("eval")
+        .arg("--raw")
+        .arg(installable.to_args().join(" "))
+        .add_verbosity_flags(verbose_count)
+        .run_capture()?
+        .ok_or_else(|| eyre!("`nix eval` produced no output for the derivation path"))?;
+
+    Ok(PathBuf::from(raw.trim()))
+}
+
+/// Finds the derivation that produced `profile_path`, via `nix-store -q --deriver`.
+fn deriver_of(profile_path: &Path) -> Result<PathBuf> {
+    let deriver = Command::new("nix-store")
+        .args(["-q", "--deriver"])
+        .arg(profile_path)
+        .run_capture()?
+        .ok_or_else(|| eyre!("`nix-store -q --deriver` produced no output for {}", profile_path.display()))?;
+
+    let deriver = deriver.trim();
+    if deriver == "unknown-deriver" {
+        return Err(eyre!(
+            "{} has no known deriver (its .drv may have been garbage-collected)",
+            profile_path.display()
+        ));
+    }
+
+    Ok(PathBuf::from(deriver))
+}
+
+/// Falls back to `/nix/var/nix/profiles/system` (NixOS) or the current user's home-manager
+/// profile when `--against` isn't given.
+fn default_comparison_profile() -> Option<PathBuf> {
+    let system_profile = PathBuf::from("/nix/var/nix/profiles/system");
+    if system_profile.exists() {
+        return Some(system_profile);
+    }
+    crate::home::default_profile()
+}
+
+/// Best-effort stand-in for `nix-diff` when it isn't installed: prints the lines of the two
+/// derivations' `nix derivation show` output that appear on only one side (sorted, old as `-`,
+/// new as `+`) so the user can spot the changed input/env by eye.
+fn plain_derivation_diff(old_drv: &Path, new_drv: &Path, verbose_count: u8) -> Result<()> {
+    let old_show = Command::new("nix")
+        .args(["derivation", "show"])
+        .arg(old_drv)
+        .add_verbosity_flags(verbose_count)
+        .run_capture()?
+        .unwrap_or_default();
+    let new_show = Command::new("nix")
+        .args(["derivation", "show"])
+        .arg(new_drv)
+        .add_verbosity_flags(verbose_count)
+        .run_capture()?
+        .unwrap_or_default();
+
+    println!("--- {}\n+++ {}", old_drv.display(), new_drv.display());
+    let (old_lines, new_lines): (Vec<&str>, Vec<&str>) = (old_show.lines().collect(), new_show.lines().collect());
+    let mut old_only: Vec<&str> = old_lines.iter().filter(|l| !new_lines.contains(l)).copied().collect();
+    let mut new_only: Vec<&str> = new_lines.iter().filter(|l| !old_lines.contains(l)).copied().collect();
+    old_only.sort_unstable();
+    new_only.sort_unstable();
+
+    if old_only.is_empty() && new_only.is_empty() {
+        println!("(no textual differences in `nix derivation show` output)");
+    }
+    for line in old_only {
+        println!("-{line}");
+    }
+    for line in new_only {
+        println!("+{line}");
+    }
+
+    Ok(())
+}
@@ -1,10 +1,9 @@
 use crate::workflow_strategy::{PlatformRebuildStrategy, ActivationMode};
+use crate::interface::ConfirmStage;
 // use crate::workflow_types::CommonRebuildArgs; // Unused
 use crate::context::OperationContext;
 // use crate::installable::Installable; // Unused
 use crate::util::{self}; // MaybeTempPath was unused, util::self might still be needed
-// Phase 3: Will be uncommented when implementing nil integration
-// use crate::pre_flight::{self, get_core_pre_flight_checks, run_shared_pre_flight_checks};
 use crate::Result; // from color_eyre
 use crate::progress;
 use std::path::{Path, PathBuf};
@@ -12,27 +11,6 @@ use std::process::Command;
 use tracing::{info, debug, warn, error};
 use color_eyre::eyre::{bail, WrapErr};
 
-// Stubs for functions to be implemented/refactored later or in other modules
-// Phase 1: Temporary stubs for pre_flight until Phase 3
-mod pre_flight {
-    use crate::Result;
-    use crate::context::OperationContext;
-    use crate::workflow_strategy::PlatformRebuildStrategy;
-    
-    pub fn get_core_pre_flight_checks(_op_ctx: &OperationContext) -> Vec<()> {
-        Vec::new()
-    }
-    
-    pub fn run_shared_pre_flight_checks<S: PlatformRebuildStrategy>(
-        _op_ctx: &OperationContext,
-        _platform_strategy: &S,
-        _platform_args: &S::PlatformArgs,
-        _checks_to_run: &[()],
-    ) -> Result<()> {
-        Ok(())
-    }
-}
-
 /// Shows the diff between two configurations using nvd
 ///
 /// # Arguments
@@ -44,7 +22,7 @@ mod pre_flight {
 /// # Returns
 ///
 /// * `Result<()>` - Success or an error
-fn show_platform_diff(
+pub(crate) fn show_platform_diff(
     current_profile: &Path,
     new_profile_path: &Path,
     verbose_count: u8,
@@ -108,22 +86,33 @@ pub fn execute_rebuild_workflow<S: PlatformRebuildStrategy>(
 ) -> Result<()> {
     let workflow_span = tracing::info_span!("execute_rebuild_workflow", platform = platform_strategy.name(), mode = ?activation_mode);
     let _enter = workflow_span.enter();
+    let workflow_started_at = std::time::Instant::now();
 
     info!("🚀 Starting {} rebuild workflow ({:?})...", platform_strategy.name(), activation_mode);
 
+    if op_ctx.common_args.plan {
+        print_execution_plan(op_ctx, platform_strategy, activation_mode);
+    }
+
     // 1. Platform-specific pre-rebuild hook
     platform_strategy.pre_rebuild_hook(op_ctx, platform_args)
         .wrap_err_with(|| format!("{} pre-rebuild hook failed", platform_strategy.name()))?;
     debug!("Pre-rebuild hook for {} completed.", platform_strategy.name());
 
+    if op_ctx.config.elevation.preauth && !op_ctx.common_args.dry_run {
+        util::preauthenticate_sudo().wrap_err("Failed to pre-authenticate sudo")?;
+    }
+
     // 2. Shared Pre-flight checks (Git, Parse, Lint, Eval, DryRun)
-    if !op_ctx.common_args.no_preflight {
-        // Get the core checks (Phase 3 will use real pre-flight checks)
-        let checks_to_run = pre_flight::get_core_pre_flight_checks(op_ctx);
-        pre_flight::run_shared_pre_flight_checks(op_ctx, platform_strategy, platform_args, &checks_to_run)?;
-        debug!("Shared pre-flight checks for {} completed.", platform_strategy.name());
+    let checks_run: Vec<String> = if op_ctx.common_args.no_preflight {
+        Vec::new()
     } else {
-        info!("[⏭️ Pre-flight] All checks skipped due to --no-preflight.");
+        crate::pre_flight::configured_check_names(op_ctx)
+    };
+    {
+        let _span = tracing::info_span!("pre_flight").entered();
+        crate::pre_flight::run_shared_pre_flight_checks(op_ctx, platform_strategy, platform_args)?;
+        debug!("Shared pre-flight checks for {} completed.", platform_strategy.name());
     }
 
     // 3. Optional Flake Update
@@ -131,7 +120,11 @@ pub fn execute_rebuild_workflow<S: PlatformRebuildStrategy>(
         info!("Updating flake inputs as requested...");
         let pb_update = crate::progress::start_spinner("Updating flake inputs...");
         
-        crate::update::update(&op_ctx.common_args.installable, op_ctx.update_args.update_input.clone())
+        crate::update::update(
+            &op_ctx.common_args.installable,
+            op_ctx.update_args.update_input.clone(),
+            &op_ctx.get_effective_project_root(),
+        )
             .wrap_err("Failed to update flake inputs")
             .map_err(|e| {
                 crate::progress::finish_spinner_fail(&pb_update);
@@ -144,23 +137,123 @@ pub fn execute_rebuild_workflow<S: PlatformRebuildStrategy>(
     }
 
     // 4. Get Toplevel Derivation
-    let toplevel_installable = platform_strategy.get_toplevel_installable(op_ctx, platform_args)
-        .wrap_err_with(|| format!("Failed to determine toplevel installable for {}", platform_strategy.name()))?;
-    debug!("Resolved toplevel installable for {}: {:?}", platform_strategy.name(), toplevel_installable);
+    let toplevel_installable = {
+        let _span = tracing::info_span!("eval").entered();
+        let toplevel_installable = platform_strategy.get_toplevel_installable(op_ctx, platform_args)
+            .wrap_err_with(|| format!("Failed to determine toplevel installable for {}", platform_strategy.name()))?;
+        debug!("Resolved toplevel installable for {}: {}", platform_strategy.name(), toplevel_installable);
+        toplevel_installable
+    };
 
     // 5. Build Configuration
+    if !op_ctx.common_args.dry_run && op_ctx.common_args.confirm_stages.contains(&ConfirmStage::Build) {
+        info!("Confirmation for building {} would be requested now.", toplevel_installable);
+        if !dialoguer::Confirm::new()
+            .with_prompt(format!("Build {}?", toplevel_installable))
+            .default(false)
+            .interact()?
+        {
+            bail!("User rejected the build for {}.", platform_strategy.name());
+        }
+        info!("User confirmed build for {}.", platform_strategy.name());
+    }
+
+    let _build_span = tracing::info_span!("build").entered();
+    if op_ctx.config.remote_builders.health_check {
+        if let Some(builders) = &op_ctx.config.remote_builders.builders {
+            let unreachable = op_ctx.nix_interface.check_remote_builders(builders);
+            if !unreachable.is_empty() {
+                warn!(
+                    "Remote builder(s) unreachable, build may fall back to local: {}",
+                    unreachable.join(", ")
+                );
+            }
+        }
+    }
+
     let out_path_manager = util::manage_out_path(op_ctx.common_args.out_link.as_ref())?;
     let built_profile_path: PathBuf;
 
+    let mut build_args = op_ctx.common_args.extra_build_args.clone();
+    if op_ctx.common_args.keep_going && !build_args.iter().any(|a| a == "--keep-going") {
+        build_args.push(std::ffi::OsString::from("--keep-going"));
+    }
+
+    let build_plan = if !op_ctx.common_args.dry_run {
+        op_ctx.nix_interface.build_plan_summary(&toplevel_installable).ok()
+    } else {
+        None
+    };
+    let build_started_at = std::time::Instant::now();
+
+    let build_pb = if !op_ctx.common_args.dry_run {
+        let total = build_plan.as_ref().map(|p| (p.to_build + p.to_fetch) as u64).unwrap_or(0);
+        Some(progress::start_build_progress(total))
+    } else {
+        None
+    };
+    let build_progress_stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let build_progress_ticker = build_pb.clone().map(|pb| {
+        let stop = build_progress_stop.clone();
+        let total = pb.length().unwrap_or(0);
+        std::thread::spawn(move || {
+            let mut pos = 0u64;
+            while !stop.load(std::sync::atomic::Ordering::Relaxed) && pos + 1 < total {
+                std::thread::sleep(std::time::Duration::from_secs(3));
+                pos += 1;
+                pb.set_position(pos);
+            }
+        })
+    });
+
+    let build_host = platform_strategy.get_build_host(op_ctx, platform_args);
+
     // Always call build_configuration; it's dry-run aware internally.
     // NixInterface::build_configuration will call cmd.run() on a dry command (which records in test mode),
     // and for its dry_run mode, it should return a conventional/placeholder PathBuf.
-    let result_from_build_config: PathBuf = op_ctx.nix_interface.build_configuration(
-        &toplevel_installable,
-        &op_ctx.common_args.extra_build_args,
-        op_ctx.common_args.no_nom,
-        Some(out_path_manager.get_path()), // Pass out_link as Option
-    )?;
+    let build_result = if let Some(build_host) = &build_host {
+        info!("Building on remote host {} instead of locally...", build_host);
+        op_ctx
+            .nix_interface
+            .build_configuration_remote(build_host, &toplevel_installable, &build_args)
+            .and_then(|remote_path| {
+                if op_ctx.common_args.dry_run {
+                    return Ok(remote_path);
+                }
+                match platform_strategy.get_target_host(op_ctx, platform_args) {
+                    Some(target_host) if &target_host != build_host => {
+                        op_ctx
+                            .nix_interface
+                            .copy_closure_between_hosts(build_host, &target_host, &remote_path)?;
+                    }
+                    _ => {
+                        op_ctx.nix_interface.copy_closure_from_host(build_host, &remote_path)?;
+                    }
+                }
+                Ok(remote_path)
+            })
+    } else {
+        op_ctx.nix_interface.build_configuration(
+            &toplevel_installable,
+            &build_args,
+            op_ctx.common_args.no_nom,
+            Some(out_path_manager.get_path()), // Pass out_link as Option
+        )
+    };
+
+    build_progress_stop.store(true, std::sync::atomic::Ordering::Relaxed);
+    if let Some(ticker) = build_progress_ticker {
+        let _ = ticker.join();
+    }
+    if let Some(pb) = &build_pb {
+        if build_result.is_ok() {
+            pb.finish_and_clear();
+        } else {
+            progress::finish_spinner_fail(pb);
+        }
+    }
+
+    let result_from_build_config: PathBuf = build_result?;
 
     if op_ctx.common_args.dry_run {
         // build_configuration was called in its dry_run mode.
@@ -176,26 +269,99 @@ pub fn execute_rebuild_workflow<S: PlatformRebuildStrategy>(
     } else {
         // Not a general dry_run for the workflow: use the actual built profile path.
         built_profile_path = result_from_build_config;
+
+        if let Some(cache_uri) = &op_ctx.config.binary_cache.push_to {
+            let pb_push = crate::progress::start_spinner(&format!("Pushing build to {}...", cache_uri));
+            match op_ctx.nix_interface.push_to_binary_cache(
+                &built_profile_path,
+                cache_uri,
+                &op_ctx.config.binary_cache.extra_args,
+            ) {
+                Ok(()) => crate::progress::finish_spinner_success(&pb_push, "Pushed build to binary cache"),
+                Err(e) => {
+                    crate::progress::finish_spinner_fail(&pb_push);
+                    warn!("Failed to push build to binary cache {}: {}", cache_uri, e);
+                }
+            }
+        }
+
+        if op_ctx.config.logging.build_stats {
+            let plan = build_plan.unwrap_or_default();
+            let stats = crate::nix_interface::BuildStats {
+                wall_time: build_started_at.elapsed(),
+                derivations_built: plan.to_build,
+                derivations_substituted: plan.to_fetch,
+                bytes_downloaded: plan.download_bytes,
+                closure_size: op_ctx.nix_interface.closure_size(&built_profile_path).ok(),
+            };
+            crate::tables::display_build_stats(&stats);
+        }
     }
 
+    drop(_build_span);
+
     // 6. Show Diff
-    if activation_mode != ActivationMode::Build && !op_ctx.common_args.dry_run {
-        if let Some(current_profile) = platform_strategy.get_current_profile_path(op_ctx, platform_args) {
-            if current_profile.exists() {
-                show_platform_diff(&current_profile, &built_profile_path, op_ctx.verbose_count)?;
-            } else {
-                info!("Current profile {} does not exist, skipping diff.", current_profile.display());
+    let mut diff_summary: Option<crate::json::DiffSummary> = None;
+    {
+        let _span = tracing::info_span!("diff").entered();
+        if activation_mode != ActivationMode::Build && !op_ctx.common_args.dry_run {
+            if let Some(current_profile) = platform_strategy.get_current_profile_path(op_ctx, platform_args) {
+                if current_profile.exists() {
+                    let (diff_current, diff_new) = platform_strategy.get_diff_target_paths(
+                        op_ctx,
+                        platform_args,
+                        &current_profile,
+                        &built_profile_path,
+                    );
+                    show_platform_diff(&diff_current, &diff_new, op_ctx.verbose_count)?;
+
+                    match op_ctx.nix_interface.diff_closures_summary(&diff_current, &diff_new) {
+                        Ok(counts) => diff_summary = Some(counts.into()),
+                        Err(e) => warn!("Failed to compute closure diff summary: {}", e),
+                    }
+                } else {
+                    info!("Current profile {} does not exist, skipping diff.", current_profile.display());
+                }
             }
+        } else if op_ctx.common_args.dry_run && activation_mode != ActivationMode::Build {
+            info!("Dry-run: Skipping diff display.");
         }
-    } else if op_ctx.common_args.dry_run && activation_mode != ActivationMode::Build {
-        info!("Dry-run: Skipping diff display.");
     }
 
     // 7. Optional Confirmation
-    if op_ctx.common_args.ask_confirmation && activation_mode != ActivationMode::Build && !op_ctx.common_args.dry_run {
+    let activation_confirm_stage = if activation_mode == ActivationMode::Boot {
+        ConfirmStage::Boot
+    } else {
+        ConfirmStage::Activate
+    };
+    if op_ctx.common_args.confirm_stages.contains(&activation_confirm_stage)
+        && activation_mode != ActivationMode::Build
+        && !op_ctx.common_args.dry_run
+    {
+        let mut prompt = format!("Apply the new {} configuration?", platform_strategy.name());
+        if let Some(summary) = &diff_summary {
+            let size_delta = platform_strategy
+                .get_current_profile_path(op_ctx, platform_args)
+                .filter(|p| p.exists())
+                .and_then(|current| {
+                    let before = op_ctx.nix_interface.closure_size(&current).ok()?;
+                    let after = op_ctx.nix_interface.closure_size(&built_profile_path).ok()?;
+                    Some(after as i64 - before as i64)
+                });
+            prompt.push_str(&format!(
+                "\n  {} added, {} removed, {} upgraded{}",
+                summary.added,
+                summary.removed,
+                summary.changed,
+                size_delta
+                    .map(|delta| format!(", closure {}{:.1} MiB", if delta >= 0 { "+" } else { "-" }, (delta.unsigned_abs() as f64) / 1024.0 / 1024.0))
+                    .unwrap_or_default(),
+            ));
+        }
+
         info!("Confirmation for applying new configuration would be requested now.");
         if !dialoguer::Confirm::new()
-            .with_prompt(format!("Apply the new {} configuration?", platform_strategy.name()))
+            .with_prompt(prompt)
             .default(false)
             .interact()?
         {
@@ -205,15 +371,28 @@ pub fn execute_rebuild_workflow<S: PlatformRebuildStrategy>(
     }
 
     // 8. Activate (if not dry run and not just a build variant)
-    if activation_mode != ActivationMode::Build && !op_ctx.common_args.dry_run {
-        info!("Attempting activation for {} (mode: {:?})...", platform_strategy.name(), activation_mode);
-        platform_strategy.activate_configuration(op_ctx, platform_args, &built_profile_path, &activation_mode)
-            .wrap_err_with(|| format!("Failed to activate {} configuration", platform_strategy.name()))?;
-        info!("Activation for {} completed.", platform_strategy.name());
-    } else if op_ctx.common_args.dry_run {
-        info!("Dry-run: Skipping activation for {}.", platform_strategy.name());
-    } else { // Build mode
-        info!("Build-only mode: Result at {}", built_profile_path.display());
+    let mut generation_number: Option<String> = None;
+    {
+        let _span = tracing::info_span!("activate").entered();
+        if activation_mode != ActivationMode::Build && !op_ctx.common_args.dry_run {
+            info!("Attempting activation for {} (mode: {:?})...", platform_strategy.name(), activation_mode);
+            platform_strategy.activate_configuration(op_ctx, platform_args, &built_profile_path, &activation_mode)
+                .wrap_err_with(|| format!("Failed to activate {} configuration", platform_strategy.name()))?;
+            info!("Activation for {} completed.", platform_strategy.name());
+
+            generation_number = platform_strategy
+                .get_generation_profile_path(op_ctx, platform_args)
+                .and_then(|profile| {
+                    crate::generations::list_generations(&profile)
+                        .into_iter()
+                        .find(|gen| gen.current)
+                        .map(|gen| gen.number)
+                });
+        } else if op_ctx.common_args.dry_run {
+            info!("Dry-run: Skipping activation for {}.", platform_strategy.name());
+        } else { // Build mode
+            info!("Build-only mode: Result at {}", built_profile_path.display());
+        }
     }
 
     // 9. Optional Cleanup (triggered by --clean flag)
@@ -242,6 +421,71 @@ pub fn execute_rebuild_workflow<S: PlatformRebuildStrategy>(
         .wrap_err_with(|| format!("{} post-rebuild hook failed", platform_strategy.name()))?;
     debug!("Post-rebuild hook for {} completed.", platform_strategy.name());
 
+    if op_ctx.common_args.json {
+        let summary = crate::json::OperationSummary {
+            platform: platform_strategy.name().to_string(),
+            mode: format!("{:?}", activation_mode),
+            built_path: built_profile_path.clone(),
+            generation: generation_number,
+            diff: diff_summary,
+            duration_secs: workflow_started_at.elapsed().as_secs_f64(),
+            checks_run,
+        };
+        match serde_json::to_string(&summary) {
+            Ok(json) => println!("{json}"),
+            Err(e) => warn!("Failed to serialize operation summary: {}", e),
+        }
+    }
+
+    if progress::is_quiet() && !op_ctx.common_args.json {
+        println!("{}", built_profile_path.display());
+    }
+
     info!("🏆 {} rebuild workflow ({:?}) finished successfully!", platform_strategy.name(), activation_mode);
     Ok(())
+}
+
+/// Prints a terraform-`plan`-style summary of the stages `execute_rebuild_workflow` is about to
+/// walk through, before any of them run. `--plan` forces `dry_run` on for the rest of the
+/// workflow, so the exact commands for each stage still get printed as that stage is reached
+/// (build, update, ...) via the same "Dry-run: would run ..." messages `--dry` already produces;
+/// this summary just gives the terraform-style overview of what order they'll happen in.
+fn print_execution_plan<S: PlatformRebuildStrategy>(
+    op_ctx: &OperationContext,
+    platform_strategy: &S,
+    activation_mode: ActivationMode,
+) {
+    info!("📋 Execution plan for {} ({:?}):", platform_strategy.name(), activation_mode);
+
+    let mut stage = 1;
+    if op_ctx.common_args.no_preflight {
+        info!("  {}. Pre-flight checks: skipped (--no-preflight)", stage);
+    } else {
+        let checks = crate::pre_flight::configured_check_names(op_ctx);
+        info!("  {}. Pre-flight checks: {}", stage, checks.join(", "));
+    }
+    stage += 1;
+
+    if op_ctx.update_args.update || op_ctx.update_args.update_input.is_some() {
+        info!("  {}. Update flake inputs (nix flake update{})", stage,
+            op_ctx.update_args.update_input.as_deref().map(|i| format!(" {i}")).unwrap_or_default());
+        stage += 1;
+    }
+
+    info!("  {}. Build {}", stage, op_ctx.common_args.installable);
+    stage += 1;
+
+    if activation_mode == ActivationMode::Build {
+        info!("  {}. (build-only mode: no diff, activation, or cleanup)", stage);
+    } else {
+        info!("  {}. Show diff against the current profile", stage);
+        stage += 1;
+        info!("  {}. Activate ({:?})", stage, activation_mode);
+        stage += 1;
+        if op_ctx.common_args.clean_after {
+            info!("  {}. Clean up old generations", stage);
+        }
+    }
+
+    info!("Exact commands for each stage will be printed below as they're reached (no changes will be made).");
 }
\ No newline at end of file
@@ -83,11 +83,15 @@ mod tests {
             medium_checks: false,
             full_checks: false,
             dry_run: true, // Use dry run for test
-            ask_confirmation: false,
+            confirm_stages: Vec::new(),
             no_nom: true,
             out_link: None,
             clean_after: false,
             extra_build_args: Vec::<OsString>::new(),
+            keep_going: false,
+            json: false,
+            plan: false,
+            no_group: false,
         };
 
         // Create operation context
@@ -4,7 +4,7 @@ use crate::context::OperationContext;
 use std::path::{Path, PathBuf};
 
 /// Represents the different modes of activation for a configuration
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ActivationMode {
     /// Activate and make default (if applicable)
     Switch,
@@ -53,8 +53,85 @@ pub trait PlatformRebuildStrategy {
     /// Final platform-specific actions after activation/cleanup.
     fn post_rebuild_hook(&self, op_ctx: &OperationContext, platform_args: &Self::PlatformArgs) -> Result<()>;
 
-    // Optional: For platform-specific pre-flight checks that don't fit the shared model.
-    // fn platform_specific_pre_flight_checks(&self, op_ctx: &OperationContext, platform_args: &Self::PlatformArgs) -> Result<()>;
+    /// The flake installable pointing at this configuration's `homebrew` module options, for
+    /// platforms that manage Homebrew declaratively (currently only Darwin via nix-darwin).
+    /// Returns `None` on platforms without a Homebrew integration, which the shared Homebrew
+    /// drift pre-flight check treats as "not applicable" rather than an error.
+    fn get_homebrew_options_installable(
+        &self,
+        _op_ctx: &OperationContext,
+        _platform_args: &Self::PlatformArgs,
+    ) -> Option<Installable> {
+        None
+    }
+
+    /// The profile symlink to list generations against for the `--json` operation summary
+    /// (e.g. `/nix/var/nix/profiles/system` for NixOS), distinct from
+    /// [`Self::get_current_profile_path`] which points at the *active* system rather than the
+    /// profile's generation history. Returns `None` on platforms that don't track generations
+    /// this way, which is treated as "generation number unavailable" rather than an error.
+    fn get_generation_profile_path(
+        &self,
+        _op_ctx: &OperationContext,
+        _platform_args: &Self::PlatformArgs,
+    ) -> Option<PathBuf> {
+        None
+    }
+
+    /// The flake installable pointing at this configuration's `sops.secrets` option, for
+    /// platforms whose modules support sops-nix. Returns `None` on platforms without sops-nix
+    /// integration wired up here, which the shared sops secrets pre-flight check treats as "not
+    /// applicable" rather than an error.
+    fn get_sops_secrets_installable(
+        &self,
+        _op_ctx: &OperationContext,
+        _platform_args: &Self::PlatformArgs,
+    ) -> Option<Installable> {
+        None
+    }
+
+    /// The flake installable pointing at this configuration's `age` option (agenix), for
+    /// platforms whose modules support agenix. Returns `None` on platforms without agenix
+    /// integration wired up here, which the shared agenix key availability check treats as "not
+    /// applicable" rather than an error.
+    fn get_agenix_secrets_installable(
+        &self,
+        _op_ctx: &OperationContext,
+        _platform_args: &Self::PlatformArgs,
+    ) -> Option<Installable> {
+        None
+    }
+
+    /// The `user@host` to build the configuration on instead of locally (`--build-host`), for
+    /// platforms that support offloading evaluation/build to a remote machine over SSH. Returns
+    /// `None` on platforms without remote-build support wired up here, which the shared build
+    /// step in [`crate::workflow_executor`] treats as "build locally" (the default).
+    fn get_build_host(&self, _op_ctx: &OperationContext, _platform_args: &Self::PlatformArgs) -> Option<String> {
+        None
+    }
+
+    /// The `user@host` to activate the configuration on instead of locally (`--target-host`).
+    /// Returns `None` on platforms without remote activation support wired up here. Used by the
+    /// shared build step to decide whether a remotely-built closure should be copied straight to
+    /// the target host instead of back to the local machine.
+    fn get_target_host(&self, _op_ctx: &OperationContext, _platform_args: &Self::PlatformArgs) -> Option<String> {
+        None
+    }
+
+    /// Adjusts the `(current, new)` paths the shared diff step (step 6 of
+    /// [`crate::workflow_executor::execute_rebuild_workflow`]) compares, for platforms where a
+    /// specialisation changes what "current" and "new" actually mean. Given the base current
+    /// profile and the base newly-built profile, returns the paths to diff. Defaults to diffing
+    /// the base profiles unchanged, which is correct for platforms without specialisations.
+    fn get_diff_target_paths(
+        &self,
+        _op_ctx: &OperationContext,
+        _platform_args: &Self::PlatformArgs,
+        current_profile: &Path,
+        built_profile_path: &Path,
+    ) -> (PathBuf, PathBuf) {
+        (current_profile.to_path_buf(), built_profile_path.to_path_buf())
+    }
 }
 
 
@@ -27,9 +27,9 @@ pub struct CommonRebuildArgs {
     /// Perform a dry run without making changes
     pub dry_run: bool,       // Renamed from 'dry'
     
-    /// Ask for confirmation before applying changes
-    pub ask_confirmation: bool, // Renamed from 'ask'
-    
+    /// Stages to ask for confirmation before (empty if `--no-ask` was passed)
+    pub confirm_stages: Vec<crate::interface::ConfirmStage>,
+
     /// Skip using nom for build output formatting
     pub no_nom: bool,
     
@@ -41,4 +41,17 @@ pub struct CommonRebuildArgs {
     
     /// Extra arguments to pass to nix build
     pub extra_build_args: Vec<OsString>,
+
+    /// Keep building other derivations after one fails, then summarize failures
+    pub keep_going: bool,
+
+    /// Print a machine-readable JSON summary of the completed operation
+    pub json: bool,
+
+    /// Print the ordered execution plan, then run the workflow as a dry run that prints each
+    /// stage's exact command
+    pub plan: bool,
+
+    /// Don't collapse repeated diagnostics into a single grouped entry
+    pub no_group: bool,
 }
\ No newline at end of file